@@ -9,6 +9,41 @@
 #include <stdlib.h>
 
 
+/**
+ * The version of the binary trace format, bumped on every incompatible change.
+ */
+#define FORMAT_VERSION 2
+
+/**
+ * How the traces of every rank are brought together to be merged at `MPI_Finalize`.
+ */
+enum Aggregation
+{
+    /**
+     * Every rank writes its trace file, which the merging rank reads back.
+     */
+    ViaFilesystem,
+    /**
+     * The traces of every rank are gathered on the merging rank.
+     */
+    ViaGather,
+    /**
+     * The traces are passed up a binary tree of ranks rooted at the merging rank.
+     */
+    ViaTree,
+};
+typedef int8_t Aggregation;
+
+/**
+ * The point of the run at which the clocks of the ranks are synchronized.
+ */
+enum ClockSyncPoint
+{
+    AtInit,
+    AtFinalize,
+};
+typedef int8_t ClockSyncPoint;
+
 enum MpiCallType
 {
     Init,
@@ -26,6 +61,33 @@ enum MpiCallType
     Igather,
     Ireduce,
     Iscatter,
+    Waitall,
+    Waitany,
+    Waitsome,
+    Testall,
+    Testany,
+    Testsome,
+    Bcast,
+    Reduce,
+    Allreduce,
+    Gather,
+    Scatter,
+    Allgather,
+    Alltoall,
+    Iallreduce,
+    Iallgather,
+    Ialltoall,
+    IreduceScatter,
+    Iscan,
+    Iexscan,
+    Gatherv,
+    Scatterv,
+    Allgatherv,
+    Alltoallv,
+    Igatherv,
+    Iscatterv,
+    Iallgatherv,
+    Ialltoallv,
 };
 typedef int8_t MpiCallType;
 
@@ -48,10 +110,10 @@ enum MpiOp
 };
 typedef int8_t MpiOp;
 
-typedef double Usecs;
-
 typedef uint64_t Tsc;
 
+typedef double Usecs;
+
 typedef int32_t MpiRank;
 
 typedef int32_t MpiComm;
@@ -79,6 +141,239 @@ typedef struct MpiCall
     MpiCallType kind;
 } MpiCall;
 
+/**
+ * The arrays of an MPI call that handles a variable number of requests or of peers, such as
+ * `MPI_Waitall` or `MPI_Alltoallv`, which do not fit in an `MpiCall`.
+ *
+ * The arrays are borrowed from the interposition library for the duration of the call to
+ * `register_mpi_call_arrays`.
+ */
+typedef struct MpiCallArrays
+{
+    /**
+     * The identifiers of the requests passed to the call.
+     */
+    const MpiReq *reqs;
+    size_t nb_reqs;
+    /**
+     * The indices, in `reqs`, of the requests that completed during the call.
+     */
+    const int32_t *indices;
+    size_t nb_indices;
+    /**
+     * The number of bytes sent to each rank of the communicator, indexed by rank.
+     */
+    const uint32_t *peer_bytes_s;
+    size_t nb_peers_s;
+    /**
+     * The number of bytes received from each rank of the communicator, indexed by rank.
+     */
+    const uint32_t *peer_bytes_r;
+    size_t nb_peers_r;
+} MpiCallArrays;
+
+/**
+ * Stores the offset of the TSC of the current rank to the TSC of rank 0, measured by ping-pongs
+ * at `MPI_Init` or `MPI_Finalize`.
+ *
+ * `tsc` is the local TSC halfway through the ping-pong with the shortest round-trip, which lasted
+ * `round_trip` cycles. At `MPI_Finalize`, this must be called before the call itself is
+ * registered, as the trace of the rank is written when it is.
+ */
+void register_clock_sync(ClockSyncPoint point,
+                         Tsc tsc,
+                         int64_t offset,
+                         Tsc round_trip);
+
+/**
+ * Sets the date, in seconds since the epoch, at which rank 0 initialized MPI, from which the
+ * directory of the run is named unless the configuration or the batch scheduler names it.
+ *
+ * This must be called by the interposition library with the same date on every rank, before
+ * `MPI_Init`/`MPI_Init_thread` is registered.
+ */
+void register_run_start(Usecs time);
+
+/**
+ * Completes the metadata of the trace with information about the MPI library.
+ *
+ * This must be called by the interposition library right after `MPI_Init`/`MPI_Init_thread` has
+ * been registered.
+ *
+ * # Safety
+ *
+ * `library_version` must either be null or point to a valid null-terminated string, such as the
+ * one returned by `MPI_Get_library_version`.
+ */
+void register_mpi_info(int32_t world_size,
+                       const char *library_version);
+
 void register_mpi_call(struct MpiCall mpi_call);
 
+/**
+ * Registers an MPI call that handles a variable number of requests or of peers, such as
+ * `MPI_Waitall` or `MPI_Alltoallv`, along with its arrays.
+ *
+ * # Safety
+ *
+ * Each array of `arrays` must either be null or point to as many initialized values as its
+ * length, and must not be modified until this function returns.
+ */
+void register_mpi_call_arrays(struct MpiCall mpi_call,
+                              struct MpiCallArrays arrays);
+
+/**
+ * Writes the buffered events before the MPI library aborts the job.
+ *
+ * This must be called by the interposition library when `MPI_Abort` is called, before the call
+ * is forwarded to the MPI library.
+ */
+void register_abort(int32_t error_code);
+
+/**
+ * Pauses tracing: MPI calls are not recorded until `interpol_resume` is called.
+ *
+ * `MPI_Pcontrol(0)` has the same effect. `MPI_Init`, `MPI_Init_thread` and `MPI_Finalize` are
+ * recorded even while tracing is paused.
+ */
+void interpol_pause(void);
+
+/**
+ * Resumes tracing after a call to `interpol_pause`.
+ *
+ * `MPI_Pcontrol` with a non-zero level has the same effect.
+ */
+void interpol_resume(void);
+
+/**
+ * Enters a user-defined region on the calling thread, such as a solver iteration or a halo
+ * exchange.
+ *
+ * Regions can be nested, and the MPI calls made by the thread until the matching
+ * `interpol_region_end` are tagged with the identifier of the innermost region that encloses
+ * them. A region is identified by its name, so it has the same identifier on every rank. Only
+ * regions entered after `MPI_Init` are recorded.
+ *
+ * # Safety
+ *
+ * `name` must either be null, in which case the call is ignored, or point to a valid
+ * null-terminated string.
+ */
+void interpol_region_begin(const char *name);
+
+/**
+ * Leaves a region entered with `interpol_region_begin` on the calling thread.
+ *
+ * Regions nested in this one that were not left yet are left along with it. Leaving a region
+ * that was not entered has no effect.
+ *
+ * # Safety
+ *
+ * `name` must either be null, in which case the call is ignored, or point to a valid
+ * null-terminated string.
+ */
+void interpol_region_end(const char *name);
+
+/**
+ * Merges the traces of every rank into a single trace, sorted by TSC, at `MPI_Finalize`.
+ *
+ * Files that cannot be read are reported, and the ranks they belong to are left out of the
+ * merged trace rather than aborting the whole job.
+ */
 void sort_all_traces(void);
+
+/**
+ * Returns the names of the files written by the current rank in the directory of the run, one
+ * per line, in a buffer of `*len` bytes to be freed with `interpol_free_buffer`.
+ *
+ * This must be called by the interposition library after `MPI_Finalize` has been registered, so
+ * that the files of every rank can be gathered on the merging rank and listed in the manifest of
+ * the run (see `interpol_write_manifest`).
+ *
+ * # Safety
+ *
+ * `len` must be valid for writes.
+ */
+uint8_t *interpol_rank_files(size_t *len);
+
+/**
+ * Writes the manifest of the run, which lists the files of every rank, before the merging rank
+ * merges them with `sort_all_traces`.
+ *
+ * # Safety
+ *
+ * `buffers` and `lens` must point to `count` elements, the files of every rank as returned by
+ * `interpol_rank_files`, indexed by rank. Every buffer must point to the number of bytes given
+ * by its length.
+ */
+void interpol_write_manifest(const uint8_t *const *buffers,
+                             const size_t *lens,
+                             size_t count);
+
+/**
+ * Returns how the traces of every rank must be aggregated at `MPI_Finalize`.
+ *
+ * Traces that are not merged, or whose configuration is invalid, are written through the
+ * filesystem (in which case `sort_all_traces` does nothing).
+ */
+Aggregation interpol_aggregation(void);
+
+/**
+ * Returns the rank that merges the traces of every rank at `MPI_Finalize`.
+ */
+MpiRank interpol_merge_root(void);
+
+/**
+ * Serializes the trace of the current rank, to be aggregated in-band at `MPI_Finalize` (see
+ * `interpol_aggregation`), and returns a buffer of `*len` bytes.
+ *
+ * This must be called by the interposition library after `MPI_Finalize` has been registered. The
+ * buffer is empty if the trace was already written (e.g. because the rank called `MPI_Abort`)
+ * or could not be serialized, and must be freed with `interpol_free_buffer`.
+ *
+ * # Safety
+ *
+ * `len` must be valid for writes.
+ */
+uint8_t *interpol_serialize_rank(size_t *len);
+
+/**
+ * Merges the serialized traces of several ranks, as returned by `interpol_serialize_rank` or by
+ * this function, into a single buffer of `*len` bytes, to be freed with `interpol_free_buffer`.
+ *
+ * # Safety
+ *
+ * `buffers` and `lens` must point to `count` elements, and every buffer must point to the
+ * number of bytes given by its length. `len` must be valid for writes.
+ */
+uint8_t *interpol_merge_buffers(const uint8_t *const *buffers,
+                                const size_t *lens,
+                                size_t count,
+                                size_t *len);
+
+/**
+ * Merges the serialized traces of every rank into a single trace, sorted by TSC, written by the
+ * merging rank (see `interpol_merge_root`) once they have been aggregated in-band.
+ *
+ * Traces that cannot be read are reported, and the ranks they belong to are left out of the
+ * merged trace.
+ *
+ * # Safety
+ *
+ * `buffers` and `lens` must point to `count` elements, and every buffer must point to the
+ * number of bytes given by its length.
+ */
+void interpol_write_merged(const uint8_t *const *buffers,
+                           const size_t *lens,
+                           size_t count);
+
+/**
+ * Frees a buffer returned by `interpol_serialize_rank` or `interpol_merge_buffers`.
+ *
+ * # Safety
+ *
+ * `buffer` and `len` must have been returned by one of these functions, and the buffer must not
+ * be used afterwards.
+ */
+void interpol_free_buffer(uint8_t *buffer,
+                          size_t len);
@@ -0,0 +1,193 @@
+//! The `interpol` command-line tool, which merges the traces of a run offline, e.g. when the merge
+//! at `MPI_Finalize` was disabled or did not complete.
+
+use interpol_rs::config::{Config, OutputFormat};
+use interpol_rs::{interpol, merge};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: interpol merge <DIR> [OPTIONS]
+
+Merges the trace files of every rank in DIR, the directory of a run, into a single trace sorted
+by TSC. Only the files listed in the manifest of the run are merged, if it has one.
+
+Options:
+    -o, --output <FILE>         Path of the merged trace [default: DIR/<PREFIX>_traces.<EXT>]
+    -f, --format <FORMAT>       Format of the merged trace, `json` or `binary` [default: json]
+    -p, --pretty                Pretty-print the merged JSON trace
+        --prefix <PREFIX>       Prefix of the trace files [default: interpol]
+        --ns-timestamps         Add nanosecond timestamps to the events of the JSON trace
+    -h, --help                  Print this help";
+
+/// The options of the `merge` subcommand.
+#[derive(Debug, PartialEq)]
+struct MergeArgs {
+    dir: PathBuf,
+    output: Option<PathBuf>,
+    format: Option<OutputFormat>,
+    pretty: bool,
+    prefix: Option<String>,
+    ns_timestamps: bool,
+}
+
+/// The command given on the command line.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Merge(MergeArgs),
+    Help,
+}
+
+/// Parses the arguments of the tool, without the name of the program.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    match args.next().as_deref() {
+        Some("merge") => {}
+        Some("-h" | "--help") => return Ok(Command::Help),
+        Some(command) => return Err(format!("unknown command `{command}`")),
+        None => return Err(String::from("missing command")),
+    }
+
+    let mut dir = None;
+    let mut merge = MergeArgs {
+        dir: PathBuf::new(),
+        output: None,
+        format: None,
+        pretty: false,
+        prefix: None,
+        ns_timestamps: false,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for `{name}`"))
+        };
+        match arg.as_str() {
+            "-o" | "--output" => merge.output = Some(PathBuf::from(value(&arg)?)),
+            "-f" | "--format" => {
+                let format = value(&arg)?;
+                merge.format = Some(
+                    OutputFormat::parse(&format)
+                        .map_err(|e| format!("invalid format `{format}`: {e}"))?,
+                );
+            }
+            "-p" | "--pretty" => merge.pretty = true,
+            "--prefix" => merge.prefix = Some(value(&arg)?),
+            "--ns-timestamps" => merge.ns_timestamps = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ if dir.is_none() => dir = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+
+    merge.dir = dir.ok_or_else(|| String::from("missing the directory of the run"))?;
+    Ok(Command::Merge(merge))
+}
+
+/// Builds the configuration with which the traces in `args.dir` are merged.
+///
+/// Settings that are not given on the command line are taken from the configuration file and the
+/// environment, as when tracing, except for the directory of the run.
+fn merge_config(args: &MergeArgs) -> Result<Config, String> {
+    let mut config = Config::from_env().map_err(|e| e.to_string())?;
+    config.output_dir = args.dir.clone();
+    config.run_id = None;
+    if let Some(prefix) = &args.prefix {
+        config.file_prefix = prefix.clone();
+    }
+    if let Some(format) = args.format {
+        config.format = format;
+    }
+    config.pretty |= args.pretty;
+    config.ns_timestamps |= args.ns_timestamps;
+    Ok(config)
+}
+
+fn merge(args: &MergeArgs) -> Result<(), String> {
+    let config = merge_config(args)?;
+    let output = args.output.clone().unwrap_or_else(|| config.merged_file());
+
+    println!("[interpol]: opening the traces in `{}`", args.dir.display());
+    let traces = merge::open_traces(&config).map_err(|e| e.to_string())?;
+    interpol::merge_traces(traces, &config, &output).map_err(|e| e.to_string())?;
+    println!("[interpol]: wrote `{}`", output.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Merge(args)) => match merge(&args) {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("[interpol]: {e}");
+                ExitCode::FAILURE
+            }
+        },
+        Err(e) => {
+            eprintln!("[interpol]: {e}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_merge_options() {
+        assert_eq!(
+            args(&[
+                "merge",
+                "traces/1234.0",
+                "-o",
+                "out.bin",
+                "--format",
+                "binary"
+            ]),
+            Ok(Command::Merge(MergeArgs {
+                dir: PathBuf::from("traces/1234.0"),
+                output: Some(PathBuf::from("out.bin")),
+                format: Some(OutputFormat::Binary),
+                pretty: false,
+                prefix: None,
+                ns_timestamps: false,
+            }))
+        );
+        assert_eq!(
+            args(&["merge", "--pretty", "--prefix", "run", "traces"]),
+            Ok(Command::Merge(MergeArgs {
+                dir: PathBuf::from("traces"),
+                output: None,
+                format: None,
+                pretty: true,
+                prefix: Some(String::from("run")),
+                ns_timestamps: false,
+            }))
+        );
+        assert_eq!(args(&["--help"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        for invalid in [
+            &[][..],
+            &["concat", "traces"],
+            &["merge"],
+            &["merge", "traces", "other"],
+            &["merge", "traces", "--format", "xml"],
+            &["merge", "traces", "--output"],
+            &["merge", "traces", "--colour"],
+        ] {
+            assert!(args(invalid).is_err(), "accepted {invalid:?}");
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use crate::metadata::Metadata;
+use crate::mpi_events::MpiEvent;
+use crate::types::MpiRank;
+use crate::{InterpolError, InterpolErrorKind};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+
+/// The magic number at the start of every binary trace file.
+pub const MAGIC: [u8; 8] = *b"INTERPOL";
+
+/// The version of the binary trace format, bumped on every incompatible change.
+pub const FORMAT_VERSION: u16 = 2;
+
+/// The metadata stored in the header of a binary trace file.
+///
+/// The header is encoded in JSON, so that new fields can be added without breaking the layout of
+/// the event records that follow it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TraceHeader {
+    /// The version of `interpol-rs` that wrote the trace.
+    pub interpol_version: String,
+    /// The rank that recorded the events, or `None` for a merged trace.
+    pub rank: Option<MpiRank>,
+    /// The metadata of the run, if it was collected.
+    pub metadata: Option<Metadata>,
+}
+
+impl TraceHeader {
+    /// Creates a new `TraceHeader` for the events of a rank, or for a merged trace.
+    pub fn new(rank: Option<MpiRank>, metadata: Option<Metadata>) -> Self {
+        Self {
+            interpol_version: env!("CARGO_PKG_VERSION").to_string(),
+            rank,
+            metadata,
+        }
+    }
+}
+
+/// Writes a binary trace to `writer`.
+///
+/// The layout of the file is the following:
+/// - the 8 bytes magic number `INTERPOL`;
+/// - the format version, as a little-endian `u16`;
+/// - the length of the header, as a little-endian `u32`, followed by the JSON-encoded header;
+/// - one record per event, made of a one byte record kind followed by the little-endian encoding
+///   of the event's fields. Optional fields are prefixed by a one byte tag, and strings (such as
+///   the names of regions) by their length as a little-endian `u64`.
+pub fn write_trace<W: Write>(
+    writer: W,
+    header: &TraceHeader,
+    events: &[MpiEvent],
+) -> Result<(), InterpolError> {
+    let mut writer = BufWriter::new(writer);
+    write_header(&mut writer, &MAGIC, header)?;
+    for event in events {
+        event
+            .write_record(&mut writer)
+            .map_err(|e| from_bincode(*e, InterpolErrorKind::Serialization))?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a binary trace previously written with `write_trace` from `reader`.
+///
+/// Files that are not binary traces, that were written with another version of the format, or
+/// that are truncated are reported as `Deserialization` errors.
+pub fn read_trace<R: Read>(reader: R) -> Result<(TraceHeader, Vec<MpiEvent>), InterpolError> {
+    let (header, events) = TraceReader::new(reader)?;
+    Ok((header, events.collect::<Result<_, _>>()?))
+}
+
+/// A binary trace read one event at a time, so that it never has to fit in memory.
+///
+/// The iterator stops after the first error.
+pub struct TraceReader<R> {
+    reader: BufReader<R>,
+    done: bool,
+}
+
+impl<R: Read> TraceReader<R> {
+    /// Reads the header of a binary trace from `reader`, and returns it along with a reader of
+    /// the events that follow it.
+    pub fn new(reader: R) -> Result<(TraceHeader, Self), InterpolError> {
+        let mut reader = BufReader::new(reader);
+        let header = read_header(&mut reader, &MAGIC, "binary trace")?;
+        Ok((
+            header,
+            Self {
+                reader,
+                done: false,
+            },
+        ))
+    }
+}
+
+impl<R: Read> Iterator for TraceReader<R> {
+    type Item = Result<MpiEvent, InterpolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut kind = [0; 1];
+        let event = match self.reader.read(&mut kind) {
+            Ok(0) => None,
+            Ok(_) => Some(
+                MpiEvent::read_record(kind[0], &mut self.reader)
+                    .map_err(|e| from_bincode(*e, InterpolErrorKind::Deserialization)),
+            ),
+            Err(e) => Some(Err(e.into())),
+        };
+        self.done = !matches!(event, Some(Ok(_)));
+        event
+    }
+}
+
+/// A binary trace written one event at a time, whose header can be rewritten once every event
+/// has been written.
+pub struct TraceWriter<W: Write + Seek> {
+    writer: BufWriter<W>,
+    header_len: usize,
+}
+
+impl<W: Write + Seek> TraceWriter<W> {
+    /// Writes the header of a binary trace to `writer`.
+    ///
+    /// The final header, given to `finish`, must not be longer once encoded than this one.
+    pub fn new(writer: W, header: &TraceHeader) -> Result<Self, InterpolError> {
+        let mut writer = BufWriter::new(writer);
+        let header = encode_header(header)?;
+        write_encoded_header(&mut writer, &MAGIC, &header)?;
+        Ok(Self {
+            writer,
+            header_len: header.len(),
+        })
+    }
+
+    /// Appends an event to the trace.
+    pub fn write_event(&mut self, event: &MpiEvent) -> Result<(), InterpolError> {
+        event
+            .write_record(&mut self.writer)
+            .map_err(|e| from_bincode(*e, InterpolErrorKind::Serialization))
+    }
+
+    /// Overwrites the header of the trace with `header`, padded with spaces to the length of the
+    /// header it was created with, and flushes the trace.
+    pub fn finish(mut self, header: &TraceHeader) -> Result<W, InterpolError> {
+        let mut header = encode_header(header)?;
+        if header.len() > self.header_len {
+            return Err(InterpolError::new(
+                InterpolErrorKind::Serialization,
+                "the final header is longer than the initial one",
+            ));
+        }
+        header.resize(self.header_len, b' ');
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        write_encoded_header(&mut self.writer, &MAGIC, &header)?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer
+            .into_inner()
+            .map_err(|e| InterpolError::from(e.into_error()))
+    }
+}
+
+/// Writes the magic number of a file, the format version and the JSON-encoded header.
+pub(crate) fn write_header<W: Write>(
+    writer: &mut W,
+    magic: &[u8; 8],
+    header: &TraceHeader,
+) -> Result<(), InterpolError> {
+    write_encoded_header(writer, magic, &encode_header(header)?)
+}
+
+fn encode_header(header: &TraceHeader) -> Result<Vec<u8>, InterpolError> {
+    serde_json::to_vec(header)
+        .map_err(|e| InterpolError::new(InterpolErrorKind::Serialization, e.to_string()))
+}
+
+fn write_encoded_header<W: Write>(
+    writer: &mut W,
+    magic: &[u8; 8],
+    header: &[u8],
+) -> Result<(), InterpolError> {
+    writer.write_all(magic)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&(header.len() as u32).to_le_bytes())?;
+    writer.write_all(header)?;
+    Ok(())
+}
+
+/// Reads the header written with `write_header`, checking the magic number and the format
+/// version. `file_kind` names the kind of file in errors.
+pub(crate) fn read_header<R: Read>(
+    reader: &mut R,
+    magic: &[u8; 8],
+    file_kind: &str,
+) -> Result<TraceHeader, InterpolError> {
+    let mut read_magic = [0; 8];
+    reader.read_exact(&mut read_magic).map_err(read_error)?;
+    if read_magic != *magic {
+        return Err(invalid_data(&format!("not an interpol {file_kind}")));
+    }
+
+    let mut version = [0; 2];
+    reader.read_exact(&mut version).map_err(read_error)?;
+    let version = u16::from_le_bytes(version);
+    if version != FORMAT_VERSION {
+        return Err(invalid_data(&format!(
+            "unsupported {file_kind} format version {version} (expected {FORMAT_VERSION})"
+        )));
+    }
+
+    let mut len = [0; 4];
+    reader.read_exact(&mut len).map_err(read_error)?;
+    let mut header = vec![0; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut header).map_err(read_error)?;
+    serde_json::from_slice(&header).map_err(|e| invalid_data(&e.to_string()))
+}
+
+pub(crate) fn invalid_data(reason: &str) -> InterpolError {
+    InterpolError::new(InterpolErrorKind::Deserialization, reason)
+}
+
+/// Converts an error raised while reading a trace, reporting its unexpected end as a truncated
+/// trace.
+pub(crate) fn read_error(error: io::Error) -> InterpolError {
+    match error.kind() {
+        io::ErrorKind::UnexpectedEof => invalid_data("truncated trace"),
+        _ => error.into(),
+    }
+}
+
+/// Converts an error raised while encoding or decoding a record into an error of the given kind,
+/// unless it was raised by the underlying reader or writer.
+pub(crate) fn from_bincode(error: bincode::ErrorKind, kind: InterpolErrorKind) -> InterpolError {
+    match error {
+        bincode::ErrorKind::Io(error) if kind == InterpolErrorKind::Deserialization => {
+            read_error(error)
+        }
+        bincode::ErrorKind::Io(error) => error.into(),
+        error => InterpolError::new(kind, error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpi_events::{
+        collectives::mpi_ireduce::MpiIreduce, management::mpi_init::MpiInit,
+        point_to_point::mpi_isend::MpiIsend, synchronization::mpi_test::MpiTest,
+    };
+    use crate::types::MpiOp;
+
+    fn events() -> Vec<MpiEvent> {
+        vec![
+            MpiInit::new(0, 512, 0.1).into(),
+            MpiIsend::new(0, 1, 8, 0, 7, 42, 1024, 2048).into(),
+            MpiIreduce::new(0, 1, 8, MpiOp::Sum, 0, 8, 4096, 2048).into(),
+            MpiTest::new(0, 7, true, 8192, 128).into(),
+        ]
+    }
+
+    #[test]
+    fn round_trips_to_json() {
+        let events = events();
+        let mut bytes = Vec::new();
+        write_trace(&mut bytes, &TraceHeader::new(Some(0), None), &events)
+            .expect("failed to write binary trace");
+        assert!(bytes.starts_with(&MAGIC));
+
+        let (header, read) = read_trace(bytes.as_slice()).expect("failed to read binary trace");
+        assert_eq!(header, TraceHeader::new(Some(0), None));
+        assert_eq!(
+            serde_json::to_string(&events).expect("failed to serialize events"),
+            serde_json::to_string(&read).expect("failed to serialize events"),
+        );
+    }
+
+    #[test]
+    fn records_have_fixed_size() {
+        let mut one = Vec::new();
+        let mut two = Vec::new();
+        let test = MpiEvent::from(MpiTest::new(0, 7, true, 8192, 128));
+        write_trace(
+            &mut one,
+            &TraceHeader::new(None, None),
+            std::slice::from_ref(&test),
+        )
+        .expect("failed to write binary trace");
+        write_trace(
+            &mut two,
+            &TraceHeader::new(None, None),
+            &[test.clone(), test],
+        )
+        .expect("failed to write binary trace");
+
+        // rank (4) + req (4) + finished (1) + tsc (8) + duration (8) + untagged region (1), plus
+        // the record kind
+        assert_eq!(two.len() - one.len(), 1 + 26);
+    }
+
+    #[test]
+    fn rejects_bad_magic_and_version() {
+        let mut bytes = Vec::new();
+        write_trace(&mut bytes, &TraceHeader::new(None, None), &events())
+            .expect("failed to write binary trace");
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(read_trace(bad_magic.as_slice()).is_err());
+
+        let mut bad_version = bytes;
+        bad_version[MAGIC.len()] = 0xff;
+        assert!(read_trace(bad_version.as_slice()).is_err());
+    }
+
+    #[test]
+    fn rewrites_header() {
+        let mut placeholder = TraceHeader::new(None, None);
+        placeholder.interpol_version = String::from("placeholder");
+        let mut writer = TraceWriter::new(io::Cursor::new(Vec::new()), &placeholder)
+            .expect("failed to write binary trace");
+        for event in events() {
+            writer.write_event(&event).expect("failed to write event");
+        }
+        let bytes = writer
+            .finish(&TraceHeader::new(None, None))
+            .expect("failed to finish binary trace")
+            .into_inner();
+
+        let (header, read) = read_trace(bytes.as_slice()).expect("failed to read binary trace");
+        assert_eq!(header, TraceHeader::new(None, None));
+        assert_eq!(read.len(), events().len());
+    }
+
+    #[test]
+    fn rejects_truncated_traces() {
+        let mut bytes = Vec::new();
+        write_trace(&mut bytes, &TraceHeader::new(Some(0), None), &events())
+            .expect("failed to write binary trace");
+        bytes.truncate(bytes.len() - 3);
+
+        let error = read_trace(bytes.as_slice()).expect_err("read a truncated trace");
+        assert_eq!(error.kind(), InterpolErrorKind::Deserialization);
+        assert_eq!(error.to_string(), "Deserialization error: truncated trace");
+    }
+}
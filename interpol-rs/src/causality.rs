@@ -0,0 +1,529 @@
+use crate::interpol::Register;
+use crate::mpi_events::MpiEvent;
+use crate::types::{MpiComm, MpiRank, MpiReq, MpiTag, Tsc};
+use crate::InterpolError;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+/// The channel through which a point-to-point message goes: its sender, receiver, communicator
+/// and tag.
+type Channel = (MpiRank, MpiRank, MpiComm, MpiTag);
+
+/// A summary of the corrections applied to a trace to preserve causality.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CausalityReport {
+    /// The number of messages whose send and receive were matched.
+    pub matched_messages: usize,
+    /// The number of messages whose receive completed before their send started.
+    pub violations: usize,
+    /// The number of events whose TSC was shifted forward.
+    pub shifted_events: usize,
+    /// The largest shift applied to an event, in cycles.
+    pub max_shift: Tsc,
+    /// The sum of the shifts applied to every event, in cycles.
+    pub total_shift: u64,
+}
+
+/// Returns the channel of a send event.
+fn send_channel(event: &MpiEvent) -> Option<Channel> {
+    match event {
+        MpiEvent::MpiSend(send) => Some((
+            send.current_rank(),
+            send.partner_rank(),
+            send.comm(),
+            send.tag(),
+        )),
+        MpiEvent::MpiIsend(isend) => Some((
+            isend.current_rank(),
+            isend.partner_rank(),
+            isend.comm(),
+            isend.tag(),
+        )),
+        _ => None,
+    }
+}
+
+/// Returns the channel of a receive event, unless it was posted with `MPI_ANY_SOURCE` or
+/// `MPI_ANY_TAG`, as the message it received cannot be known from the trace.
+fn recv_channel(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    comm: MpiComm,
+    tag: MpiTag,
+) -> Option<Channel> {
+    (partner_rank >= 0 && tag >= 0).then_some((partner_rank, current_rank, comm, tag))
+}
+
+/// A point-to-point message: its channel, and the number of messages that went through the
+/// channel before it.
+///
+/// Messages going through the same channel are non-overtaking, so the n-th send is matched with
+/// the n-th receive posted on the channel.
+type Message = (Channel, u64);
+
+/// The events of a rank, replayed in order.
+struct Rank<I> {
+    events: I,
+    /// The next event of the rank, with the messages that must have been received when it
+    /// completes: those of an `MpiRecv`, or of the `MpiIrecv` completed by an `MpiWait` or a
+    /// finished `MpiTest` (or one of their variants).
+    head: Option<(MpiEvent, Vec<Message>)>,
+    /// The shift applied to the events of the rank so far.
+    shift: Tsc,
+    /// The messages received by the `MpiIrecv` that did not complete yet, by request.
+    pending: HashMap<MpiReq, Vec<Message>>,
+    /// Whether the next event must be processed even if its messages have not been sent, which
+    /// only happens if mismatched messages lead to a cycle of waiting ranks.
+    forced: bool,
+}
+
+/// An event released by `CausalMerge` once no earlier event can follow it.
+struct Buffered {
+    tsc: Tsc,
+    /// The order in which events were processed, which breaks ties between equal TSC.
+    order: u64,
+    event: MpiEvent,
+}
+
+impl PartialEq for Buffered {
+    fn eq(&self, other: &Self) -> bool {
+        (self.tsc, self.order) == (other.tsc, other.order)
+    }
+}
+
+impl Eq for Buffered {}
+
+impl PartialOrd for Buffered {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Buffered {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.tsc, self.order).cmp(&(other.tsc, other.order))
+    }
+}
+
+/// A k-way merge of the events of every rank, sorted by TSC, that optionally shifts events
+/// forward so that no message is received before it was sent.
+///
+/// The events of each rank must be sorted by TSC, and in a common timebase if they are corrected
+/// (see `clock::GlobalTimebase`). Point-to-point messages are matched on their communicator, tag
+/// and order, and the events of every rank are replayed in order with a forward controlled logical
+/// clock: when a receive completes before the start of its send, the receiving event and every
+/// later event of its rank are shifted by the same amount, so that the intervals between the
+/// events of a rank are preserved. Durations are left untouched.
+///
+/// As communicators are identified by their local handle, only messages on communicators whose
+/// handle is the same on every rank (such as `MPI_COMM_WORLD`) are reliably matched.
+///
+/// Only the next event of every rank is read ahead, and processed events are buffered until no
+/// rank can produce an earlier one, which only takes long when a rank waits for a message sent
+/// much later. Errors of a rank are yielded as they are read, and end its events.
+pub struct CausalMerge<I> {
+    ranks: Vec<Rank<I>>,
+    correct: bool,
+    /// The ranks whose next event can be processed, by shifted TSC of the event.
+    ready: BinaryHeap<Reverse<(Tsc, usize)>>,
+    /// The ranks waiting for a message to be sent.
+    waiting: HashMap<Message, Vec<usize>>,
+    /// The start of the messages sent whose receive has not been processed yet.
+    sends: HashMap<Message, Tsc>,
+    /// The messages whose receive was forced before their send was processed.
+    forced_receives: HashSet<Message>,
+    /// The number of messages sent and received on each channel so far.
+    sent: HashMap<Channel, u64>,
+    received: HashMap<Channel, u64>,
+    processed: BinaryHeap<Reverse<Buffered>>,
+    order: u64,
+    errors: VecDeque<InterpolError>,
+    report: CausalityReport,
+}
+
+impl<I: Iterator<Item = Result<MpiEvent, InterpolError>>> CausalMerge<I> {
+    /// Merges the events of every rank, correcting them if `correct` is set.
+    pub fn new(ranks: impl IntoIterator<Item = I>, correct: bool) -> Self {
+        let mut merge = Self {
+            ranks: ranks
+                .into_iter()
+                .map(|events| Rank {
+                    events,
+                    head: None,
+                    shift: 0,
+                    pending: HashMap::new(),
+                    forced: false,
+                })
+                .collect(),
+            correct,
+            ready: BinaryHeap::new(),
+            waiting: HashMap::new(),
+            sends: HashMap::new(),
+            forced_receives: HashSet::new(),
+            sent: HashMap::new(),
+            received: HashMap::new(),
+            processed: BinaryHeap::new(),
+            order: 0,
+            errors: VecDeque::new(),
+            report: CausalityReport::default(),
+        };
+        for q in 0..merge.ranks.len() {
+            merge.read_next(q);
+        }
+        merge
+    }
+
+    /// Returns the corrections applied so far, which are complete once every event has been
+    /// yielded.
+    pub fn report(&self) -> &CausalityReport {
+        &self.report
+    }
+
+    /// Reads the next event of a rank, and makes the rank ready.
+    fn read_next(&mut self, q: usize) {
+        let event = match self.ranks[q].events.next() {
+            Some(Ok(event)) => event,
+            Some(Err(e)) => {
+                self.errors.push_back(e);
+                return;
+            }
+            None => return,
+        };
+
+        let messages = if self.correct {
+            self.received_messages(q, &event)
+        } else {
+            Vec::new()
+        };
+        let rank = &mut self.ranks[q];
+        self.ready.push(Reverse((event.tsc() + rank.shift, q)));
+        rank.head = Some((event, messages));
+    }
+
+    /// Returns the messages that must have been received when an event of a rank completes,
+    /// keeping track of the messages of its `MpiIrecv`.
+    fn received_messages(&mut self, q: usize, event: &MpiEvent) -> Vec<Message> {
+        let mut receive = |channel: Option<Channel>| {
+            let channel = channel?;
+            let count = self.received.entry(channel).or_default();
+            *count += 1;
+            Some((channel, *count - 1))
+        };
+        match event {
+            MpiEvent::MpiRecv(recv) => receive(recv_channel(
+                recv.current_rank(),
+                recv.partner_rank(),
+                recv.comm(),
+                recv.tag(),
+            ))
+            .into_iter()
+            .collect(),
+            MpiEvent::MpiIrecv(irecv) => {
+                let channel = recv_channel(
+                    irecv.current_rank(),
+                    irecv.partner_rank(),
+                    irecv.comm(),
+                    irecv.tag(),
+                );
+                if let Some(message) = receive(channel) {
+                    let pending = &mut self.ranks[q].pending;
+                    pending.entry(irecv.req()).or_default().push(message);
+                }
+                Vec::new()
+            }
+            MpiEvent::MpiWait(wait) => self.ranks[q]
+                .pending
+                .remove(&wait.req())
+                .unwrap_or_default(),
+            MpiEvent::MpiTest(test) if test.finished() => self.ranks[q]
+                .pending
+                .remove(&test.req())
+                .unwrap_or_default(),
+            MpiEvent::MpiWaitall(waitall) => self.complete(q, waitall.reqs().iter().copied()),
+            MpiEvent::MpiWaitany(waitany) => self.complete(q, waitany.completed()),
+            MpiEvent::MpiWaitsome(waitsome) => self.complete(q, waitsome.completed()),
+            MpiEvent::MpiTestall(testall) if testall.finished() => {
+                self.complete(q, testall.reqs().iter().copied())
+            }
+            MpiEvent::MpiTestany(testany) => self.complete(q, testany.completed()),
+            MpiEvent::MpiTestsome(testsome) => self.complete(q, testsome.completed()),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Removes the messages of the requests of rank `q` that completed, which must have been
+    /// received once the event that completes them ends.
+    fn complete(&mut self, q: usize, reqs: impl IntoIterator<Item = MpiReq>) -> Vec<Message> {
+        let pending = &mut self.ranks[q].pending;
+        reqs.into_iter()
+            .filter_map(|req| pending.remove(&req))
+            .flatten()
+            .collect()
+    }
+
+    /// Processes the next event of the merge. Returns `false` once every event was processed.
+    fn step(&mut self) -> bool {
+        let q = match self.ready.pop() {
+            Some(Reverse((_, q))) => q,
+            // Every rank left waits for a message: the cycle is broken by forcing the earliest
+            // blocked event, ties broken by rank
+            None => match self.earliest_blocked() {
+                Some(message) => {
+                    let blocked = self.waiting.remove(&message).unwrap_or_default();
+                    for &q in &blocked {
+                        self.ranks[q].forced = true;
+                    }
+                    self.ready
+                        .extend(blocked.into_iter().map(|q| Reverse((0, q))));
+                    return true;
+                }
+                None => return false,
+            },
+        };
+
+        let rank = &mut self.ranks[q];
+        let (mut event, messages) = match rank.head.take() {
+            Some(head) => head,
+            None => return true,
+        };
+        if !rank.forced {
+            if let Some(&message) = messages
+                .iter()
+                .find(|message| !self.sends.contains_key(message))
+            {
+                rank.head = Some((event, messages));
+                self.waiting.entry(message).or_default().push(q);
+                return true;
+            }
+        }
+        rank.forced = false;
+
+        let end = event.tsc() + rank.shift + event.duration();
+        let mut start = None;
+        for message in messages {
+            match self.sends.remove(&message) {
+                Some(send) => {
+                    self.report.matched_messages += 1;
+                    start = start.max(Some(send));
+                }
+                None => {
+                    self.forced_receives.insert(message);
+                }
+            }
+        }
+        if let Some(start) = start {
+            if end < start {
+                self.report.violations += 1;
+                rank.shift += start - end;
+            }
+        }
+        if rank.shift > 0 {
+            self.report.shifted_events += 1;
+            self.report.max_shift = self.report.max_shift.max(rank.shift);
+            self.report.total_shift += rank.shift;
+            let tsc = event.tsc() + rank.shift;
+            event.set_tsc(tsc);
+        }
+
+        if let Some(channel) = send_channel(&event).filter(|_| self.correct) {
+            let count = self.sent.entry(channel).or_default();
+            let message = (channel, *count);
+            *count += 1;
+            if self.forced_receives.remove(&message) {
+                self.report.matched_messages += 1;
+            } else {
+                self.sends.insert(message, event.tsc());
+            }
+            for w in self.waiting.remove(&message).unwrap_or_default() {
+                if let Some((head, _)) = &self.ranks[w].head {
+                    self.ready
+                        .push(Reverse((head.tsc() + self.ranks[w].shift, w)));
+                }
+            }
+        }
+
+        self.processed.push(Reverse(Buffered {
+            tsc: event.tsc(),
+            order: self.order,
+            event,
+        }));
+        self.order += 1;
+        self.read_next(q);
+        true
+    }
+
+    /// Returns the message waited for by the blocked event with the smallest shifted TSC and rank.
+    fn earliest_blocked(&self) -> Option<Message> {
+        self.waiting
+            .iter()
+            .flat_map(|(&message, blocked)| {
+                blocked.iter().filter_map(move |&q| {
+                    let rank = &self.ranks[q];
+                    Some((rank.head.as_ref()?.0.tsc() + rank.shift, q, message))
+                })
+            })
+            .min()
+            .map(|(_, _, message)| message)
+    }
+
+    /// Returns the smallest TSC that an event yet to be processed can have, or `None` if every
+    /// event was processed.
+    fn watermark(&self) -> Option<Tsc> {
+        let ready = self.ready.peek().map(|Reverse((tsc, _))| *tsc);
+        let waiting = self.waiting.values().flatten().filter_map(|&q| {
+            let rank = &self.ranks[q];
+            Some(rank.head.as_ref()?.0.tsc() + rank.shift)
+        });
+        ready.into_iter().chain(waiting).min()
+    }
+}
+
+impl<I: Iterator<Item = Result<MpiEvent, InterpolError>>> Iterator for CausalMerge<I> {
+    type Item = Result<MpiEvent, InterpolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(error) = self.errors.pop_front() {
+                return Some(Err(error));
+            }
+            if let Some(Reverse(next)) = self.processed.peek() {
+                if self
+                    .watermark()
+                    .is_none_or(|watermark| next.tsc <= watermark)
+                {
+                    return self.processed.pop().map(|Reverse(next)| Ok(next.event));
+                }
+            }
+            if !self.step() && self.processed.is_empty() {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpi_events::{
+        point_to_point::{mpi_irecv::MpiIrecv, mpi_isend::MpiIsend, mpi_recv::MpiRecv},
+        synchronization::mpi_wait::MpiWait,
+    };
+    const MPI_COMM_WORLD: i32 = 0;
+
+    /// Merges and corrects the events of every rank.
+    fn correct(ranks: Vec<Vec<MpiEvent>>) -> (Vec<MpiEvent>, CausalityReport) {
+        let mut merge = CausalMerge::new(
+            ranks
+                .into_iter()
+                .map(|events| events.into_iter().map(Ok::<_, InterpolError>)),
+            true,
+        );
+        let events = merge
+            .by_ref()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("failed to merge events");
+        (events, merge.report().clone())
+    }
+
+    #[test]
+    fn keeps_causal_traces() {
+        let send: MpiEvent = MpiIsend::new(0, 1, 8, MPI_COMM_WORLD, 3, 42, 1_000, 100).into();
+        let recv: MpiEvent = MpiRecv::new(1, 0, 8, MPI_COMM_WORLD, 42, 900, 400).into();
+
+        let (events, report) = correct(vec![vec![send.clone()], vec![recv.clone()]]);
+        assert_eq!(report.matched_messages, 1);
+        assert_eq!(report.violations, 0);
+        assert_eq!(events, vec![recv, send]);
+    }
+
+    #[test]
+    fn shifts_receives_after_their_sends() {
+        let (events, report) = correct(vec![
+            vec![
+                MpiIsend::new(0, 1, 8, MPI_COMM_WORLD, 3, 42, 1_000, 100).into(),
+                MpiIsend::new(0, 1, 8, MPI_COMM_WORLD, 4, 42, 1_200, 100).into(),
+            ],
+            vec![
+                MpiIrecv::new(1, 0, 8, MPI_COMM_WORLD, 7, 42, 100, 10).into(),
+                MpiWait::new(1, 7, 200, 50).into(),
+                MpiRecv::new(1, 0, 8, MPI_COMM_WORLD, 42, 300, 50).into(),
+            ],
+        ]);
+        assert_eq!(report.matched_messages, 2);
+        assert_eq!(report.violations, 2);
+        // The `MpiWait` ends at the start of the first send, and the `MpiRecv` at the start of
+        // the second one; the `MpiIrecv` happened before any message was received
+        let tscs: Vec<_> = events
+            .iter()
+            .map(|event| (event.current_rank(), event.tsc()))
+            .collect();
+        assert_eq!(
+            tscs,
+            vec![(1, 100), (1, 950), (0, 1_000), (1, 1_150), (0, 1_200)]
+        );
+        assert_eq!(report.shifted_events, 2);
+        assert_eq!(report.max_shift, 850);
+        assert_eq!(report.total_shift, 750 + 850);
+    }
+
+    #[test]
+    fn ignores_wildcard_receives() {
+        let (events, report) = correct(vec![
+            vec![MpiIsend::new(0, 1, 8, MPI_COMM_WORLD, 3, 42, 1_000, 100).into()],
+            vec![MpiRecv::new(1, -1, 8, MPI_COMM_WORLD, 42, 400, 50).into()],
+        ]);
+        assert_eq!(report, CausalityReport::default());
+        assert_eq!(events[0].tsc(), 400);
+    }
+
+    #[test]
+    fn forces_the_earliest_blocked_receive() {
+        // Both receives wait for a message that is never sent on their channel
+        let (events, report) = correct(vec![
+            vec![MpiRecv::new(0, 1, 8, MPI_COMM_WORLD, 5, 700, 50).into()],
+            vec![MpiRecv::new(1, 0, 8, MPI_COMM_WORLD, 6, 300, 50).into()],
+        ]);
+        assert_eq!(report, CausalityReport::default());
+        let tscs: Vec<_> = events
+            .iter()
+            .map(|event| (event.current_rank(), event.tsc()))
+            .collect();
+        assert_eq!(tscs, vec![(1, 300), (0, 700)]);
+    }
+
+    #[test]
+    fn merges_ranks_in_order() {
+        let ranks: Vec<Vec<Result<MpiEvent, InterpolError>>> = vec![
+            vec![
+                Ok(MpiWait::new(0, 1, 100, 10).into()),
+                Ok(MpiWait::new(0, 2, 300, 10).into()),
+                Err(InterpolError::new(
+                    crate::InterpolErrorKind::Deserialization,
+                    "truncated trace",
+                )),
+            ],
+            vec![
+                Ok(MpiWait::new(1, 1, 200, 10).into()),
+                Ok(MpiWait::new(1, 2, 250, 10).into()),
+                Ok(MpiWait::new(1, 3, 400, 10).into()),
+            ],
+        ];
+
+        let merged: Vec<_> = CausalMerge::new(ranks.into_iter().map(Vec::into_iter), false)
+            .map(|event| event.map(|event| event.tsc()).map_err(|e| e.kind()))
+            .collect();
+        // The error of rank 0 is read ahead, along with the event at 300
+        assert_eq!(
+            merged,
+            vec![
+                Ok(100),
+                Ok(200),
+                Ok(250),
+                Err(crate::InterpolErrorKind::Deserialization),
+                Ok(300),
+                Ok(400),
+            ]
+        );
+    }
+}
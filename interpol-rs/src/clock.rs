@@ -0,0 +1,297 @@
+use crate::metadata::Metadata;
+use crate::mpi_events::MpiEvent;
+use crate::types::{MpiRank, Tsc, Usecs};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The minimum wall-clock time between `MPI_Init` and `MPI_Finalize`, in seconds, for their
+/// TSC/time pairs to be used to calibrate the TSC frequency.
+///
+/// As the time is measured with `gettimeofday`, shorter runs would yield an imprecise frequency.
+const MIN_CALIBRATION_SPAN: Usecs = 1.0;
+
+/// The duration of the calibration loop used for runs too short to be calibrated with their
+/// `MPI_Init`/`MPI_Finalize` pairs.
+const CALIBRATION_LOOP_DURATION: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// A calibrated Time Stamp Counter, used to convert TSC values of a rank to nanoseconds.
+///
+/// The TSC value and wall-clock time measured at `MPI_Init` anchor the TSC to the Unix epoch.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TscClock {
+    /// The value of the TSC at `MPI_Init`.
+    pub init_tsc: Tsc,
+    /// The wall-clock time at `MPI_Init`, in seconds since the Unix epoch.
+    pub init_time: Usecs,
+    /// The frequency of the TSC, in cycles per second.
+    pub frequency: f64,
+}
+
+impl TscClock {
+    /// Calibrates the TSC of the current rank from the TSC/time pairs measured at `MPI_Init` and
+    /// `MPI_Finalize`.
+    ///
+    /// If the run was too short for the pairs to give a precise frequency, the TSC is calibrated
+    /// with a short busy loop instead.
+    pub fn calibrate(
+        init_tsc: Tsc,
+        init_time: Usecs,
+        finalize_tsc: Tsc,
+        finalize_time: Usecs,
+    ) -> Option<Self> {
+        let frequency = from_pairs(init_tsc, init_time, finalize_tsc, finalize_time)
+            .or_else(calibration_loop)?;
+
+        Some(Self {
+            init_tsc,
+            init_time,
+            frequency,
+        })
+    }
+
+    /// Converts a number of TSC cycles to nanoseconds.
+    pub fn cycles_to_ns(&self, cycles: Tsc) -> u64 {
+        (cycles as f64 / self.frequency * 1e9).round() as u64
+    }
+
+    /// Converts a TSC value to a number of nanoseconds since the Unix epoch.
+    pub fn tsc_to_ns(&self, tsc: Tsc) -> u64 {
+        let elapsed = (tsc as f64 - self.init_tsc as f64) / self.frequency;
+        ((self.init_time + elapsed) * 1e9).round().max(0.0) as u64
+    }
+}
+
+/// Computes the frequency of the TSC from two TSC/time pairs, if they are far enough apart.
+fn from_pairs(
+    init_tsc: Tsc,
+    init_time: Usecs,
+    finalize_tsc: Tsc,
+    finalize_time: Usecs,
+) -> Option<f64> {
+    let span = finalize_time - init_time;
+    if span < MIN_CALIBRATION_SPAN || finalize_tsc <= init_tsc {
+        return None;
+    }
+
+    Some((finalize_tsc - init_tsc) as f64 / span)
+}
+
+/// Reads the Time Stamp Counter, for events that are not timed by the interposition library.
+#[cfg(target_arch = "x86_64")]
+pub fn read_tsc() -> Tsc {
+    // SAFETY: `rdtsc` is available on every x86_64 CPU
+    unsafe { std::arch::x86_64::_rdtsc() }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn read_tsc() -> Tsc {
+    0
+}
+
+/// The offset between the TSC of a rank and the TSC of rank 0, measured with ping-pongs.
+///
+/// Only the ping-pong with the shortest round-trip is kept: the TSC of rank 0 is assumed to have
+/// been read halfway through it, so the error on the offset is at most half of `round_trip`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockSync {
+    /// The value of the local TSC halfway through the ping-pong.
+    pub tsc: Tsc,
+    /// The number of cycles to add to the local TSC to get the TSC of rank 0.
+    pub offset: i64,
+    /// The round-trip time of the ping-pong, in local TSC cycles.
+    pub round_trip: Tsc,
+}
+
+impl ClockSync {
+    /// Computes the drift of the local TSC relative to the TSC of rank 0 between this
+    /// synchronization and a later one, in cycles per local cycle.
+    ///
+    /// Returns `None` if the synchronizations are not ordered, in which case no drift can be
+    /// estimated.
+    pub fn drift_to(&self, later: &ClockSync) -> Option<f64> {
+        if later.tsc <= self.tsc {
+            return None;
+        }
+
+        Some((later.offset - self.offset) as f64 / (later.tsc - self.tsc) as f64)
+    }
+
+    /// Translates a local TSC value to the timebase of rank 0, interpolating the offset linearly
+    /// from this synchronization with the given drift.
+    pub fn to_global(&self, tsc: Tsc, drift: f64) -> Tsc {
+        let elapsed = tsc as f64 - self.tsc as f64;
+        let offset = self.offset + (drift * elapsed).round() as i64;
+        tsc.saturating_add_signed(offset)
+    }
+}
+
+/// The translation of the TSC of every rank to the timebase of rank 0.
+///
+/// The offset of each rank is measured at `MPI_Init`, and interpolated linearly across the run
+/// with the drift between the synchronizations at `MPI_Init` and `MPI_Finalize` (if the latter is
+/// missing, e.g. because the run was interrupted, the offset is considered constant). Durations
+/// are kept in local cycles, as the drift over a single call is negligible.
+#[derive(Clone, Debug)]
+pub struct GlobalTimebase {
+    syncs: HashMap<MpiRank, (ClockSync, f64)>,
+}
+
+impl GlobalTimebase {
+    /// Computes the translation of every rank described by `metadata`.
+    ///
+    /// Returns `None` if the events already are in the global timebase, or if a rank has no
+    /// synchronization (e.g. traces recorded by an older version of `interpol-rs`), as shifting
+    /// only some of the ranks would make things worse.
+    pub fn new(metadata: &Metadata) -> Option<Self> {
+        if metadata.global_timebase {
+            return None;
+        }
+
+        let syncs = metadata
+            .ranks
+            .iter()
+            .map(|info| {
+                let init = info.init_sync.clone()?;
+                let drift = info
+                    .finalize_sync
+                    .as_ref()
+                    .and_then(|finalize| init.drift_to(finalize))
+                    .unwrap_or(0.0);
+                Some((info.rank, (init, drift)))
+            })
+            .collect::<Option<_>>()?;
+        Some(Self { syncs })
+    }
+
+    /// Returns whether the events of `rank` can be translated.
+    pub fn covers(&self, rank: MpiRank) -> bool {
+        self.syncs.contains_key(&rank)
+    }
+
+    /// Translates the TSC of an event. Events of ranks that are not covered are left untouched.
+    ///
+    /// The translation is monotonic, so events of a rank sorted by TSC stay sorted.
+    pub fn translate(&self, event: &mut MpiEvent) {
+        if let Some((init, drift)) = self.syncs.get(&event.current_rank()) {
+            event.set_tsc(init.to_global(event.tsc(), *drift));
+        }
+    }
+
+    /// Records the drift used for each rank in `metadata`, and marks it as describing events in
+    /// the global timebase.
+    pub fn record(&self, metadata: &mut Metadata) {
+        for info in metadata.ranks.iter_mut() {
+            info.drift = self.syncs.get(&info.rank).map(|(_, drift)| *drift);
+        }
+        metadata.global_timebase = true;
+    }
+}
+
+/// Computes the frequency of the TSC by reading it before and after a busy loop.
+#[cfg(target_arch = "x86_64")]
+fn calibration_loop() -> Option<f64> {
+    use std::arch::x86_64::_rdtsc;
+
+    let start = std::time::Instant::now();
+    // SAFETY: `rdtsc` is available on every x86_64 CPU
+    let start_tsc = unsafe { _rdtsc() };
+    while start.elapsed() < CALIBRATION_LOOP_DURATION {
+        std::hint::spin_loop();
+    }
+    // SAFETY: `rdtsc` is available on every x86_64 CPU
+    let end_tsc = unsafe { _rdtsc() };
+
+    // The thread may have migrated to a core whose TSC is behind
+    Some(end_tsc.checked_sub(start_tsc)? as f64 / start.elapsed().as_secs_f64())
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn calibration_loop() -> Option<f64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpi_events::synchronization::mpi_wait::MpiWait;
+
+    #[test]
+    fn calibrates_from_pairs() {
+        let clock = TscClock::calibrate(1_000, 100.0, 2_000_001_000, 102.0)
+            .expect("failed to calibrate TSC");
+
+        assert_eq!(clock.frequency, 1e9);
+        assert_eq!(clock.cycles_to_ns(2_500), 2_500);
+        assert_eq!(clock.tsc_to_ns(1_000_001_000), 101_000_000_000);
+    }
+
+    #[test]
+    fn falls_back_on_short_runs() {
+        assert_eq!(from_pairs(1_000, 100.0, 2_000, 100.5), None);
+        assert_eq!(from_pairs(2_000, 100.0, 1_000, 102.0), None);
+
+        #[cfg(target_arch = "x86_64")]
+        assert!(TscClock::calibrate(1_000, 100.0, 2_000, 100.5).is_some_and(|c| c.frequency > 0.0));
+    }
+
+    #[test]
+    fn translates_to_global_timebase() {
+        let mut metadata: Metadata = serde_json::from_str(
+            "{\"interpol_version\":\"0.3.0\",\"command_line\":[],\"start_date\":\"\",\"mpi_library_version\":null,\"world_size\":2,\"provided_thread_lvl\":null,\"ranks\":[{\"rank\":0,\"hostname\":\"node0\",\"pid\":1000},{\"rank\":1,\"hostname\":\"node1\",\"pid\":1001}]}",
+        )
+        .expect("failed to deserialize metadata");
+        let mut events: Vec<MpiEvent> = vec![
+            MpiWait::new(0, 7, 4_096, 128).into(),
+            MpiWait::new(1, 7, 1_096, 128).into(),
+        ];
+
+        // Rank 1 has not been synchronized
+        metadata.ranks[0].init_sync = Some(ClockSync {
+            tsc: 0,
+            offset: 0,
+            round_trip: 0,
+        });
+        assert!(GlobalTimebase::new(&metadata).is_none());
+
+        metadata.ranks[1].init_sync = Some(ClockSync {
+            tsc: 500,
+            offset: 3_000,
+            round_trip: 40,
+        });
+        let timebase = GlobalTimebase::new(&metadata).expect("failed to build timebase");
+        assert!(timebase.covers(0) && timebase.covers(1) && !timebase.covers(2));
+        for event in events.iter_mut() {
+            timebase.translate(event);
+        }
+        timebase.record(&mut metadata);
+        assert_eq!(events[0].tsc(), 4_096);
+        assert_eq!(events[1].tsc(), 4_096);
+        assert_eq!(events[1].duration(), 128);
+        assert!(metadata.global_timebase);
+        assert_eq!(metadata.ranks[1].drift, Some(0.0));
+
+        // Events already in the global timebase are not shifted twice
+        assert!(GlobalTimebase::new(&metadata).is_none());
+    }
+
+    #[test]
+    fn corrects_linear_drift() {
+        let init = ClockSync {
+            tsc: 1_000,
+            offset: 500,
+            round_trip: 40,
+        };
+        let finalize = ClockSync {
+            tsc: 1_001_000,
+            offset: 1_500,
+            round_trip: 40,
+        };
+        let drift = init.drift_to(&finalize).expect("failed to compute drift");
+
+        assert_eq!(drift, 1e-3);
+        assert_eq!(init.to_global(1_000, drift), 1_500);
+        assert_eq!(init.to_global(501_000, drift), 502_000);
+        assert_eq!(init.to_global(1_001_000, drift), 1_002_500);
+        assert_eq!(finalize.drift_to(&init), None);
+    }
+}
@@ -0,0 +1,776 @@
+use crate::filter::{Filters, RankRange};
+use crate::metadata;
+use crate::mpi_events::MpiEvent;
+use crate::types::{Aggregation, MpiComm, MpiRank, Tsc, Usecs};
+use crate::{InterpolError, InterpolErrorKind};
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// The configuration file read from the working directory, unless `INTERPOL_CONFIG` is set.
+const CONFIG_FILE: &str = "interpol.toml";
+
+/// The format in which trace files are written.
+///
+/// Binary traces are much smaller and faster to parse, and can be converted back to JSON using
+/// `binary::read_trace`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Binary,
+}
+
+impl OutputFormat {
+    /// Returns the extension of the trace files written in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Binary => "bin",
+        }
+    }
+
+    /// Parses the name of a format, `json` or `binary`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "json" => Ok(OutputFormat::Json),
+            "binary" => Ok(OutputFormat::Binary),
+            _ => Err(String::from("expected `json` or `binary`")),
+        }
+    }
+}
+
+/// The configuration of `interpol-rs`.
+///
+/// It is loaded once per process, from the `interpol.toml` file of the working directory (or the
+/// file given by `INTERPOL_CONFIG`) if it exists, and from the `INTERPOL_*` environment variables,
+/// which take precedence over the file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// The directory in which the directory of every run is created (`INTERPOL_DIR`,
+    /// `output_dir`).
+    pub output_dir: PathBuf,
+    /// The name of the directory in which the trace files of the run are written
+    /// (`INTERPOL_RUN_ID`, `run_id`). Unless it is set, it is the id of the job given by the
+    /// batch scheduler (`SLURM_JOB_ID` and `SLURM_STEP_ID`, or `PBS_JOBID`), and otherwise it is
+    /// derived from the date at which rank 0 initialized MPI (see `set_run_start`).
+    pub run_id: Option<String>,
+    /// The prefix of the name of every trace file (`INTERPOL_PREFIX`, `file_prefix`).
+    pub file_prefix: String,
+    /// The format of the trace files (`INTERPOL_FORMAT`, `format`).
+    pub format: OutputFormat,
+    /// Whether the merged JSON trace is pretty-printed (`INTERPOL_OUTPUT=readable`, `pretty`).
+    pub pretty: bool,
+    /// Whether the traces of every rank are merged at `MPI_Finalize` (`INTERPOL_MERGE`,
+    /// `merge`).
+    pub merge: bool,
+    /// How the traces of every rank are brought together to be merged (`INTERPOL_AGGREGATION`,
+    /// `aggregation`).
+    pub aggregation: Aggregation,
+    /// The rank that merges the traces (`INTERPOL_MERGE_ROOT`, `merge_root`).
+    pub merge_root: MpiRank,
+    /// Whether JSON traces include nanosecond timestamps (`INTERPOL_TIMESTAMPS=ns`, `timestamps`).
+    pub ns_timestamps: bool,
+    /// The number of bytes of events that a process buffers before flushing them to a segment
+    /// file (`INTERPOL_MEMORY_BUDGET`, `memory_budget`).
+    pub memory_budget: Option<usize>,
+    /// Whether tracing starts paused, until `interpol_resume` or `MPI_Pcontrol(1)` is called
+    /// (`INTERPOL_START_PAUSED`, `start_paused`).
+    pub start_paused: bool,
+    /// Whether every event is also appended to a journal file as soon as it is recorded, so that
+    /// it survives a crash of the process (`INTERPOL_JOURNAL`, `journal`).
+    pub journal: bool,
+    /// The interval at which the journal is written to disk (`INTERPOL_JOURNAL_SYNC`,
+    /// `journal_sync`, in milliseconds).
+    pub journal_sync: Duration,
+    /// The filters applied to MPI calls before they are recorded (`INTERPOL_RANKS`,
+    /// `INTERPOL_EVENTS`, `INTERPOL_COMMS`, `INTERPOL_MIN_DURATION`, and the `ranks`, `events`,
+    /// `comms` and `min_duration` keys).
+    pub filters: Filters,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("interpol-tmp"),
+            run_id: None,
+            file_prefix: String::from("interpol"),
+            format: OutputFormat::Json,
+            pretty: false,
+            merge: true,
+            aggregation: Aggregation::ViaFilesystem,
+            merge_root: 0,
+            ns_timestamps: false,
+            memory_budget: None,
+            start_paused: false,
+            journal: false,
+            journal_sync: Duration::from_secs(1),
+            filters: Filters::default(),
+        }
+    }
+}
+
+/// The contents of a configuration file, in which every setting is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    output_dir: Option<String>,
+    run_id: Option<String>,
+    file_prefix: Option<String>,
+    format: Option<String>,
+    pretty: Option<bool>,
+    merge: Option<bool>,
+    aggregation: Option<String>,
+    merge_root: Option<MpiRank>,
+    timestamps: Option<String>,
+    memory_budget: Option<Bytes>,
+    start_paused: Option<bool>,
+    journal: Option<bool>,
+    journal_sync: Option<u64>,
+    ranks: Option<String>,
+    events: Option<Vec<String>>,
+    comms: Option<Vec<MpiComm>>,
+    min_duration: Option<Tsc>,
+}
+
+/// A number of bytes in a configuration file, given either as an integer or as a string with a
+/// unit suffix.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Bytes {
+    Count(usize),
+    Text(String),
+}
+
+impl Config {
+    /// Loads the configuration from the configuration file and the environment of the process.
+    pub fn from_env() -> Result<Self, InterpolError> {
+        let (path, required) = match std::env::var_os("INTERPOL_CONFIG") {
+            Some(path) => (PathBuf::from(path), true),
+            None => (PathBuf::from(CONFIG_FILE), false),
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => Some(contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => None,
+            Err(e) => {
+                return Err(config_error(format!(
+                    "failed to read `{}`: {e}",
+                    path.display()
+                )))
+            }
+        };
+
+        Self::from_sources(contents.as_deref(), |name| std::env::var(name).ok())
+    }
+
+    /// Builds the configuration from the contents of a configuration file, if any, and from
+    /// environment variables, which take precedence over the file.
+    pub fn from_sources(
+        file: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, InterpolError> {
+        let mut config = Config::default();
+        if let Some(contents) = file {
+            let file = toml::from_str(contents)
+                .map_err(|e| config_error(format!("invalid configuration file: {e}")))?;
+            config.apply_file(file)?;
+        }
+        config.apply_env(env)?;
+        Ok(config)
+    }
+
+    /// Returns the name of the directory of the run, if it is known.
+    pub fn resolved_run_id(&self) -> Option<&str> {
+        self.run_id
+            .as_deref()
+            .or_else(|| RUN_START.get().map(String::as_str))
+    }
+
+    /// Returns the directory in which the trace files of the run are written, or the output
+    /// directory itself if the run cannot be told apart (e.g. when merging traces offline).
+    pub fn run_dir(&self) -> PathBuf {
+        match self.resolved_run_id() {
+            Some(run_id) => self.output_dir.join(run_id),
+            None => self.output_dir.clone(),
+        }
+    }
+
+    /// Returns the path of a trace file of the given rank, such as `interpol_rank0_traces.json`.
+    pub fn rank_file(&self, rank: MpiRank, name: &str) -> PathBuf {
+        self.run_dir().join(format!(
+            "{}_rank{rank}_{name}.{}",
+            self.file_prefix,
+            self.format.extension()
+        ))
+    }
+
+    /// Returns the rank of a trace file written with `rank_file` or of a journal, given its file
+    /// name.
+    pub fn rank_of_file(&self, file_name: &str) -> Option<MpiRank> {
+        let rank = file_name
+            .strip_prefix(&self.file_prefix)?
+            .strip_prefix("_rank")?;
+        let end = rank.find(['_', '.'])?;
+        rank[..end].parse().ok()
+    }
+
+    /// Returns the path of the journal of the given rank, such as `interpol_rank0.journal`.
+    pub fn journal_file(&self, rank: MpiRank) -> PathBuf {
+        self.run_dir()
+            .join(format!("{}_rank{rank}.journal", self.file_prefix))
+    }
+
+    /// Returns the path of the merged trace, such as `interpol_traces.json`.
+    pub fn merged_file(&self) -> PathBuf {
+        self.run_dir().join(format!(
+            "{}_traces.{}",
+            self.file_prefix,
+            self.format.extension()
+        ))
+    }
+
+    /// Returns the path of the manifest of the run, such as `interpol_manifest.json`.
+    pub fn manifest_file(&self) -> PathBuf {
+        self.run_dir()
+            .join(format!("{}_manifest.json", self.file_prefix))
+    }
+
+    fn apply_file(&mut self, file: ConfigFile) -> Result<(), InterpolError> {
+        if let Some(value) = file.output_dir {
+            self.output_dir = setting("`output_dir`", &value, parse_dir)?;
+        }
+        if let Some(value) = file.run_id {
+            self.run_id = Some(setting("`run_id`", &value, parse_run_id)?);
+        }
+        if let Some(value) = file.file_prefix {
+            self.file_prefix = setting("`file_prefix`", &value, parse_prefix)?;
+        }
+        if let Some(value) = file.format {
+            self.format = setting("`format`", &value, OutputFormat::parse)?;
+        }
+        if let Some(value) = file.pretty {
+            self.pretty = value;
+        }
+        if let Some(value) = file.merge {
+            self.merge = value;
+        }
+        if let Some(value) = file.aggregation {
+            self.aggregation = setting("`aggregation`", &value, parse_aggregation)?;
+        }
+        if let Some(value) = file.merge_root {
+            self.merge_root = setting("`merge_root`", &value.to_string(), parse_root)?;
+        }
+        if let Some(value) = file.timestamps {
+            self.ns_timestamps = setting("`timestamps`", &value, parse_timestamps)?;
+        }
+        match file.memory_budget {
+            Some(Bytes::Count(0)) => {
+                return Err(config_error(String::from(
+                    "invalid value 0 for `memory_budget`: expected a positive number of bytes",
+                )))
+            }
+            Some(Bytes::Count(value)) => self.memory_budget = Some(value),
+            Some(Bytes::Text(value)) => {
+                self.memory_budget = Some(setting("`memory_budget`", &value, parse_bytes)?)
+            }
+            None => {}
+        }
+        if let Some(value) = file.start_paused {
+            self.start_paused = value;
+        }
+        if let Some(value) = file.journal {
+            self.journal = value;
+        }
+        if let Some(value) = file.journal_sync {
+            self.journal_sync = setting("`journal_sync`", &value.to_string(), parse_millis)?;
+        }
+        if let Some(value) = file.ranks {
+            self.filters.ranks = Some(setting("`ranks`", &value, parse_ranks)?);
+        }
+        if let Some(events) = file.events {
+            self.filters.events = Some(parse_events(events.iter().map(String::as_str))?);
+        }
+        if let Some(comms) = file.comms {
+            if comms.is_empty() {
+                return Err(config_error(String::from(
+                    "the list of communicators to record is empty",
+                )));
+            }
+            self.filters.comms = Some(comms);
+        }
+        if let Some(value) = file.min_duration {
+            self.filters.min_duration = Some(value);
+        }
+
+        Ok(())
+    }
+
+    fn apply_env(&mut self, env: impl Fn(&str) -> Option<String>) -> Result<(), InterpolError> {
+        if let Some(value) = env("INTERPOL_DIR") {
+            self.output_dir = setting("`INTERPOL_DIR`", &value, parse_dir)?;
+        }
+        if let Some(value) = env("INTERPOL_RUN_ID") {
+            self.run_id = Some(setting("`INTERPOL_RUN_ID`", &value, parse_run_id)?);
+        } else if self.run_id.is_none() {
+            self.run_id = job_id(&env);
+        }
+        if let Some(value) = env("INTERPOL_PREFIX") {
+            self.file_prefix = setting("`INTERPOL_PREFIX`", &value, parse_prefix)?;
+        }
+        if let Some(value) = env("INTERPOL_FORMAT") {
+            self.format = setting("`INTERPOL_FORMAT`", &value, OutputFormat::parse)?;
+        }
+        if let Some(value) = env("INTERPOL_OUTPUT") {
+            self.pretty = setting("`INTERPOL_OUTPUT`", &value, parse_output)?;
+        }
+        if let Some(value) = env("INTERPOL_MERGE") {
+            self.merge = setting("`INTERPOL_MERGE`", &value, parse_bool)?;
+        }
+        if let Some(value) = env("INTERPOL_AGGREGATION") {
+            self.aggregation = setting("`INTERPOL_AGGREGATION`", &value, parse_aggregation)?;
+        }
+        if let Some(value) = env("INTERPOL_MERGE_ROOT") {
+            self.merge_root = setting("`INTERPOL_MERGE_ROOT`", &value, parse_root)?;
+        }
+        if let Some(value) = env("INTERPOL_TIMESTAMPS") {
+            self.ns_timestamps = setting("`INTERPOL_TIMESTAMPS`", &value, parse_timestamps)?;
+        }
+        if let Some(value) = env("INTERPOL_MEMORY_BUDGET") {
+            self.memory_budget = Some(setting("`INTERPOL_MEMORY_BUDGET`", &value, parse_bytes)?);
+        }
+        if let Some(value) = env("INTERPOL_START_PAUSED") {
+            self.start_paused = setting("`INTERPOL_START_PAUSED`", &value, parse_bool)?;
+        }
+        if let Some(value) = env("INTERPOL_JOURNAL") {
+            self.journal = setting("`INTERPOL_JOURNAL`", &value, parse_bool)?;
+        }
+        if let Some(value) = env("INTERPOL_JOURNAL_SYNC") {
+            self.journal_sync = setting("`INTERPOL_JOURNAL_SYNC`", &value, parse_millis)?;
+        }
+        if let Some(value) = env("INTERPOL_RANKS") {
+            self.filters.ranks = Some(setting("`INTERPOL_RANKS`", &value, parse_ranks)?);
+        }
+        if let Some(value) = env("INTERPOL_EVENTS") {
+            self.filters.events = Some(parse_events(value.split(','))?);
+        }
+        if let Some(value) = env("INTERPOL_COMMS") {
+            self.filters.comms = Some(setting("`INTERPOL_COMMS`", &value, parse_comms)?);
+        }
+        if let Some(value) = env("INTERPOL_MIN_DURATION") {
+            self.filters.min_duration =
+                Some(setting("`INTERPOL_MIN_DURATION`", &value, parse_cycles)?);
+        }
+
+        Ok(())
+    }
+}
+
+/// The configuration of the current process, loaded on the first call to `load`.
+static CONFIG: OnceLock<Result<Config, InterpolError>> = OnceLock::new();
+
+/// Returns the configuration of the current process, loading it on the first call.
+///
+/// An invalid configuration is reported once, and no events are recorded for the whole run.
+pub fn load() -> Result<&'static Config, &'static InterpolError> {
+    CONFIG
+        .get_or_init(|| {
+            let config = Config::from_env();
+            if let Err(e) = &config {
+                eprintln!("[interpol]: {e}; tracing is disabled");
+            }
+            config
+        })
+        .as_ref()
+}
+
+/// The name of the directory of the run derived from the date at which rank 0 initialized MPI,
+/// used when neither the configuration nor the batch scheduler gives one.
+static RUN_START: OnceLock<String> = OnceLock::new();
+
+/// Sets the date, in seconds since the epoch, at which rank 0 initialized MPI, which every rank
+/// must agree on.
+pub(crate) fn set_run_start(time: Usecs) {
+    RUN_START.get_or_init(|| run_id_from_start(time));
+}
+
+/// Returns the name of the directory of a run started at the given date, such as
+/// `run-20220614T093012.251342Z`.
+fn run_id_from_start(time: Usecs) -> String {
+    let date: String = metadata::format_date(time)
+        .chars()
+        .filter(|&c| c != '-' && c != ':')
+        .collect();
+    let micros = (time.max(0.0).fract() * 1e6) as u32;
+    format!("run-{}.{micros:06}Z", date.trim_end_matches('Z'))
+}
+
+/// Returns the id of the job given by the batch scheduler, if any, to name the directory of the
+/// run. Slurm job steps launched by the same job each get their own directory.
+fn job_id(env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let job_id = match (env("SLURM_JOB_ID"), env("SLURM_STEP_ID")) {
+        (Some(job), Some(step)) => format!("{job}.{step}"),
+        (Some(job), None) => job,
+        (None, _) => env("PBS_JOBID")?,
+    };
+    parse_run_id(job_id.trim()).ok()
+}
+
+/// Normalizes the name of an event kind, so that `MpiIsend`, `MPI_Isend` and `isend` all refer to
+/// the same kind.
+pub fn event_key(name: &str) -> String {
+    let key: String = name
+        .chars()
+        .filter(|&c| c != '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    match key.strip_prefix("mpi") {
+        Some(stripped) => stripped.to_string(),
+        None => key,
+    }
+}
+
+fn config_error(reason: String) -> InterpolError {
+    InterpolError::new(InterpolErrorKind::Config, reason)
+}
+
+/// Parses the value of a setting, naming the setting in the error if it is invalid.
+fn setting<T>(
+    name: &str,
+    value: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<T, InterpolError> {
+    parse(value.trim())
+        .map_err(|reason| config_error(format!("invalid value \"{value}\" for {name}: {reason}")))
+}
+
+fn parse_dir(value: &str) -> Result<PathBuf, String> {
+    match value {
+        "" => Err(String::from("expected a directory")),
+        _ => Ok(PathBuf::from(value)),
+    }
+}
+
+fn parse_prefix(value: &str) -> Result<String, String> {
+    if value.is_empty() || value.contains('/') {
+        Err(String::from("expected a non-empty file name prefix"))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+fn parse_run_id(value: &str) -> Result<String, String> {
+    if value.is_empty() || value.contains('/') || value == "." || value == ".." {
+        Err(String::from("expected a non-empty directory name"))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+fn parse_output(value: &str) -> Result<bool, String> {
+    match value {
+        "readable" => Ok(true),
+        "compact" => Ok(false),
+        _ => Err(String::from("expected `readable` or `compact`")),
+    }
+}
+
+fn parse_aggregation(value: &str) -> Result<Aggregation, String> {
+    match value {
+        "filesystem" => Ok(Aggregation::ViaFilesystem),
+        "gather" => Ok(Aggregation::ViaGather),
+        "tree" => Ok(Aggregation::ViaTree),
+        _ => Err(String::from("expected `filesystem`, `gather` or `tree`")),
+    }
+}
+
+fn parse_root(value: &str) -> Result<MpiRank, String> {
+    match value.parse() {
+        Ok(rank) if rank >= 0 => Ok(rank),
+        _ => Err(String::from("expected a rank")),
+    }
+}
+
+fn parse_timestamps(value: &str) -> Result<bool, String> {
+    match value {
+        "ns" => Ok(true),
+        "tsc" => Ok(false),
+        _ => Err(String::from("expected `ns` or `tsc`")),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(String::from("expected a boolean")),
+    }
+}
+
+/// Parses a number of bytes, optionally followed by a `K`, `M` or `G` binary unit suffix.
+fn parse_bytes(value: &str) -> Result<usize, String> {
+    let invalid =
+        || String::from("expected a positive number of bytes, optionally followed by K, M or G");
+    let (digits, multiplier) = match value
+        .chars()
+        .last()
+        .ok_or_else(invalid)?
+        .to_ascii_uppercase()
+    {
+        'K' => (&value[..value.len() - 1], 1 << 10),
+        'M' => (&value[..value.len() - 1], 1 << 20),
+        'G' => (&value[..value.len() - 1], 1 << 30),
+        _ => (value, 1),
+    };
+
+    match digits.trim().parse::<usize>() {
+        Ok(0) | Err(_) => Err(invalid()),
+        Ok(n) => n.checked_mul(multiplier).ok_or_else(invalid),
+    }
+}
+
+/// Parses a comma-separated list of ranks and inclusive rank ranges, such as `0,2,4-7`.
+fn parse_ranks(value: &str) -> Result<Vec<RankRange>, String> {
+    let invalid = || String::from("expected a comma-separated list of ranks and ranges");
+    let ranks = value
+        .split(',')
+        .map(|item| {
+            let (first, last) = item.split_once('-').unwrap_or((item, item));
+            let first: MpiRank = first.trim().parse().map_err(|_| invalid())?;
+            let last: MpiRank = last.trim().parse().map_err(|_| invalid())?;
+            if first < 0 || last < first {
+                return Err(invalid());
+            }
+            Ok(RankRange { first, last })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ranks)
+}
+
+/// Parses a comma-separated list of communicator identifiers.
+fn parse_comms(value: &str) -> Result<Vec<MpiComm>, String> {
+    value
+        .split(',')
+        .map(|comm| {
+            comm.trim()
+                .parse()
+                .map_err(|_| String::from("expected a comma-separated list of communicators"))
+        })
+        .collect()
+}
+
+fn parse_millis(value: &str) -> Result<Duration, String> {
+    match value.parse() {
+        Ok(0) | Err(_) => Err(String::from("expected a positive number of milliseconds")),
+        Ok(millis) => Ok(Duration::from_millis(millis)),
+    }
+}
+
+fn parse_cycles(value: &str) -> Result<Tsc, String> {
+    value
+        .parse()
+        .map_err(|_| String::from("expected a number of TSC cycles"))
+}
+
+/// Parses a list of event kinds into the names of the events, rejecting unknown kinds and empty
+/// lists.
+fn parse_events<'a>(names: impl Iterator<Item = &'a str>) -> Result<Vec<String>, InterpolError> {
+    let mut events = Vec::new();
+    for name in names.map(str::trim).filter(|name| !name.is_empty()) {
+        let key = event_key(name);
+        match MpiEvent::NAMES.iter().find(|known| event_key(known) == key) {
+            Some(known) => events.push(known.to_string()),
+            None => {
+                return Err(config_error(format!(
+                    "unknown event kind \"{name}\" (expected one of {})",
+                    MpiEvent::NAMES.join(", ")
+                )))
+            }
+        }
+    }
+
+    if events.is_empty() {
+        return Err(config_error(String::from(
+            "the list of events to record is empty",
+        )));
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        move |name| vars.get(name).map(|value| value.to_string())
+    }
+
+    #[test]
+    fn loads_defaults() {
+        let config = Config::from_sources(None, env(&[])).expect("failed to load configuration");
+
+        assert_eq!(config, Config::default());
+        assert_eq!(config.output_dir, PathBuf::from("interpol-tmp"));
+        assert!(config.merge);
+        assert!(!config.filters.is_active());
+    }
+
+    #[test]
+    fn names_rank_files() {
+        let config = Config::default();
+        let path = config.rank_file(12, "segment3");
+
+        assert_eq!(
+            path,
+            PathBuf::from("interpol-tmp/interpol_rank12_segment3.json")
+        );
+        assert_eq!(
+            config.rank_of_file("interpol_rank12_segment3.json"),
+            Some(12)
+        );
+        assert_eq!(
+            config.journal_file(12),
+            PathBuf::from("interpol-tmp/interpol_rank12.journal")
+        );
+        assert_eq!(config.rank_of_file("interpol_rank12.journal"), Some(12));
+        assert_eq!(config.rank_of_file("interpol_rankX_traces.json"), None);
+        assert_eq!(config.rank_of_file("other_rank1_traces.json"), None);
+    }
+
+    #[test]
+    fn names_run_directories() {
+        let slurm = env(&[("SLURM_JOB_ID", "1234"), ("SLURM_STEP_ID", "0")]);
+        let config = Config::from_sources(None, slurm).expect("failed to load configuration");
+        assert_eq!(config.run_id.as_deref(), Some("1234.0"));
+        assert_eq!(
+            config.rank_file(0, "traces"),
+            PathBuf::from("interpol-tmp/1234.0/interpol_rank0_traces.json")
+        );
+        assert_eq!(
+            config.manifest_file(),
+            PathBuf::from("interpol-tmp/1234.0/interpol_manifest.json")
+        );
+
+        let pbs = env(&[("PBS_JOBID", "5678.server")]);
+        let config = Config::from_sources(Some("run_id = \"solver\""), pbs)
+            .expect("failed to load configuration");
+        assert_eq!(config.run_id.as_deref(), Some("solver"));
+        let pbs = env(&[("PBS_JOBID", "5678.server")]);
+        let config = Config::from_sources(None, pbs).expect("failed to load configuration");
+        assert_eq!(config.run_id.as_deref(), Some("5678.server"));
+
+        assert_eq!(
+            run_id_from_start(1_655_199_012.251_342),
+            "run-20220614T093012.251342Z"
+        );
+    }
+
+    #[test]
+    fn environment_overrides_file() {
+        let file = r#"
+            output_dir = "traces"
+            file_prefix = "run"
+            run_id = "first"
+            format = "binary"
+            pretty = true
+            merge = false
+            aggregation = "tree"
+            merge_root = 2
+            memory_budget = "64M"
+            start_paused = true
+            journal = true
+            ranks = "0-3"
+            events = ["MpiIsend", "MPI_Wait"]
+            min_duration = 1000
+        "#;
+        let config = Config::from_sources(
+            Some(file),
+            env(&[
+                ("INTERPOL_FORMAT", "json"),
+                ("INTERPOL_EVENTS", "isend, MPI_Irecv"),
+                ("INTERPOL_RANKS", "0,4-7"),
+                ("INTERPOL_COMMS", "0"),
+                ("INTERPOL_JOURNAL_SYNC", "250"),
+                ("INTERPOL_MERGE_ROOT", "3"),
+                ("INTERPOL_RUN_ID", "second"),
+                ("SLURM_JOB_ID", "1234"),
+            ]),
+        )
+        .expect("failed to load configuration");
+
+        assert_eq!(config.output_dir, PathBuf::from("traces"));
+        assert_eq!(config.file_prefix, "run");
+        assert_eq!(config.run_id.as_deref(), Some("second"));
+        assert_eq!(config.format, OutputFormat::Json);
+        assert!(config.pretty);
+        assert!(!config.merge);
+        assert_eq!(config.aggregation, Aggregation::ViaTree);
+        assert_eq!(config.merge_root, 3);
+        assert_eq!(config.memory_budget, Some(64 << 20));
+        assert!(config.start_paused);
+        assert!(config.journal);
+        assert_eq!(config.journal_sync, Duration::from_millis(250));
+        assert_eq!(
+            config.filters,
+            Filters {
+                ranks: Some(vec![
+                    RankRange { first: 0, last: 0 },
+                    RankRange { first: 4, last: 7 },
+                ]),
+                events: Some(vec![String::from("MpiIsend"), String::from("MpiIrecv")]),
+                comms: Some(vec![0]),
+                min_duration: Some(1000),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        for vars in [
+            [("INTERPOL_FORMAT", "xml")],
+            [("INTERPOL_MERGE", "maybe")],
+            [("INTERPOL_OUTPUT", "pretty")],
+            [("INTERPOL_MEMORY_BUDGET", "0")],
+            [("INTERPOL_EVENTS", "MpiSend,MpiFoo")],
+            [("INTERPOL_PREFIX", "a/b")],
+            [("INTERPOL_RANKS", "3-1")],
+            [("INTERPOL_RANKS", "0,")],
+            [("INTERPOL_COMMS", "world")],
+            [("INTERPOL_MIN_DURATION", "-5")],
+            [("INTERPOL_JOURNAL_SYNC", "0")],
+            [("INTERPOL_AGGREGATION", "mpi")],
+            [("INTERPOL_MERGE_ROOT", "-1")],
+            [("INTERPOL_RUN_ID", "runs/1")],
+        ] {
+            let error =
+                Config::from_sources(None, env(&vars)).expect_err("invalid setting accepted");
+            assert!(matches!(error.kind, InterpolErrorKind::Config));
+        }
+
+        assert!(Config::from_sources(Some("colour = true"), env(&[])).is_err());
+        assert!(Config::from_sources(Some("memory_budget = 0"), env(&[])).is_err());
+        assert!(Config::from_sources(Some("comms = []"), env(&[])).is_err());
+    }
+
+    #[test]
+    fn parses_memory_budget() {
+        assert_eq!(parse_bytes("4096"), Ok(4096));
+        assert_eq!(parse_bytes("64K"), Ok(64 << 10));
+        assert_eq!(parse_bytes("512m"), Ok(512 << 20));
+        assert_eq!(parse_bytes("2G"), Ok(2 << 30));
+        assert!(parse_bytes("").is_err());
+        assert!(parse_bytes("0").is_err());
+        assert!(parse_bytes("lots").is_err());
+    }
+
+    #[test]
+    fn normalizes_event_names() {
+        assert_eq!(event_key("MpiInitThread"), "initthread");
+        assert_eq!(event_key("MPI_Init_thread"), "initthread");
+        assert_eq!(event_key("ibarrier"), "ibarrier");
+    }
+}
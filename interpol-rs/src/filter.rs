@@ -0,0 +1,107 @@
+use crate::types::{MpiCallType, MpiComm, MpiRank, Tsc};
+use serde::{Deserialize, Serialize};
+
+/// An inclusive range of ranks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankRange {
+    pub first: MpiRank,
+    pub last: MpiRank,
+}
+
+impl RankRange {
+    /// Returns whether the range contains the given rank.
+    pub fn contains(&self, rank: MpiRank) -> bool {
+        (self.first..=self.last).contains(&rank)
+    }
+}
+
+/// Filters applied to MPI calls before their events are built and recorded.
+///
+/// A call is recorded only if it is accepted by every filter that is set. `MPI_Init`,
+/// `MPI_Init_thread` and `MPI_Finalize` are always recorded, as they delimit the trace of each
+/// rank. The filters are stored in the metadata of the trace, so that analyses know that the
+/// recorded data is partial.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Filters {
+    /// The ranks whose calls are recorded.
+    pub ranks: Option<Vec<RankRange>>,
+    /// The kinds of events that are recorded, named as in the `type` field of serialized events.
+    pub events: Option<Vec<String>>,
+    /// The communicators on which calls are recorded. Calls that do not take a communicator,
+    /// such as `MPI_Wait`, are not filtered by communicator.
+    pub comms: Option<Vec<MpiComm>>,
+    /// The minimum duration of the recorded calls, in TSC cycles.
+    pub min_duration: Option<Tsc>,
+}
+
+impl Filters {
+    /// Returns whether any filter is set.
+    pub fn is_active(&self) -> bool {
+        *self != Filters::default()
+    }
+
+    /// Returns whether a call must be recorded.
+    ///
+    /// `comm` is negative for calls that do not take a communicator.
+    pub fn keeps(&self, kind: &MpiCallType, rank: MpiRank, comm: MpiComm, duration: Tsc) -> bool {
+        if matches!(
+            kind,
+            MpiCallType::Init | MpiCallType::Initthread | MpiCallType::Finalize
+        ) {
+            return true;
+        }
+
+        self.ranks
+            .as_ref()
+            .is_none_or(|ranks| ranks.iter().any(|range| range.contains(rank)))
+            && self
+                .events
+                .as_ref()
+                .is_none_or(|events| events.iter().any(|event| event == kind.event_name()))
+            && (comm < 0
+                || self
+                    .comms
+                    .as_ref()
+                    .is_none_or(|comms| comms.contains(&comm)))
+            && self.min_duration.is_none_or(|min| duration >= min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn keeps_everything_by_default() {
+        let filters = Filters::default();
+
+        assert!(!filters.is_active());
+        assert!(filters.keeps(&MpiCallType::Isend, 3, MPI_COMM_WORLD, 0));
+    }
+
+    #[test]
+    fn filters_calls() {
+        let filters = Filters {
+            ranks: Some(vec![
+                RankRange { first: 0, last: 0 },
+                RankRange { first: 4, last: 7 },
+            ]),
+            events: Some(vec![String::from("MpiIsend"), String::from("MpiWait")]),
+            comms: Some(vec![MPI_COMM_WORLD]),
+            min_duration: Some(100),
+        };
+        assert!(filters.is_active());
+
+        assert!(filters.keeps(&MpiCallType::Isend, 5, MPI_COMM_WORLD, 100));
+        assert!(filters.keeps(&MpiCallType::Wait, 0, -1, 200));
+        assert!(!filters.keeps(&MpiCallType::Isend, 2, MPI_COMM_WORLD, 100));
+        assert!(!filters.keeps(&MpiCallType::Irecv, 5, MPI_COMM_WORLD, 100));
+        assert!(!filters.keeps(&MpiCallType::Isend, 5, 3, 100));
+        assert!(!filters.keeps(&MpiCallType::Isend, 5, MPI_COMM_WORLD, 99));
+
+        // Management calls are always recorded
+        assert!(filters.keeps(&MpiCallType::Init, 2, -1, 0));
+        assert!(filters.keeps(&MpiCallType::Finalize, 2, -1, 0));
+    }
+}
@@ -1,87 +1,173 @@
+use crate::binary::{self, TraceHeader};
+use crate::causality::CausalMerge;
+use crate::clock::{self, ClockSync};
+use crate::config::{self, Config, OutputFormat};
+use crate::filter::Filters;
+use crate::journal::JournalWriter;
+use crate::manifest::{self, Manifest};
+use crate::merge::{self, Merged, TraceSet};
+use crate::metadata;
 use crate::mpi_events::{
     collectives::{
-        mpi_ibcast::MpiIbcastBuilder, mpi_igather::MpiIgatherBuilder,
-        mpi_ireduce::MpiIreduceBuilder, mpi_iscatter::MpiIscatterBuilder,
+        mpi_allgather::MpiAllgatherBuilder, mpi_allgatherv::MpiAllgathervBuilder,
+        mpi_allreduce::MpiAllreduceBuilder, mpi_alltoall::MpiAlltoallBuilder,
+        mpi_alltoallv::MpiAlltoallvBuilder, mpi_bcast::MpiBcastBuilder,
+        mpi_gather::MpiGatherBuilder, mpi_gatherv::MpiGathervBuilder,
+        mpi_iallgather::MpiIallgatherBuilder, mpi_iallgatherv::MpiIallgathervBuilder,
+        mpi_iallreduce::MpiIallreduceBuilder, mpi_ialltoall::MpiIalltoallBuilder,
+        mpi_ialltoallv::MpiIalltoallvBuilder, mpi_ibcast::MpiIbcastBuilder,
+        mpi_iexscan::MpiIexscanBuilder, mpi_igather::MpiIgatherBuilder,
+        mpi_igatherv::MpiIgathervBuilder, mpi_ireduce::MpiIreduceBuilder,
+        mpi_ireduce_scatter::MpiIreduceScatterBuilder, mpi_iscan::MpiIscanBuilder,
+        mpi_iscatter::MpiIscatterBuilder, mpi_iscatterv::MpiIscattervBuilder,
+        mpi_reduce::MpiReduceBuilder, mpi_scatter::MpiScatterBuilder,
+        mpi_scatterv::MpiScattervBuilder,
     },
     management::{
         mpi_finalize::MpiFinalizeBuilder, mpi_init::MpiInitBuilder,
         mpi_init_thread::MpiInitThreadBuilder,
     },
+    markers::{
+        interpol_pause::InterpolPause, interpol_resume::InterpolResume, region_begin::RegionBegin,
+        region_end::RegionEnd,
+    },
     point_to_point::{
         mpi_irecv::MpiIrecvBuilder, mpi_isend::MpiIsendBuilder, mpi_recv::MpiRecvBuilder,
         mpi_send::MpiSendBuilder,
     },
     synchronization::{
         mpi_barrier::MpiBarrierBuilder, mpi_ibarrier::MpiIbarrierBuilder, mpi_test::MpiTestBuilder,
-        mpi_wait::MpiWaitBuilder,
+        mpi_testall::MpiTestallBuilder, mpi_testany::MpiTestanyBuilder,
+        mpi_testsome::MpiTestsomeBuilder, mpi_wait::MpiWaitBuilder, mpi_waitall::MpiWaitallBuilder,
+        mpi_waitany::MpiWaitanyBuilder, mpi_waitsome::MpiWaitsomeBuilder,
     },
+    MpiEvent,
+};
+use crate::region;
+use crate::trace_file;
+use crate::types::{
+    Aggregation, ClockSyncPoint, MpiCallType, MpiComm, MpiOp, MpiRank, MpiReq, MpiTag, RegionId,
+    Tsc, Usecs,
 };
-use crate::types::{MpiCallType, MpiComm, MpiOp, MpiRank, MpiReq, MpiTag, Tsc, Usecs};
-use crate::InterpolError;
-use rayon::prelude::*;
+use crate::{InterpolError, InterpolErrorKind};
+use std::ffi::{c_char, c_int, CStr};
 use std::fs::{self, File};
-use std::io::Write;
-use std::sync::Mutex;
-
-static INTERPOL_DIR: &str = "interpol-tmp";
+use std::io::{Cursor, Read, Write};
+use std::os::fd::FromRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 
+/// A buffer of events recorded by a single thread.
+///
+/// The `Mutex` is only ever taken by its owning thread while recording, and by the thread that
+/// gathers every buffer at `MPI_Finalize`. It is therefore uncontended during the run.
 #[repr(transparent)]
-pub struct Trace(Mutex<Vec<Box<dyn Register>>>);
+pub struct Trace(Mutex<Vec<MpiEvent>>);
 
-#[typetag::serde(tag = "type")]
-pub trait Register: Send + Sync {
-    fn register(
-        self,
-        events: &mut Vec<Box<dyn Register>>,
-    ) -> Result<(), std::collections::TryReserveError>;
+pub trait Register: Into<MpiEvent> {
+    fn register(self, events: &mut Vec<MpiEvent>) -> Result<(), std::collections::TryReserveError>;
 
     fn tsc(&self) -> Tsc;
+
+    fn set_tsc(&mut self, tsc: Tsc);
+
+    fn duration(&self) -> Tsc;
+
+    fn current_rank(&self) -> MpiRank;
+
+    fn set_region(&mut self, region: Option<RegionId>);
 }
 
+/// Implements `Register` for an event type.
+///
+/// Events that do not measure the duration of their call (i.e. that do not have a `duration`
+/// field) must be marked as `instant`. Events that are not MPI calls, and thus do not have a
+/// `region` field, must be marked as `marker`.
 #[macro_export]
 macro_rules! impl_register {
-    ($t:ty) => {
+    ($t:ident) => {
+        $crate::impl_register!(
+            @impl $t,
+            |event: &$t| event.duration,
+            |event: &mut $t, region| event.region = region
+        );
+    };
+    ($t:ident, instant) => {
+        $crate::impl_register!(
+            @impl $t,
+            |_: &$t| 0,
+            |event: &mut $t, region| event.region = region
+        );
+    };
+    ($t:ident, marker) => {
+        $crate::impl_register!(@impl $t, |_: &$t| 0, |_: &mut $t, _| {});
+    };
+    (@impl $t:ident, $duration:expr, $set_region:expr) => {
         use std::collections::TryReserveError;
         use $crate::interpol::Register;
+        use $crate::mpi_events::MpiEvent;
+
+        impl From<$t> for MpiEvent {
+            fn from(event: $t) -> Self {
+                MpiEvent::$t(event)
+            }
+        }
 
-        #[typetag::serde]
         impl Register for $t {
-            fn register(self, events: &mut Vec<Box<dyn Register>>) -> Result<(), TryReserveError> {
+            fn register(self, events: &mut Vec<MpiEvent>) -> Result<(), TryReserveError> {
                 // Ensure that the program does not panic if allocation fails
                 events.try_reserve_exact(2 * events.len())?;
-                events.push(Box::new(self));
+                events.push(self.into());
                 Ok(())
             }
 
             fn tsc(&self) -> $crate::types::Tsc {
                 self.tsc
             }
+
+            fn set_tsc(&mut self, tsc: $crate::types::Tsc) {
+                self.tsc = tsc;
+            }
+
+            fn duration(&self) -> $crate::types::Tsc {
+                ($duration)(self)
+            }
+
+            fn current_rank(&self) -> $crate::types::MpiRank {
+                self.current_rank
+            }
+
+            fn set_region(&mut self, region: Option<$crate::types::RegionId>) {
+                ($set_region)(self, region)
+            }
         }
     };
 }
 
-/// A vector that keeps track of interposed MPI functions called by a process.
-///
-/// The `lazy_static` macro creates static objects that are only initialized when needed at
-/// runtime. In this case, this implementation is similar to a singleton. It removes the need
-/// to pass a constant pointer on the `Vec` back to the C part of the interposition library,
-/// therefore avoiding the use of `unsafe` code sections.
+/// The list of every event buffer created by the threads of the process.
 ///
 /// As the MPI standard allows for processes to run code in parallel (e.g. through libraries
-/// like OpenMP or pthread), the `Vec` *must* be wrapped in a `Mutex` to prevent concurrent
-/// attempts at pushing onto the traces vector. Each time an event is registered, the caller
-/// must first take the lock on the `Mutex` before pushing an `Event`. This ensures that
-/// `interpol-rs` is thread-safe even in `MPI_THREAD_MULTIPLE` context.
+/// like OpenMP or pthread), each thread records its events into its own `Trace` so that
+/// interposed calls made concurrently never contend on a shared lock. A thread registers its
+/// buffer in this list the first time it records an event; the lock on the list is thus only
+/// taken once per thread, and when all buffers are gathered.
 ///
-/// We have chosen to implement mutual exclusion in the Rust part of the interposition library
-/// to reduce the critical section of code to the minimum, i.e. when a MPI call has been
-/// registered and *needs* to be saved. This choice theoretically allows for the best
-/// safety/performance ratio.
-///
-/// It should be noted that in a MPI context, it is "rare" that the same process manages a
-/// large number of threads. Therefore, the contention on the `Mutex` should not impact the
-/// performance of the application and the blocking of threads will be kept to a minimum.
-static EVENTS: Trace = Trace(Mutex::new(Vec::new()));
+/// The list keeps a reference to every buffer, so events recorded by a thread that has exited
+/// before `MPI_Finalize` are not lost.
+static BUFFERS: Mutex<Vec<Arc<Trace>>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// The event buffer of the current thread, registered in `BUFFERS` on first use.
+    static EVENTS: Arc<Trace> = {
+        let trace = Arc::new(Trace(Mutex::new(Vec::new())));
+        BUFFERS
+            .lock()
+            .expect("failed to take the lock on the list of buffers")
+            .push(Arc::clone(&trace));
+        trace
+    };
+}
 
 #[derive(Debug, PartialEq)]
 #[repr(C)]
@@ -103,110 +189,955 @@ pub struct MpiCall {
     kind: MpiCallType,
 }
 
+/// The arrays of an MPI call that handles a variable number of requests or of peers, such as
+/// `MPI_Waitall` or `MPI_Alltoallv`, which do not fit in an `MpiCall`.
+///
+/// The arrays are borrowed from the interposition library for the duration of the call to
+/// `register_mpi_call_arrays`.
+#[derive(Debug)]
+#[repr(C)]
+pub struct MpiCallArrays {
+    /// The identifiers of the requests passed to the call.
+    reqs: *const MpiReq,
+    nb_reqs: usize,
+    /// The indices, in `reqs`, of the requests that completed during the call.
+    indices: *const i32,
+    nb_indices: usize,
+    /// The number of bytes sent to each rank of the communicator, indexed by rank.
+    peer_bytes_s: *const u32,
+    nb_peers_s: usize,
+    /// The number of bytes received from each rank of the communicator, indexed by rank.
+    peer_bytes_r: *const u32,
+    nb_peers_r: usize,
+}
+
+/// The arrays of an MPI call, borrowed from an `MpiCallArrays`.
+#[derive(Debug, Default)]
+struct CallArrays<'a> {
+    reqs: &'a [MpiReq],
+    indices: &'a [i32],
+    peer_bytes_s: &'a [u32],
+    peer_bytes_r: &'a [u32],
+}
+
+impl CallArrays<'_> {
+    /// Returns the indices of the completed requests, dropping the invalid ones (e.g.
+    /// `MPI_UNDEFINED`).
+    fn completed(&self) -> Vec<u32> {
+        self.indices
+            .iter()
+            .filter_map(|&index| u32::try_from(index).ok())
+            .filter(|&index| (index as usize) < self.reqs.len())
+            .collect()
+    }
+}
+
+/// Borrows an array passed by the interposition library, which may be null if it is empty.
+///
+/// # Safety
+///
+/// `ptr` must either be null or point to `len` initialized values that outlive the returned slice.
+unsafe fn borrow_array<'a, T>(ptr: *const T, len: usize) -> &'a [T] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(ptr, len)
+    }
+}
+
+/// The number of bytes held by all the event buffers of the process.
+static BUFFERED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// The index of the next segment file to be written, also used to prevent concurrent flushes.
+static SEGMENT_INDEX: Mutex<usize> = Mutex::new(0);
+
+/// Pushes an event onto the buffer of the calling thread, tagged with the innermost region
+/// entered by the thread.
+fn record<R: Register + Clone>(mut event: R) -> Result<(), InterpolError> {
+    event.set_region(region::current());
+    if JOURNALING.load(Ordering::Relaxed) {
+        append_to_journal(&event.clone().into());
+    }
+    // Account for the event before pushing it so that a concurrent flush never subtracts more
+    // than what has been added
+    BUFFERED_BYTES.fetch_add(std::mem::size_of::<MpiEvent>(), Ordering::Relaxed);
+    EVENTS.with(|trace| {
+        let mut guard = trace
+            .0
+            .lock()
+            .expect("failed to take the lock on the thread-local buffer");
+        event.register(&mut guard)
+    })?;
+
+    Ok(())
+}
+
+/// Whether events are appended to the journal of the rank as they are recorded.
+static JOURNALING: AtomicBool = AtomicBool::new(false);
+
+/// The journal of the rank, if the configuration enables it.
+///
+/// Unlike the event buffers, the journal is shared by every thread of the process.
+static JOURNAL: Mutex<Option<JournalWriter>> = Mutex::new(None);
+
+/// The number of threads that have been given an index in the journal.
+static JOURNAL_THREADS: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+    /// The index of the current thread in the journal, with which its events are told apart from
+    /// those of other threads when they are read back.
+    static JOURNAL_THREAD: u32 = JOURNAL_THREADS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Creates the journal of the rank, to which every event recorded afterwards is appended.
+fn open_journal(current_rank: MpiRank, config: &Config) -> Result<(), InterpolError> {
+    let path = config.journal_file(current_rank);
+    fs::create_dir_all(config.run_dir())?;
+    let header = TraceHeader::new(Some(current_rank), metadata::current());
+    let journal = JournalWriter::create(&path, &header, config.journal_sync)
+        .map_err(|e| e.with_file(&path).with_rank(Some(current_rank)))?;
+    add_written_file(&path);
+
+    *JOURNAL
+        .lock()
+        .expect("failed to take the lock on the journal") = Some(journal);
+    JOURNALING.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Opens the journal of the rank if the configuration enables it, before `MPI_Init` is recorded.
+///
+/// The rank is still traced if its journal cannot be created.
+fn start_journal(current_rank: MpiRank, config: &Config) {
+    if !config.journal {
+        return;
+    }
+    if let Err(e) = open_journal(current_rank, config) {
+        eprintln!("Rank {current_rank}: {e}; journaling is disabled");
+    }
+}
+
+/// Appends an event to the journal of the rank.
+fn append_to_journal(event: &MpiEvent) {
+    let thread = JOURNAL_THREAD.with(|thread| *thread);
+    write_to_journal(event.current_rank(), |journal| {
+        journal.append(thread, event)
+    });
+}
+
+/// Appends the metadata of the rank to its journal, once it has been completed with information
+/// that was not known when the journal was created, such as the offset of the clock of the rank.
+fn append_metadata_to_journal() {
+    if !JOURNALING.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(metadata) = metadata::current() {
+        let current_rank = CURRENT_RANK.load(Ordering::Relaxed);
+        write_to_journal(current_rank, |journal| journal.append_metadata(&metadata));
+    }
+}
+
+/// Writes to the journal of the rank, if it is open.
+///
+/// The journal is only a safety net, so a failed write does not prevent the event from being
+/// recorded: the error is reported and journaling is stopped, so that it is only reported once.
+fn write_to_journal(
+    current_rank: MpiRank,
+    write: impl FnOnce(&mut JournalWriter) -> Result<(), InterpolError>,
+) {
+    let mut guard = JOURNAL
+        .lock()
+        .expect("failed to take the lock on the journal");
+    let written = match guard.as_mut() {
+        Some(journal) => write(journal),
+        None => Ok(()),
+    };
+    if let Err(e) = written {
+        JOURNALING.store(false, Ordering::Relaxed);
+        *guard = None;
+        eprintln!("Rank {current_rank}: {e}; journaling is disabled");
+    }
+}
+
+/// Closes the journal of the rank once its trace has been written, removing it if requested.
+fn close_journal(
+    current_rank: MpiRank,
+    config: &Config,
+    remove: bool,
+) -> Result<(), InterpolError> {
+    JOURNALING.store(false, Ordering::Relaxed);
+    let journal = match JOURNAL.try_lock() {
+        Ok(mut guard) => guard.take(),
+        Err(_) => return Ok(()),
+    };
+
+    match journal {
+        Some(_) if remove => {
+            let path = config.journal_file(current_rank);
+            fs::remove_file(&path)?;
+            remove_written_file(&path);
+        }
+        Some(mut journal) => journal.sync()?,
+        None => {}
+    }
+    Ok(())
+}
+
+/// The names of the files written by the current rank in the directory of the run, which are
+/// listed in the manifest of the run.
+static WRITTEN_FILES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Adds a file to the files written by the current rank.
+///
+/// As it may be called while the process is terminating, the file is not added if the list is
+/// locked.
+fn add_written_file(path: &Path) {
+    let Some(name) = path.file_name() else {
+        return;
+    };
+    let name = name.to_string_lossy();
+    if let Ok(mut files) = WRITTEN_FILES.try_lock() {
+        if !files.iter().any(|file| *file == name) {
+            files.push(name.into_owned());
+        }
+    }
+}
+
+/// Removes a file from the files written by the current rank, once it has been deleted.
+fn remove_written_file(path: &Path) {
+    let Some(name) = path.file_name() else {
+        return;
+    };
+    let name = name.to_string_lossy();
+    if let Ok(mut files) = WRITTEN_FILES.try_lock() {
+        files.retain(|file| *file != name);
+    }
+}
+
+/// Drains the events of every registered buffer into a single `Vec`, sorted by TSC.
+fn gather_events(buffers: &Mutex<Vec<Arc<Trace>>>) -> Vec<MpiEvent> {
+    let buffers = buffers
+        .lock()
+        .expect("failed to take the lock on the list of buffers");
+    let mut events = Vec::new();
+    for trace in buffers.iter() {
+        let mut guard = trace
+            .0
+            .lock()
+            .expect("failed to take the lock on a thread-local buffer");
+        events.append(&mut guard);
+    }
+    sort_drained(events)
+}
+
+/// Drains the events of the registered buffers that are not locked into a single `Vec`, sorted
+/// by TSC.
+///
+/// This is used when the process is terminating, possibly while another thread is recording an
+/// event: the buffer of that thread is then skipped rather than waited for.
+fn try_gather_events(buffers: &Mutex<Vec<Arc<Trace>>>) -> Vec<MpiEvent> {
+    let buffers = match buffers.try_lock() {
+        Ok(buffers) => buffers,
+        Err(_) => return Vec::new(),
+    };
+    let mut events = Vec::new();
+    for trace in buffers.iter() {
+        if let Ok(mut guard) = trace.0.try_lock() {
+            events.append(&mut guard);
+        }
+    }
+    sort_drained(events)
+}
+
+/// Puts drained events that could not be written back into the buffer of the calling thread, so
+/// that they are written along with the next ones.
+fn restore_events(events: Vec<MpiEvent>) {
+    BUFFERED_BYTES.fetch_add(
+        events.len() * std::mem::size_of::<MpiEvent>(),
+        Ordering::Relaxed,
+    );
+    EVENTS.with(|trace| {
+        trace
+            .0
+            .lock()
+            .expect("failed to take the lock on the thread-local buffer")
+            .extend(events)
+    });
+}
+
+/// Sorts drained events by TSC, and accounts for the memory they no longer take in the buffers.
+fn sort_drained(mut events: Vec<MpiEvent>) -> Vec<MpiEvent> {
+    // Each buffer is already ordered, a stable sort merges them efficiently
+    events.sort_by_key(|event| event.tsc());
+
+    let drained = events.len() * std::mem::size_of::<MpiEvent>();
+    BUFFERED_BYTES.fetch_sub(drained, Ordering::Relaxed);
+    events
+}
+
+/// Flushes the buffered events of the current rank to a new segment file if they exceed the
+/// memory budget.
+///
+/// Only one thread flushes at a time; others keep recording into their buffers in the meantime.
+fn flush_if_over_budget(current_rank: MpiRank, config: &Config) -> Result<(), InterpolError> {
+    let budget = match config.memory_budget {
+        Some(budget) => budget,
+        None => return Ok(()),
+    };
+    if BUFFERED_BYTES.load(Ordering::Relaxed) <= budget {
+        return Ok(());
+    }
+    let mut index = match SEGMENT_INDEX.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => return Ok(()),
+    };
+
+    let events = gather_events(&BUFFERS);
+    let path = config.rank_file(current_rank, &format!("segment{}", *index));
+    write_trace_file(&events, metadata::current(), &path, current_rank, config)?;
+    *index += 1;
+    Ok(())
+}
+
+/// Removes the segment files left over by a previous run with the same run id for the current
+/// rank, and on rank 0 the manifest of that run, so that it is not used if this run does not
+/// reach `MPI_Finalize`.
+fn remove_stale_files(current_rank: MpiRank, config: &Config) -> Result<(), InterpolError> {
+    if current_rank == 0 {
+        match fs::remove_file(config.manifest_file()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    let prefix = format!("{}_rank{current_rank}_segment", config.file_prefix);
+    let entries = match fs::read_dir(config.run_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+
+    for entry in entries {
+        let dir_entry = entry?;
+        if dir_entry.file_name().to_string_lossy().starts_with(&prefix) {
+            fs::remove_file(dir_entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Serialize the contents of the `Vec` and write them to an output file
 fn serialize(
-    events: &mut Vec<Box<dyn Register>>,
+    events: &[MpiEvent],
     current_rank: MpiRank,
+    config: &Config,
 ) -> Result<(), InterpolError> {
     println!("[interpol]: serializing traces for rank {current_rank}");
-    let traces = serde_json::to_string(events).expect("failed to serialize traces to string");
-    let filename = format!("{}/rank{}_traces.json", INTERPOL_DIR, current_rank);
+    write_trace_file(
+        events,
+        metadata::current(),
+        &config.rank_file(current_rank, "traces"),
+        current_rank,
+        config,
+    )
+}
+
+/// Serializes a list of events in the configured output format, along with the metadata of the
+/// process, and writes them to the given file.
+fn write_trace_file(
+    events: &[MpiEvent],
+    metadata: Option<metadata::Metadata>,
+    path: &Path,
+    current_rank: MpiRank,
+    config: &Config,
+) -> Result<(), InterpolError> {
+    let write = || -> Result<(), InterpolError> {
+        fs::create_dir_all(config.run_dir())?;
+        let mut file = File::options()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(path)?;
+        match config.format {
+            OutputFormat::Json => {
+                let traces =
+                    trace_file::to_json(metadata.as_ref(), events, false, config.ns_timestamps)
+                        .map_err(|e| {
+                            InterpolError::new(InterpolErrorKind::Serialization, e.to_string())
+                        })?;
+                write!(file, "{}", traces)?;
+            }
+            OutputFormat::Binary => {
+                let header = TraceHeader::new(Some(current_rank), metadata);
+                binary::write_trace(file, &header, events)?;
+            }
+        }
 
-    fs::create_dir_all(INTERPOL_DIR)?;
-    let mut file = File::options()
-        .write(true)
-        .truncate(true)
-        .create(true)
-        .open(filename)?;
-    write!(file, "{}", traces)?;
+        Ok(())
+    };
+
+    write().map_err(|e| e.with_file(path).with_rank(Some(current_rank)))?;
+    add_written_file(path);
     Ok(())
 }
 
+/// Stores the offset of the TSC of the current rank to the TSC of rank 0, measured by ping-pongs
+/// at `MPI_Init` or `MPI_Finalize`.
+///
+/// `tsc` is the local TSC halfway through the ping-pong with the shortest round-trip, which lasted
+/// `round_trip` cycles. At `MPI_Finalize`, this must be called before the call itself is
+/// registered, as the trace of the rank is written when it is.
+#[no_mangle]
+pub extern "C" fn register_clock_sync(
+    point: ClockSyncPoint,
+    tsc: Tsc,
+    offset: i64,
+    round_trip: Tsc,
+) {
+    metadata::set_clock_sync(
+        point,
+        ClockSync {
+            tsc,
+            offset,
+            round_trip,
+        },
+    );
+    append_metadata_to_journal();
+}
+
+/// Sets the date, in seconds since the epoch, at which rank 0 initialized MPI, from which the
+/// directory of the run is named unless the configuration or the batch scheduler names it.
+///
+/// This must be called by the interposition library with the same date on every rank, before
+/// `MPI_Init`/`MPI_Init_thread` is registered.
+#[no_mangle]
+pub extern "C" fn register_run_start(time: Usecs) {
+    config::set_run_start(time);
+}
+
+/// Completes the metadata of the trace with information about the MPI library.
+///
+/// This must be called by the interposition library right after `MPI_Init`/`MPI_Init_thread` has
+/// been registered.
+///
+/// # Safety
+///
+/// `library_version` must either be null or point to a valid null-terminated string, such as the
+/// one returned by `MPI_Get_library_version`.
+#[no_mangle]
+pub unsafe extern "C" fn register_mpi_info(world_size: i32, library_version: *const c_char) {
+    let library_version = if library_version.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(library_version)
+            .to_string_lossy()
+            .trim_end()
+            .to_string()
+    };
+    metadata::set_mpi_info(world_size, library_version);
+    append_metadata_to_journal();
+}
+
 #[no_mangle]
 pub extern "C" fn register_mpi_call(mpi_call: MpiCall) {
+    // The configuration is loaded on the first call, and nothing is recorded if it is invalid
+    let config = match config::load() {
+        Ok(config) => config,
+        Err(_) => return,
+    };
     let rank = mpi_call.current_rank;
-    match dispatch(mpi_call) {
+    match dispatch(mpi_call, &CallArrays::default(), config)
+        .and_then(|_| flush_if_over_budget(rank, config))
+    {
         Ok(_) => (),
         Err(e) => eprintln!("Rank {}: {e}", rank),
     }
 }
 
-fn dispatch(call: MpiCall) -> Result<(), InterpolError> {
-    match call.kind {
-        MpiCallType::Init => register_init(call.current_rank, call.tsc, call.time),
-        MpiCallType::Initthread => register_init_thread(
-            call.current_rank,
-            call.required_thread_lvl,
-            call.provided_thread_lvl,
-            call.tsc,
-            call.time,
-        ),
-        MpiCallType::Finalize => register_finalize(call.current_rank, call.tsc, call.time),
-        MpiCallType::Send => register_send(
-            call.current_rank,
-            call.partner_rank,
-            call.nb_bytes_s,
-            call.comm,
-            call.tag,
-            call.tsc,
-            call.duration,
-        ),
-        MpiCallType::Isend => register_isend(
-            call.current_rank,
-            call.partner_rank,
-            call.nb_bytes_s,
-            call.comm,
-            call.req,
-            call.tag,
-            call.tsc,
-            call.duration,
-        ),
-        MpiCallType::Recv => register_recv(
-            call.current_rank,
-            call.partner_rank,
-            call.nb_bytes_r,
-            call.comm,
-            call.tag,
-            call.tsc,
-            call.duration,
-        ),
-        MpiCallType::Irecv => register_irecv(
-            call.current_rank,
-            call.partner_rank,
-            call.nb_bytes_r,
-            call.comm,
-            call.req,
-            call.tag,
-            call.tsc,
-            call.duration,
-        ),
-        MpiCallType::Barrier => {
-            register_barrier(call.current_rank, call.comm, call.tsc, call.duration)
+/// Registers an MPI call that handles a variable number of requests or of peers, such as
+/// `MPI_Waitall` or `MPI_Alltoallv`, along with its arrays.
+///
+/// # Safety
+///
+/// Each array of `arrays` must either be null or point to as many initialized values as its
+/// length, and must not be modified until this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn register_mpi_call_arrays(mpi_call: MpiCall, arrays: MpiCallArrays) {
+    let config = match config::load() {
+        Ok(config) => config,
+        Err(_) => return,
+    };
+    let arrays = CallArrays {
+        reqs: borrow_array(arrays.reqs, arrays.nb_reqs),
+        indices: borrow_array(arrays.indices, arrays.nb_indices),
+        peer_bytes_s: borrow_array(arrays.peer_bytes_s, arrays.nb_peers_s),
+        peer_bytes_r: borrow_array(arrays.peer_bytes_r, arrays.nb_peers_r),
+    };
+    let rank = mpi_call.current_rank;
+    match dispatch(mpi_call, &arrays, config).and_then(|_| flush_if_over_budget(rank, config)) {
+        Ok(_) => (),
+        Err(e) => eprintln!("Rank {}: {e}", rank),
+    }
+}
+
+/// Whether MPI calls are currently recorded, toggled with `interpol_pause`/`interpol_resume` and
+/// `MPI_Pcontrol`.
+static TRACING: AtomicBool = AtomicBool::new(true);
+
+/// The rank of the current process, set once MPI is initialized, with which pause, resume and
+/// region markers are recorded.
+static CURRENT_RANK: AtomicI32 = AtomicI32::new(-1);
+
+/// Whether the trace of the current rank has been written, either at `MPI_Finalize` or because the
+/// process is terminating.
+static TRACE_WRITTEN: AtomicBool = AtomicBool::new(false);
+
+/// The signals on which the buffered events are written.
+const TERMINATION_SIGNALS: [c_int; 3] = [libc::SIGTERM, libc::SIGINT, libc::SIGUSR2];
+
+/// The write end of the pipe through which `on_signal` hands the signals it receives over to the
+/// thread that writes the buffered events, or -1 if the handlers are not installed.
+static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+/// Set once the handlers that write the buffered events on abnormal termination are installed.
+static TERMINATION_HANDLERS: OnceLock<()> = OnceLock::new();
+
+/// Writes the events recorded so far by the current rank, marked as incomplete in the metadata of
+/// the trace.
+///
+/// If the process is terminating (`terminal`), the events are written to the trace file of the
+/// rank, and nothing is written afterwards, even at `MPI_Finalize`. Otherwise, they are written
+/// to a new segment file, which is merged with the rest of the trace, or put back into a buffer if
+/// the segment cannot be written. Only the written copy of the metadata is marked as incomplete,
+/// as the other threads keep recording events in the meantime.
+fn write_incomplete_trace(reason: &str, terminal: bool) {
+    let current_rank = CURRENT_RANK.load(Ordering::Relaxed);
+    let config = match config::load() {
+        Ok(config) if current_rank >= 0 => config,
+        _ => return,
+    };
+    if terminal && TRACE_WRITTEN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if !terminal && TRACE_WRITTEN.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let (path, segment) = if terminal {
+        (config.rank_file(current_rank, "traces"), None)
+    } else {
+        let index = match SEGMENT_INDEX.try_lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+        let path = config.rank_file(current_rank, &format!("segment{}", *index));
+        (path, Some(index))
+    };
+
+    let events = try_gather_events(&BUFFERS);
+    eprintln!(
+        "[interpol]: rank {current_rank}: {reason}, writing the {} events recorded so far",
+        events.len()
+    );
+    let written = write_trace_file(
+        &events,
+        metadata::incomplete(reason),
+        &path,
+        current_rank,
+        config,
+    );
+    match &written {
+        Ok(_) => {
+            if let Some(mut index) = segment {
+                *index += 1;
+            }
         }
-        MpiCallType::Ibarrier => register_ibarrier(
-            call.current_rank,
-            call.comm,
-            call.req,
-            call.tsc,
-            call.duration,
-        ),
-        MpiCallType::Test => register_test(
-            call.current_rank,
-            call.req,
-            call.finished,
-            call.tsc,
-            call.duration,
-        ),
-        MpiCallType::Wait => register_wait(call.current_rank, call.req, call.tsc, call.duration),
-        MpiCallType::Ibcast => register_ibcast(
-            call.current_rank,
-            call.partner_rank,
-            call.nb_bytes_s,
-            call.comm,
-            call.req,
-            call.tsc,
-            call.duration,
-        ),
+        Err(e) => eprintln!("[interpol]: {e}"),
+    }
+    if terminal {
+        // The journal holds the events of the rank that could not be written, if any
+        if let Err(e) = close_journal(current_rank, config, written.is_ok()) {
+            eprintln!("[interpol]: {e}");
+        }
+    } else if written.is_err() {
+        restore_events(events);
+    }
+}
+
+/// Hands a signal over to the thread that writes the buffered events (see `handle_signals`).
+///
+/// Only async-signal-safe functions can be called from a signal handler, so the signal is merely
+/// written to a non-blocking pipe, and lost if the pipe is full.
+extern "C" fn on_signal(signal: c_int) {
+    let pipe = SIGNAL_PIPE.load(Ordering::Relaxed);
+    if pipe < 0 {
+        return;
+    }
+    let signal = signal as u8;
+    // SAFETY: `write` and `__errno_location` are async-signal-safe, and `errno` is restored so
+    // that the interrupted code does not see it change
+    unsafe {
+        let errno = *libc::__errno_location();
+        libc::write(pipe, (&signal as *const u8).cast(), 1);
+        *libc::__errno_location() = errno;
+    }
+}
+
+/// Writes the buffered events when a signal is received, until the pipe written by `on_signal`
+/// is closed.
+///
+/// `SIGUSR2` (which batch schedulers can send ahead of the time limit of a job) writes the events
+/// recorded so far and lets the program go on. Other signals write the trace of the rank, then are
+/// raised again with their default disposition, so that the process terminates.
+fn handle_signals(mut pipe: File) {
+    let mut signal = [0; 1];
+    while pipe.read_exact(&mut signal).is_ok() {
+        let signal = c_int::from(signal[0]);
+        let reason = match signal {
+            libc::SIGTERM => "SIGTERM",
+            libc::SIGINT => "SIGINT",
+            _ => {
+                write_incomplete_trace("SIGUSR2", false);
+                continue;
+            }
+        };
+
+        write_incomplete_trace(reason, true);
+        // SAFETY: restoring the default disposition of a signal and raising it have no
+        // precondition
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+    }
+}
+
+/// Writes the buffered events if the program exits without calling `MPI_Finalize`.
+extern "C" fn on_exit() {
+    write_incomplete_trace("exit without MPI_Finalize", true);
+}
+
+/// Installs the handlers that write the buffered events if the process terminates abnormally.
+///
+/// The events written on a signal are written by a dedicated thread, to which the signal handler
+/// hands the signal over through a pipe. The signal handlers are not installed if the pipe or the
+/// thread cannot be created.
+fn install_termination_handlers() {
+    TERMINATION_HANDLERS.get_or_init(|| {
+        // SAFETY: `atexit` only registers `on_exit`
+        unsafe {
+            libc::atexit(on_exit);
+        }
+        let mut fds = [-1; 2];
+        // SAFETY: `fds` can hold the two ends of the pipe. The write end is only used by
+        // `on_signal`, and the read end is owned by the signal thread
+        let reader = unsafe {
+            if libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) != 0 {
+                return;
+            }
+            libc::fcntl(fds[1], libc::F_SETFL, libc::O_NONBLOCK);
+            File::from_raw_fd(fds[0])
+        };
+        let spawned = std::thread::Builder::new()
+            .name(String::from("interpol-signals"))
+            .spawn(move || handle_signals(reader));
+        if spawned.is_err() {
+            // SAFETY: the write end of the pipe is not used by anything else
+            unsafe { libc::close(fds[1]) };
+            return;
+        }
+        SIGNAL_PIPE.store(fds[1], Ordering::Relaxed);
+
+        let handler: extern "C" fn(c_int) = on_signal;
+        // SAFETY: `sigaction` is zeroed then filled before being passed to the C library
+        unsafe {
+            for signal in TERMINATION_SIGNALS {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = handler as libc::sighandler_t;
+                action.sa_flags = libc::SA_RESTART;
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(signal, &action, std::ptr::null_mut());
+            }
+        }
+    });
+}
+
+/// Writes the buffered events before the MPI library aborts the job.
+///
+/// This must be called by the interposition library when `MPI_Abort` is called, before the call
+/// is forwarded to the MPI library.
+#[no_mangle]
+pub extern "C" fn register_abort(error_code: i32) {
+    write_incomplete_trace(&format!("MPI_Abort({error_code})"), true);
+}
+
+/// Sets up the pause and resume markers once MPI is initialized, and pauses tracing right away if
+/// the configuration requires it. The handlers that write the buffered events on abnormal
+/// termination are installed as well.
+fn start_tracing(current_rank: MpiRank, tsc: Tsc, config: &Config) -> Result<(), InterpolError> {
+    CURRENT_RANK.store(current_rank, Ordering::Relaxed);
+    install_termination_handlers();
+    if config.start_paused && TRACING.swap(false, Ordering::Relaxed) {
+        record(InterpolPause::new(current_rank, tsc))?;
+    }
+
+    Ok(())
+}
+
+/// Pauses or resumes tracing, recording a marker if the state changed after MPI was initialized.
+fn set_tracing(enabled: bool) -> Result<(), InterpolError> {
+    if TRACING.swap(enabled, Ordering::Relaxed) == enabled {
+        return Ok(());
+    }
+    let current_rank = CURRENT_RANK.load(Ordering::Relaxed);
+    if current_rank < 0 {
+        return Ok(());
+    }
+
+    let tsc = clock::read_tsc();
+    if enabled {
+        record(InterpolResume::new(current_rank, tsc))
+    } else {
+        record(InterpolPause::new(current_rank, tsc))
+    }
+}
+
+/// Pauses tracing: MPI calls are not recorded until `interpol_resume` is called.
+///
+/// `MPI_Pcontrol(0)` has the same effect. `MPI_Init`, `MPI_Init_thread` and `MPI_Finalize` are
+/// recorded even while tracing is paused.
+#[no_mangle]
+pub extern "C" fn interpol_pause() {
+    if let Err(e) = set_tracing(false) {
+        eprintln!("Rank {}: {e}", CURRENT_RANK.load(Ordering::Relaxed));
+    }
+}
+
+/// Resumes tracing after a call to `interpol_pause`.
+///
+/// `MPI_Pcontrol` with a non-zero level has the same effect.
+#[no_mangle]
+pub extern "C" fn interpol_resume() {
+    if let Err(e) = set_tracing(true) {
+        eprintln!("Rank {}: {e}", CURRENT_RANK.load(Ordering::Relaxed));
+    }
+}
+
+/// Returns the name of a region passed by the interposition library, or `None` if it is null.
+///
+/// # Safety
+///
+/// `name` must either be null or point to a valid null-terminated string.
+unsafe fn region_name(name: *const c_char) -> Option<String> {
+    (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned())
+}
+
+/// Enters a user-defined region on the calling thread, such as a solver iteration or a halo
+/// exchange.
+///
+/// Regions can be nested, and the MPI calls made by the thread until the matching
+/// `interpol_region_end` are tagged with the identifier of the innermost region that encloses
+/// them. A region is identified by its name, so it has the same identifier on every rank. Only
+/// regions entered after `MPI_Init` are recorded.
+///
+/// # Safety
+///
+/// `name` must either be null, in which case the call is ignored, or point to a valid
+/// null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn interpol_region_begin(name: *const c_char) {
+    let Some(name) = region_name(name) else {
+        return;
+    };
+    let id = region::region_id(&name);
+    let parent = region::enter(id);
+
+    let current_rank = CURRENT_RANK.load(Ordering::Relaxed);
+    if current_rank < 0 {
+        return;
+    }
+    let begin = RegionBegin::new(current_rank, id, parent, name, clock::read_tsc());
+    if let Err(e) = record(begin) {
+        eprintln!("Rank {current_rank}: {e}");
+    }
+}
+
+/// Leaves a region entered with `interpol_region_begin` on the calling thread.
+///
+/// Regions nested in this one that were not left yet are left along with it. Leaving a region
+/// that was not entered has no effect.
+///
+/// # Safety
+///
+/// `name` must either be null, in which case the call is ignored, or point to a valid
+/// null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn interpol_region_end(name: *const c_char) {
+    let Some(name) = region_name(name) else {
+        return;
+    };
+    let current_rank = CURRENT_RANK.load(Ordering::Relaxed);
+    let ended = region::exit(region::region_id(&name));
+    match ended.len() {
+        0 => eprintln!("Rank {current_rank}: region `{name}` ended but was never entered"),
+        1 => (),
+        n => eprintln!(
+            "Rank {current_rank}: {} region(s) nested in `{name}` were not ended",
+            n - 1
+        ),
+    }
+
+    if current_rank < 0 {
+        return;
+    }
+    let tsc = clock::read_tsc();
+    for id in ended {
+        if let Err(e) = record(RegionEnd::new(current_rank, id, tsc)) {
+            eprintln!("Rank {current_rank}: {e}");
+            return;
+        }
+    }
+}
+
+/// Returns the filters of the configuration, if any is set, to be recorded in the metadata.
+fn active_filters(config: &Config) -> Option<Filters> {
+    config.filters.is_active().then(|| config.filters.clone())
+}
+
+/// Builds the event of an MPI call and records it, unless it is dropped by the configured
+/// filters.
+fn dispatch(call: MpiCall, arrays: &CallArrays, config: &Config) -> Result<(), InterpolError> {
+    if !config
+        .filters
+        .keeps(&call.kind, call.current_rank, call.comm, call.duration)
+    {
+        return Ok(());
+    }
+    let management = matches!(
+        call.kind,
+        MpiCallType::Init | MpiCallType::Initthread | MpiCallType::Finalize
+    );
+    if !management && !TRACING.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    match call.kind {
+        MpiCallType::Init => register_init(call.current_rank, call.tsc, call.time, config),
+        MpiCallType::Initthread => register_init_thread(
+            call.current_rank,
+            call.required_thread_lvl,
+            call.provided_thread_lvl,
+            call.tsc,
+            call.time,
+            config,
+        ),
+        MpiCallType::Finalize => register_finalize(call.current_rank, call.tsc, call.time, config),
+        MpiCallType::Send => register_send(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.comm,
+            call.tag,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Isend => register_isend(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.comm,
+            call.req,
+            call.tag,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Recv => register_recv(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_r,
+            call.comm,
+            call.tag,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Irecv => register_irecv(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_r,
+            call.comm,
+            call.req,
+            call.tag,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Barrier => {
+            register_barrier(call.current_rank, call.comm, call.tsc, call.duration)
+        }
+        MpiCallType::Ibarrier => register_ibarrier(
+            call.current_rank,
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Test => register_test(
+            call.current_rank,
+            call.req,
+            call.finished,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Wait => register_wait(call.current_rank, call.req, call.tsc, call.duration),
+        MpiCallType::Testall => register_testall(
+            call.current_rank,
+            arrays.reqs.to_vec(),
+            call.finished,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Testany => register_testany(
+            call.current_rank,
+            arrays.reqs.to_vec(),
+            call.finished,
+            arrays.completed().first().copied(),
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Testsome => register_testsome(
+            call.current_rank,
+            arrays.reqs.to_vec(),
+            arrays.completed(),
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Waitall => register_waitall(
+            call.current_rank,
+            arrays.reqs.to_vec(),
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Waitany => register_waitany(
+            call.current_rank,
+            arrays.reqs.to_vec(),
+            arrays.completed().first().copied(),
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Waitsome => register_waitsome(
+            call.current_rank,
+            arrays.reqs.to_vec(),
+            arrays.completed(),
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Ibcast => register_ibcast(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
         MpiCallType::Igather => register_igather(
             call.current_rank,
             call.partner_rank,
@@ -237,465 +1168,1591 @@ fn dispatch(call: MpiCall) -> Result<(), InterpolError> {
             call.tsc,
             call.duration,
         ),
-    }
-}
-
-/// Registers an `MPI_Init` call into a static vector.
-fn register_init(current_rank: MpiRank, tsc: Tsc, time: Usecs) -> Result<(), InterpolError> {
-    let init_event = MpiInitBuilder::default()
+        MpiCallType::Bcast => register_bcast(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Reduce => register_reduce(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.op_type,
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Allreduce => register_allreduce(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.op_type,
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Gather => register_gather(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Scatter => register_scatter(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Allgather => register_allgather(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Alltoall => register_alltoall(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Iallreduce => register_iallreduce(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.op_type,
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Iallgather => register_iallgather(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Ialltoall => register_ialltoall(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::IreduceScatter => register_ireduce_scatter(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            call.op_type,
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Iscan => register_iscan(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.op_type,
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Iexscan => register_iexscan(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.op_type,
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Gatherv => register_gatherv(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            arrays.peer_bytes_r.to_vec(),
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Scatterv => register_scatterv(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            arrays.peer_bytes_s.to_vec(),
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Allgatherv => register_allgatherv(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            arrays.peer_bytes_r.to_vec(),
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Alltoallv => register_alltoallv(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            arrays.peer_bytes_s.to_vec(),
+            arrays.peer_bytes_r.to_vec(),
+            call.comm,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Igatherv => register_igatherv(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            arrays.peer_bytes_r.to_vec(),
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Iscatterv => register_iscatterv(
+            call.current_rank,
+            call.partner_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            arrays.peer_bytes_s.to_vec(),
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Iallgatherv => register_iallgatherv(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            arrays.peer_bytes_r.to_vec(),
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+        MpiCallType::Ialltoallv => register_ialltoallv(
+            call.current_rank,
+            call.nb_bytes_s,
+            call.nb_bytes_r,
+            arrays.peer_bytes_s.to_vec(),
+            arrays.peer_bytes_r.to_vec(),
+            call.comm,
+            call.req,
+            call.tsc,
+            call.duration,
+        ),
+    }
+}
+
+/// Registers an `MPI_Init` call into the buffer of the calling thread.
+fn register_init(
+    current_rank: MpiRank,
+    tsc: Tsc,
+    time: Usecs,
+    config: &Config,
+) -> Result<(), InterpolError> {
+    let init_event = MpiInitBuilder::default()
+        .current_rank(current_rank)
+        .tsc(tsc)
+        .time(time)
+        .build()?;
+
+    metadata::collect(current_rank, tsc, time, None, active_filters(config));
+    remove_stale_files(current_rank, config)?;
+    start_journal(current_rank, config);
+    record(init_event)?;
+    start_tracing(current_rank, tsc, config)
+}
+
+/// Registers an `MPI_Init_thread` call into the buffer of the calling thread.
+fn register_init_thread(
+    current_rank: MpiRank,
+    required_thread_lvl: i32,
+    provided_thread_lvl: i32,
+    tsc: Tsc,
+    time: Usecs,
+    config: &Config,
+) -> Result<(), InterpolError> {
+    let init_thread_event = MpiInitThreadBuilder::default()
+        .current_rank(current_rank)
+        .required_thread_lvl(required_thread_lvl)
+        .provided_thread_lvl(provided_thread_lvl)
+        .tsc(tsc)
+        .time(time)
+        .build()?;
+
+    metadata::collect(
+        current_rank,
+        tsc,
+        time,
+        Some(provided_thread_lvl),
+        active_filters(config),
+    );
+    remove_stale_files(current_rank, config)?;
+    start_journal(current_rank, config);
+    record(init_thread_event)?;
+    start_tracing(current_rank, tsc, config)
+}
+
+/// Registers an `MPI_Finalize` call into the buffer of the calling thread.
+///
+/// As this *should* be the final registered event, the buffers of every thread are gathered and
+/// sorted, then serialized.
+fn register_finalize(
+    current_rank: MpiRank,
+    tsc: Tsc,
+    time: Usecs,
+    config: &Config,
+) -> Result<(), InterpolError> {
+    let finalize_event = MpiFinalizeBuilder::default()
+        .current_rank(current_rank)
+        .tsc(tsc)
+        .time(time)
+        .build()?;
+
+    record(finalize_event)?;
+    metadata::calibrate_tsc(tsc, time);
+    // The trace is serialized by `interpol_serialize_rank` when it is aggregated in-band
+    if in_band(config) || TRACE_WRITTEN.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    // Serialize all events of the current rank, which makes its journal useless
+    let events = gather_events(&BUFFERS);
+    serialize(&events, current_rank, config)?;
+    close_journal(current_rank, config, true)?;
+    Ok(())
+}
+
+/// Registers an `MPI_Send` call into the buffer of the calling thread.
+fn register_send(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    comm: MpiComm,
+    tag: MpiTag,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let send_event = MpiSendBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes(nb_bytes)
+        .comm(comm)
+        .tag(tag)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(send_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Recv` call into the buffer of the calling thread.
+fn register_recv(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    comm: MpiComm,
+    tag: MpiTag,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let recv_event = MpiRecvBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes(nb_bytes)
+        .comm(comm)
+        .tag(tag)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(recv_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Isend` call into the buffer of the calling thread.
+fn register_isend(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    comm: MpiComm,
+    req: MpiReq,
+    tag: MpiTag,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let isend_event = MpiIsendBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes(nb_bytes)
+        .comm(comm)
+        .req(req)
+        .tag(tag)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(isend_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Irecv` call into the buffer of the calling thread.
+fn register_irecv(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    comm: MpiComm,
+    req: MpiReq,
+    tag: MpiTag,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let irecv_event = MpiIrecvBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes(nb_bytes)
+        .comm(comm)
+        .req(req)
+        .tag(tag)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(irecv_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Barrier` call into the buffer of the calling thread.
+fn register_barrier(
+    current_rank: MpiRank,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let barrier_event = MpiBarrierBuilder::default()
+        .current_rank(current_rank)
+        .comm(comm)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(barrier_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Ibarrier` call into the buffer of the calling thread.
+fn register_ibarrier(
+    current_rank: MpiRank,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let ibarrier_event = MpiIbarrierBuilder::default()
+        .current_rank(current_rank)
+        .comm(comm)
+        .req(req)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(ibarrier_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Test` call into the buffer of the calling thread.
+fn register_test(
+    current_rank: MpiRank,
+    req: MpiReq,
+    finished: bool,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let test_event = MpiTestBuilder::default()
+        .current_rank(current_rank)
+        .req(req)
+        .finished(finished)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(test_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Testall` call into the buffer of the calling thread.
+fn register_testall(
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    finished: bool,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let testall_event = MpiTestallBuilder::default()
+        .current_rank(current_rank)
+        .reqs(reqs)
+        .finished(finished)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(testall_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Testany` call into the buffer of the calling thread.
+fn register_testany(
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    finished: bool,
+    index: Option<u32>,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let testany_event = MpiTestanyBuilder::default()
+        .current_rank(current_rank)
+        .reqs(reqs)
+        .finished(finished)
+        .index(index)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(testany_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Testsome` call into the buffer of the calling thread.
+fn register_testsome(
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    indices: Vec<u32>,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let testsome_event = MpiTestsomeBuilder::default()
+        .current_rank(current_rank)
+        .reqs(reqs)
+        .indices(indices)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(testsome_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Wait` call into the buffer of the calling thread.
+fn register_wait(
+    current_rank: MpiRank,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let wait_event = MpiWaitBuilder::default()
+        .current_rank(current_rank)
+        .req(req)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(wait_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Waitall` call into the buffer of the calling thread.
+fn register_waitall(
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let waitall_event = MpiWaitallBuilder::default()
+        .current_rank(current_rank)
+        .reqs(reqs)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(waitall_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Waitany` call into the buffer of the calling thread.
+fn register_waitany(
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    index: Option<u32>,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let waitany_event = MpiWaitanyBuilder::default()
+        .current_rank(current_rank)
+        .reqs(reqs)
+        .index(index)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(waitany_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Waitsome` call into the buffer of the calling thread.
+fn register_waitsome(
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    indices: Vec<u32>,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let waitsome_event = MpiWaitsomeBuilder::default()
+        .current_rank(current_rank)
+        .reqs(reqs)
+        .indices(indices)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(waitsome_event)?;
+
+    Ok(())
+}
+
+fn register_ibcast(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let ibcast_event = MpiIbcastBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes(nb_bytes)
+        .comm(comm)
+        .req(req)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(ibcast_event)?;
+    Ok(())
+}
+
+fn register_igather(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let igather_event = MpiIgatherBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .comm(comm)
+        .req(req)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(igather_event)?;
+    Ok(())
+}
+
+fn register_ireduce(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let ireduce_event = MpiIreduceBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes(nb_bytes)
+        .op_type(op_type)
+        .comm(comm)
+        .req(req)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(ireduce_event)?;
+    Ok(())
+}
+
+fn register_iscatter(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let iscatter_event = MpiIscatterBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .comm(comm)
+        .req(req)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(iscatter_event)?;
+    Ok(())
+}
+
+/// Registers an `MPI_Bcast` call into the buffer of the calling thread.
+fn register_bcast(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let bcast_event = MpiBcastBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes(nb_bytes)
+        .comm(comm)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(bcast_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Reduce` call into the buffer of the calling thread.
+fn register_reduce(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let reduce_event = MpiReduceBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes(nb_bytes)
+        .op_type(op_type)
+        .comm(comm)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(reduce_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Allreduce` call into the buffer of the calling thread.
+fn register_allreduce(
+    current_rank: MpiRank,
+    nb_bytes: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let allreduce_event = MpiAllreduceBuilder::default()
+        .current_rank(current_rank)
+        .nb_bytes(nb_bytes)
+        .op_type(op_type)
+        .comm(comm)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(allreduce_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Gather` call into the buffer of the calling thread.
+fn register_gather(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let gather_event = MpiGatherBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .comm(comm)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(gather_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Scatter` call into the buffer of the calling thread.
+fn register_scatter(
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let scatter_event = MpiScatterBuilder::default()
+        .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .comm(comm)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(scatter_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Allgather` call into the buffer of the calling thread.
+fn register_allgather(
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let allgather_event = MpiAllgatherBuilder::default()
+        .current_rank(current_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .comm(comm)
+        .tsc(tsc)
+        .duration(duration)
+        .build()?;
+
+    record(allgather_event)?;
+
+    Ok(())
+}
+
+/// Registers an `MPI_Alltoall` call into the buffer of the calling thread.
+fn register_alltoall(
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let alltoall_event = MpiAlltoallBuilder::default()
         .current_rank(current_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .comm(comm)
         .tsc(tsc)
-        .time(time)
+        .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiInit` event");
-    init_event.register(&mut guard)?;
+    record(alltoall_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Init_thread` call into a static vector.
-fn register_init_thread(
+/// Registers an `MPI_Iallreduce` call into the buffer of the calling thread.
+fn register_iallreduce(
     current_rank: MpiRank,
-    required_thread_lvl: i32,
-    provided_thread_lvl: i32,
+    nb_bytes: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    req: MpiReq,
     tsc: Tsc,
-    time: Usecs,
+    duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let init_thread_event = MpiInitThreadBuilder::default()
+    let iallreduce_event = MpiIallreduceBuilder::default()
         .current_rank(current_rank)
-        .required_thread_lvl(required_thread_lvl)
-        .provided_thread_lvl(provided_thread_lvl)
+        .nb_bytes(nb_bytes)
+        .op_type(op_type)
+        .comm(comm)
+        .req(req)
         .tsc(tsc)
-        .time(time)
+        .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiInitThread` event");
-    init_thread_event.register(&mut guard)?;
+    record(iallreduce_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Finalize` call into a static vector.
-///
-/// As this *should* be the final registered event, the contents of the vector will be sorted with
-/// every other MPI processes vectors' and then serialized.
-fn register_finalize(current_rank: MpiRank, tsc: Tsc, time: Usecs) -> Result<(), InterpolError> {
-    let finalize_event = MpiFinalizeBuilder::default()
+/// Registers an `MPI_Iallgather` call into the buffer of the calling thread.
+fn register_iallgather(
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+) -> Result<(), InterpolError> {
+    let iallgather_event = MpiIallgatherBuilder::default()
         .current_rank(current_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .comm(comm)
+        .req(req)
         .tsc(tsc)
-        .time(time)
+        .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiFinalize` event");
-    finalize_event.register(&mut guard)?;
+    record(iallgather_event)?;
 
-    // Serialize all events of the current rank
-    serialize(&mut guard, current_rank)?;
     Ok(())
 }
 
-/// Registers an `MPI_Send` call into a static vector.
-fn register_send(
+/// Registers an `MPI_Ialltoall` call into the buffer of the calling thread.
+fn register_ialltoall(
     current_rank: MpiRank,
-    partner_rank: MpiRank,
-    nb_bytes: u32,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
     comm: MpiComm,
-    tag: MpiTag,
+    req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let send_event = MpiSendBuilder::default()
+    let ialltoall_event = MpiIalltoallBuilder::default()
         .current_rank(current_rank)
-        .partner_rank(partner_rank)
-        .nb_bytes(nb_bytes)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
         .comm(comm)
-        .tag(tag)
+        .req(req)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiSend` event");
-    send_event.register(&mut guard)?;
+    record(ialltoall_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Recv` call into a static vector.
-fn register_recv(
+/// Registers an `MPI_Ireduce_scatter` call into the buffer of the calling thread.
+#[allow(clippy::too_many_arguments)]
+fn register_ireduce_scatter(
     current_rank: MpiRank,
-    partner_rank: MpiRank,
-    nb_bytes: u32,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    op_type: MpiOp,
     comm: MpiComm,
-    tag: MpiTag,
+    req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let recv_event = MpiRecvBuilder::default()
+    let ireduce_scatter_event = MpiIreduceScatterBuilder::default()
         .current_rank(current_rank)
-        .partner_rank(partner_rank)
-        .nb_bytes(nb_bytes)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .op_type(op_type)
         .comm(comm)
-        .tag(tag)
+        .req(req)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiRecv` event");
-    recv_event.register(&mut guard)?;
+    record(ireduce_scatter_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Isend` call into a static vector.
-fn register_isend(
+/// Registers an `MPI_Iscan` call into the buffer of the calling thread.
+fn register_iscan(
     current_rank: MpiRank,
-    partner_rank: MpiRank,
     nb_bytes: u32,
+    op_type: MpiOp,
     comm: MpiComm,
     req: MpiReq,
-    tag: MpiTag,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let isend_event = MpiIsendBuilder::default()
+    let iscan_event = MpiIscanBuilder::default()
         .current_rank(current_rank)
-        .partner_rank(partner_rank)
         .nb_bytes(nb_bytes)
+        .op_type(op_type)
         .comm(comm)
         .req(req)
-        .tag(tag)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiIsend` event");
-    isend_event.register(&mut guard)?;
+    record(iscan_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Irecv` call into a static vector.
-fn register_irecv(
+/// Registers an `MPI_Iexscan` call into the buffer of the calling thread.
+fn register_iexscan(
     current_rank: MpiRank,
-    partner_rank: MpiRank,
     nb_bytes: u32,
+    op_type: MpiOp,
     comm: MpiComm,
     req: MpiReq,
-    tag: MpiTag,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let irecv_event = MpiIrecvBuilder::default()
+    let iexscan_event = MpiIexscanBuilder::default()
         .current_rank(current_rank)
-        .partner_rank(partner_rank)
         .nb_bytes(nb_bytes)
+        .op_type(op_type)
         .comm(comm)
         .req(req)
-        .tag(tag)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiIrecv` event");
-    irecv_event.register(&mut guard)?;
+    record(iexscan_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Barrier` call into a static vector.
-fn register_barrier(
+/// Registers an `MPI_Gatherv` call into the buffer of the calling thread.
+#[allow(clippy::too_many_arguments)]
+fn register_gatherv(
     current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_recv: Vec<u32>,
     comm: MpiComm,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let barrier_event = MpiBarrierBuilder::default()
+    let gatherv_event = MpiGathervBuilder::default()
         .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .peer_bytes_recv(peer_bytes_recv)
         .comm(comm)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiBarrier` event");
-    barrier_event.register(&mut guard)?;
+    record(gatherv_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Ibarrier` call into a static vector.
-fn register_ibarrier(
+/// Registers an `MPI_Scatterv` call into the buffer of the calling thread.
+#[allow(clippy::too_many_arguments)]
+fn register_scatterv(
     current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_send: Vec<u32>,
     comm: MpiComm,
-    req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let ibarrier_event = MpiIbarrierBuilder::default()
+    let scatterv_event = MpiScattervBuilder::default()
         .current_rank(current_rank)
+        .partner_rank(partner_rank)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .peer_bytes_send(peer_bytes_send)
         .comm(comm)
-        .req(req)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiIbarrier` event");
-    ibarrier_event.register(&mut guard)?;
+    record(scatterv_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Test` call into a static vector.
-fn register_test(
+/// Registers an `MPI_Allgatherv` call into the buffer of the calling thread.
+fn register_allgatherv(
     current_rank: MpiRank,
-    req: MpiReq,
-    finished: bool,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_recv: Vec<u32>,
+    comm: MpiComm,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let test_event = MpiTestBuilder::default()
+    let allgatherv_event = MpiAllgathervBuilder::default()
         .current_rank(current_rank)
-        .req(req)
-        .finished(finished)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .peer_bytes_recv(peer_bytes_recv)
+        .comm(comm)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiTest` event");
-    test_event.register(&mut guard)?;
+    record(allgatherv_event)?;
 
     Ok(())
 }
 
-/// Registers an `MPI_Wait` call into a static vector.
-fn register_wait(
+/// Registers an `MPI_Alltoallv` call into the buffer of the calling thread.
+#[allow(clippy::too_many_arguments)]
+fn register_alltoallv(
     current_rank: MpiRank,
-    req: MpiReq,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_send: Vec<u32>,
+    peer_bytes_recv: Vec<u32>,
+    comm: MpiComm,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let wait_event = MpiWaitBuilder::default()
+    let alltoallv_event = MpiAlltoallvBuilder::default()
         .current_rank(current_rank)
-        .req(req)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .peer_bytes_send(peer_bytes_send)
+        .peer_bytes_recv(peer_bytes_recv)
+        .comm(comm)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiWait` event");
-    wait_event.register(&mut guard)?;
+    record(alltoallv_event)?;
 
     Ok(())
 }
 
-fn register_ibcast(
+/// Registers an `MPI_Igatherv` call into the buffer of the calling thread.
+#[allow(clippy::too_many_arguments)]
+fn register_igatherv(
     current_rank: MpiRank,
     partner_rank: MpiRank,
-    nb_bytes: u32,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_recv: Vec<u32>,
     comm: MpiComm,
     req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let ibcast_event = MpiIbcastBuilder::default()
+    let igatherv_event = MpiIgathervBuilder::default()
         .current_rank(current_rank)
         .partner_rank(partner_rank)
-        .nb_bytes(nb_bytes)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .peer_bytes_recv(peer_bytes_recv)
         .comm(comm)
         .req(req)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiIbcast` event");
-    ibcast_event.register(&mut guard)?;
+    record(igatherv_event)?;
+
     Ok(())
 }
 
-fn register_igather(
+/// Registers an `MPI_Iscatterv` call into the buffer of the calling thread.
+#[allow(clippy::too_many_arguments)]
+fn register_iscatterv(
     current_rank: MpiRank,
     partner_rank: MpiRank,
     nb_bytes_send: u32,
     nb_bytes_recv: u32,
+    peer_bytes_send: Vec<u32>,
     comm: MpiComm,
     req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let igather_event = MpiIgatherBuilder::default()
+    let iscatterv_event = MpiIscattervBuilder::default()
         .current_rank(current_rank)
         .partner_rank(partner_rank)
         .nb_bytes_send(nb_bytes_send)
         .nb_bytes_recv(nb_bytes_recv)
+        .peer_bytes_send(peer_bytes_send)
         .comm(comm)
         .req(req)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiIbcast` event");
-    igather_event.register(&mut guard)?;
+    record(iscatterv_event)?;
+
     Ok(())
 }
 
-fn register_ireduce(
+/// Registers an `MPI_Iallgatherv` call into the buffer of the calling thread.
+#[allow(clippy::too_many_arguments)]
+fn register_iallgatherv(
     current_rank: MpiRank,
-    partner_rank: MpiRank,
-    nb_bytes: u32,
-    op_type: MpiOp,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_recv: Vec<u32>,
     comm: MpiComm,
     req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let ireduce_event = MpiIreduceBuilder::default()
+    let iallgatherv_event = MpiIallgathervBuilder::default()
         .current_rank(current_rank)
-        .partner_rank(partner_rank)
-        .nb_bytes(nb_bytes)
-        .op_type(op_type)
+        .nb_bytes_send(nb_bytes_send)
+        .nb_bytes_recv(nb_bytes_recv)
+        .peer_bytes_recv(peer_bytes_recv)
         .comm(comm)
         .req(req)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiIbcast` event");
-    ireduce_event.register(&mut guard)?;
+    record(iallgatherv_event)?;
+
     Ok(())
 }
 
-fn register_iscatter(
+/// Registers an `MPI_Ialltoallv` call into the buffer of the calling thread.
+#[allow(clippy::too_many_arguments)]
+fn register_ialltoallv(
     current_rank: MpiRank,
-    partner_rank: MpiRank,
     nb_bytes_send: u32,
     nb_bytes_recv: u32,
+    peer_bytes_send: Vec<u32>,
+    peer_bytes_recv: Vec<u32>,
     comm: MpiComm,
     req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
 ) -> Result<(), InterpolError> {
-    let iscatter_event = MpiIscatterBuilder::default()
+    let ialltoallv_event = MpiIalltoallvBuilder::default()
         .current_rank(current_rank)
-        .partner_rank(partner_rank)
         .nb_bytes_send(nb_bytes_send)
         .nb_bytes_recv(nb_bytes_recv)
+        .peer_bytes_send(peer_bytes_send)
+        .peer_bytes_recv(peer_bytes_recv)
         .comm(comm)
         .req(req)
         .tsc(tsc)
         .duration(duration)
         .build()?;
 
-    let mut guard = EVENTS
-        .0
-        .lock()
-        .expect("failed to take the lock on vector for `MpiIbcast` event");
-    iscatter_event.register(&mut guard)?;
+    record(ialltoallv_event)?;
+
     Ok(())
 }
 
+/// Merges the traces of every rank into a single trace, sorted by TSC, at `MPI_Finalize`.
+///
+/// Files that cannot be read are reported, and the ranks they belong to are left out of the
+/// merged trace rather than aborting the whole job.
 #[no_mangle]
 pub extern "C" fn sort_all_traces() {
-    println!("[interpol]: deserializing traces for each rank");
-    let mut all_traces = match deserialize_all_traces() {
-        Ok(t) => t,
-        Err(e) => panic!("{e}"),
+    let config = match config::load() {
+        Ok(config) if config.merge => config,
+        _ => return,
     };
 
-    print!("[interpol]: sorting traces... ");
-    let start = std::time::Instant::now();
-    all_traces.par_sort_unstable_by_key(|event| event.tsc());
-    let end = start.elapsed();
-    println!("finished in {end:?}");
-
-    let serialized_traces = match std::env::var_os("INTERPOL_OUTPUT") {
-        Some(val) => {
-            if val == "readable" {
-                println!("[interpol]: serializing all traces (pretty print)");
-                serde_json::to_string_pretty(&all_traces).expect("failed to serialize all traces")
-            } else {
-                println!("[interpol]: serializing all traces (compressed print)");
-                serde_json::to_string(&all_traces).expect("failed to serialize all traces")
-            }
+    println!("[interpol]: opening the traces of each rank");
+    let merged = merge::open_traces(config)
+        .and_then(|traces| merge_traces(traces, config, &config.merged_file()));
+    if let Err(e) = merged {
+        eprintln!("[interpol]: {e}");
+    }
+}
+
+/// Merges the traces of every rank into `output`, reporting the traces that are left out of the
+/// merged trace and the corrections applied to it.
+///
+/// This is also used by the `interpol merge` command to merge traces offline.
+pub fn merge_traces(
+    traces: TraceSet,
+    config: &Config,
+    output: &Path,
+) -> Result<Merged, InterpolError> {
+    for failure in traces.failures() {
+        eprintln!("[interpol]: skipping unreadable trace file: {failure}");
+    }
+    let mut skipped_ranks: Vec<MpiRank> =
+        traces.failures().iter().filter_map(|e| e.rank()).collect();
+    skipped_ranks.sort_unstable();
+    skipped_ranks.dedup();
+    if !skipped_ranks.is_empty() {
+        eprintln!("[interpol]: ranks {skipped_ranks:?} are left out of the merged trace");
+    }
+
+    let incomplete: Vec<String> = traces
+        .metadata()
+        .iter()
+        .flat_map(|metadata| &metadata.ranks)
+        .filter_map(|info| Some(format!("{} ({})", info.rank, info.incomplete.as_ref()?)))
+        .collect();
+    if !incomplete.is_empty() {
+        eprintln!(
+            "[interpol]: the traces of ranks {} are incomplete",
+            incomplete.join(", ")
+        );
+    }
+
+    match (config.format, config.pretty) {
+        (OutputFormat::Json, true) => println!("[interpol]: merging all traces (pretty print)"),
+        (OutputFormat::Json, false) => {
+            println!("[interpol]: merging all traces (compressed print)")
         }
-        None => {
-            println!("[interpol]: serializing all traces (compressed print)");
-            serde_json::to_string(&all_traces).expect("failed to serialize all traces")
+        (OutputFormat::Binary, _) => println!("[interpol]: merging all traces (binary)"),
+    }
+    let start = std::time::Instant::now();
+    let merged = merge::write_merged(traces, config, output).map_err(|e| e.with_file(output))?;
+    for error in &merged.errors {
+        eprintln!("[interpol]: stopped reading a trace file early, its remaining events are missing: {error}");
+    }
+    match &merged.causality {
+        Some(report) => {
+            println!("[interpol]: translated the TSC of every rank to the timebase of rank 0");
+            println!(
+                "[interpol]: matched {} messages, {} received before being sent: shifted {} events by up to {} cycles",
+                report.matched_messages, report.violations, report.shifted_events, report.max_shift
+            );
         }
+        None => eprintln!("[interpol]: clocks are not synchronized, keeping the TSC of each rank"),
+    }
+    println!(
+        "[interpol]: merged {} events in {:?}",
+        merged.events,
+        start.elapsed()
+    );
+    Ok(merged)
+}
+
+/// Returns whether the traces are aggregated in-band at `MPI_Finalize`, rather than through the
+/// filesystem.
+fn in_band(config: &Config) -> bool {
+    config.merge && config.aggregation != Aggregation::ViaFilesystem
+}
+
+/// Returns the names of the files written by the current rank in the directory of the run, one
+/// per line, in a buffer of `*len` bytes to be freed with `interpol_free_buffer`.
+///
+/// This must be called by the interposition library after `MPI_Finalize` has been registered, so
+/// that the files of every rank can be gathered on the merging rank and listed in the manifest of
+/// the run (see `interpol_write_manifest`).
+///
+/// # Safety
+///
+/// `len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn interpol_rank_files(len: *mut usize) -> *mut u8 {
+    let files = WRITTEN_FILES
+        .lock()
+        .expect("failed to take the lock on the list of written files");
+    into_buffer(manifest::file_list(&files), len)
+}
+
+/// Writes the manifest of the run, which lists the files of every rank, before the merging rank
+/// merges them with `sort_all_traces`.
+///
+/// # Safety
+///
+/// `buffers` and `lens` must point to `count` elements, the files of every rank as returned by
+/// `interpol_rank_files`, indexed by rank. Every buffer must point to the number of bytes given
+/// by its length.
+#[no_mangle]
+pub unsafe extern "C" fn interpol_write_manifest(
+    buffers: *const *const u8,
+    lens: *const usize,
+    count: usize,
+) {
+    let config = match config::load() {
+        Ok(config) => config,
+        Err(_) => return,
     };
 
-    match write_all_traces(serialized_traces) {
-        Ok(_) => (),
-        Err(e) => eprintln!("{e}"),
+    let lists = borrow_buffers(buffers, lens, count);
+    let manifest = Manifest::from_file_lists(config.resolved_run_id(), &lists);
+    let missing = manifest.missing_ranks();
+    if !missing.is_empty() {
+        eprintln!("[interpol]: ranks {missing:?} did not write any trace file");
+    }
+    let run_dir = config.run_dir();
+    let written = fs::create_dir_all(&run_dir)
+        .map_err(|e| InterpolError::from(e).with_file(&run_dir))
+        .and_then(|_| manifest.write(&config.manifest_file()));
+    if let Err(e) = written {
+        eprintln!("[interpol]: {e}");
     }
 }
 
-fn deserialize_all_traces() -> Result<Vec<Box<dyn Register>>, InterpolError> {
-    let mut all_traces = Vec::new();
+/// Returns how the traces of every rank must be aggregated at `MPI_Finalize`.
+///
+/// Traces that are not merged, or whose configuration is invalid, are written through the
+/// filesystem (in which case `sort_all_traces` does nothing).
+#[no_mangle]
+pub extern "C" fn interpol_aggregation() -> Aggregation {
+    match config::load() {
+        Ok(config) if in_band(config) => config.aggregation,
+        _ => Aggregation::ViaFilesystem,
+    }
+}
 
-    for entry in fs::read_dir(INTERPOL_DIR)? {
-        let dir_entry = entry?;
-        if dir_entry.file_name() == std::path::Path::new("interpol_traces.json") {
-            fs::remove_file(dir_entry.path())?;
-            continue;
+/// Returns the rank that merges the traces of every rank at `MPI_Finalize`.
+#[no_mangle]
+pub extern "C" fn interpol_merge_root() -> MpiRank {
+    config::load().map_or(0, |config| config.merge_root)
+}
+
+/// Serializes the events of the current rank, along with the segments it flushed, into a bundle
+/// holding its binary trace (see `merge::bundle`). The segments and the journal of the rank are
+/// removed once they are serialized.
+fn serialize_rank(current_rank: MpiRank, config: &Config) -> Result<Vec<u8>, InterpolError> {
+    let segments: Vec<PathBuf> = {
+        let index = SEGMENT_INDEX
+            .lock()
+            .expect("failed to take the lock on the segment index");
+        (0..*index)
+            .map(|i| config.rank_file(current_rank, &format!("segment{i}")))
+            .collect()
+    };
+    let mut files = Vec::new();
+    for path in &segments {
+        match merge::open_trace_file(path) {
+            Some(Ok((_, events))) => files.push(events),
+            Some(Err(e)) => eprintln!("[interpol]: {}", e.with_file(path)),
+            None => {}
         }
-        if dir_entry.path().extension().unwrap() == "json" {
-            let contents = fs::read_to_string(dir_entry.path())?;
-            let mut deserialized: Vec<Box<dyn Register>> =
-                serde_json::from_str(&contents).expect("failed to deserialize trace file contents");
-            all_traces.append(&mut deserialized);
+    }
+    files.push(Box::new(gather_events(&BUFFERS).into_iter().map(Ok)));
+
+    let header = TraceHeader::new(Some(current_rank), metadata::current());
+    let mut writer = binary::TraceWriter::new(Cursor::new(Vec::new()), &header)?;
+    for event in CausalMerge::new(files, false) {
+        match event {
+            Ok(event) => writer.write_event(&event)?,
+            Err(e) => eprintln!("[interpol]: {}", e.with_rank(Some(current_rank))),
         }
     }
+    let trace = writer.finish(&header)?.into_inner();
 
-    Ok(all_traces)
+    for path in &segments {
+        fs::remove_file(path)?;
+        remove_written_file(path);
+    }
+    close_journal(current_rank, config, true)?;
+    let mut bundle = Vec::new();
+    merge::bundle(&trace, &mut bundle);
+    Ok(bundle)
 }
 
-fn write_all_traces(serialized_traces: String) -> Result<(), InterpolError> {
-    let mut file = File::options()
-        .write(true)
-        .truncate(true)
-        .create(true)
-        .open(format!("{}/{}", INTERPOL_DIR, "interpol_traces.json"))?;
-    write!(file, "{}", serialized_traces)?;
-    Ok(())
+/// Hands a buffer over to the interposition library, which must free it with
+/// `interpol_free_buffer`.
+///
+/// # Safety
+///
+/// `len` must be valid for writes.
+unsafe fn into_buffer(buffer: Vec<u8>, len: *mut usize) -> *mut u8 {
+    *len = buffer.len();
+    Box::into_raw(buffer.into_boxed_slice()) as *mut u8
+}
+
+/// Borrows the buffers passed by the interposition library.
+///
+/// # Safety
+///
+/// `buffers` and `lens` must point to `count` elements, and every buffer must point to the
+/// number of bytes given by its length (or be null if its length is 0).
+unsafe fn borrow_buffers<'a>(
+    buffers: *const *const u8,
+    lens: *const usize,
+    count: usize,
+) -> Vec<&'a [u8]> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let buffers = std::slice::from_raw_parts(buffers, count);
+    let lens = std::slice::from_raw_parts(lens, count);
+    buffers
+        .iter()
+        .zip(lens)
+        .map(|(&buffer, &len)| match len {
+            0 => &[][..],
+            _ => std::slice::from_raw_parts(buffer, len),
+        })
+        .collect()
+}
+
+/// Serializes the trace of the current rank, to be aggregated in-band at `MPI_Finalize` (see
+/// `interpol_aggregation`), and returns a buffer of `*len` bytes.
+///
+/// This must be called by the interposition library after `MPI_Finalize` has been registered. The
+/// buffer is empty if the trace was already written (e.g. because the rank called `MPI_Abort`)
+/// or could not be serialized, and must be freed with `interpol_free_buffer`.
+///
+/// # Safety
+///
+/// `len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn interpol_serialize_rank(len: *mut usize) -> *mut u8 {
+    let current_rank = CURRENT_RANK.load(Ordering::Relaxed);
+    let bundle = match config::load() {
+        Ok(config) if current_rank >= 0 && !TRACE_WRITTEN.swap(true, Ordering::SeqCst) => {
+            serialize_rank(current_rank, config).unwrap_or_else(|e| {
+                eprintln!("[interpol]: {}", e.with_rank(Some(current_rank)));
+                Vec::new()
+            })
+        }
+        _ => Vec::new(),
+    };
+    into_buffer(bundle, len)
+}
+
+/// Merges the serialized traces of several ranks, as returned by `interpol_serialize_rank` or by
+/// this function, into a single buffer of `*len` bytes, to be freed with `interpol_free_buffer`.
+///
+/// # Safety
+///
+/// `buffers` and `lens` must point to `count` elements, and every buffer must point to the
+/// number of bytes given by its length. `len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn interpol_merge_buffers(
+    buffers: *const *const u8,
+    lens: *const usize,
+    count: usize,
+    len: *mut usize,
+) -> *mut u8 {
+    into_buffer(borrow_buffers(buffers, lens, count).concat(), len)
+}
+
+/// Merges the serialized traces of every rank into a single trace, sorted by TSC, written by the
+/// merging rank (see `interpol_merge_root`) once they have been aggregated in-band.
+///
+/// Traces that cannot be read are reported, and the ranks they belong to are left out of the
+/// merged trace.
+///
+/// # Safety
+///
+/// `buffers` and `lens` must point to `count` elements, and every buffer must point to the
+/// number of bytes given by its length.
+#[no_mangle]
+pub unsafe extern "C" fn interpol_write_merged(
+    buffers: *const *const u8,
+    lens: *const usize,
+    count: usize,
+) {
+    let config = match config::load() {
+        Ok(config) => config,
+        Err(_) => return,
+    };
+
+    println!("[interpol]: reading the traces aggregated from each rank");
+    let merged = merge::open_bundles(&borrow_buffers(buffers, lens, count))
+        .and_then(|traces| merge_traces(traces, config, &config.merged_file()));
+    if let Err(e) = merged {
+        eprintln!("[interpol]: {e}");
+    }
+}
+
+/// Frees a buffer returned by `interpol_serialize_rank` or `interpol_merge_buffers`.
+///
+/// # Safety
+///
+/// `buffer` and `len` must have been returned by one of these functions, and the buffer must not
+/// be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn interpol_free_buffer(buffer: *mut u8, len: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+        buffer, len,
+    )));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpi_events::synchronization::mpi_wait::MpiWait;
+
+    #[test]
+    fn gathers_events_from_all_threads() {
+        let buffers = Mutex::new(Vec::new());
+        std::thread::scope(|scope| {
+            for thread in 0..4 {
+                let trace = Arc::new(Trace(Mutex::new(Vec::new())));
+                buffers.lock().unwrap().push(Arc::clone(&trace));
+                scope.spawn(move || {
+                    let mut guard = trace.0.lock().unwrap();
+                    for i in 0..8 {
+                        MpiWait::new(0, thread, 4 * i + thread as Tsc, 1)
+                            .register(&mut guard)
+                            .expect("failed to register `MpiWait`");
+                    }
+                });
+            }
+        });
+
+        let events = gather_events(&buffers);
+        assert_eq!(events.len(), 32);
+        assert!(events.windows(2).all(|w| w[0].tsc() <= w[1].tsc()));
+        assert!(buffers
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|trace| trace.0.lock().unwrap().is_empty()));
+    }
 }
@@ -0,0 +1,468 @@
+use crate::binary::{self, TraceHeader};
+use crate::metadata::Metadata;
+use crate::mpi_events::MpiEvent;
+use crate::{InterpolError, InterpolErrorKind};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The magic number at the start of every journal file.
+pub const JOURNAL_MAGIC: [u8; 8] = *b"INTERJNL";
+
+/// The kind of the records that hold the JSON-encoded metadata of the rank, which only appear in
+/// journals: the kinds of events are attributed from 0.
+const METADATA_RECORD: u8 = u8::MAX;
+
+/// The length of the thread index and of the kind at the start of every record.
+const RECORD_PREFIX_LEN: usize = 5;
+
+/// An append-only file to which the events of a rank are written as they are recorded, so that
+/// they survive a crash of the process.
+///
+/// The layout of a journal is the same as the one of a binary trace (see `binary::write_trace`),
+/// with the `INTERJNL` magic number, except that every record is prefixed by its length and its
+/// CRC-32, both as little-endian `u32`. A record that was only partially written when the process
+/// died is thus detected and dropped when the journal is read back.
+///
+/// Events are appended in the order in which calls complete, so the events of concurrent threads
+/// are not sorted by TSC. Every record thus starts with the index of the thread that recorded its
+/// event, as a little-endian `u32`: the events of each thread are sorted, and can be merged.
+///
+/// As the metadata of the header is the one known when the journal is created, the metadata is
+/// appended again whenever it is completed (e.g. with the offset of the clock of the rank measured
+/// after `MPI_Init`), as a record of its own.
+///
+/// Records go through a buffered writer, which is written and synced to disk whenever
+/// `sync_interval` has elapsed since the last sync: a crash loses at most the events recorded
+/// during that interval.
+pub struct JournalWriter {
+    writer: BufWriter<File>,
+    sync_interval: Duration,
+    last_sync: Instant,
+    record: Vec<u8>,
+}
+
+impl JournalWriter {
+    /// Creates (or truncates) the journal at `path` and writes its header to disk.
+    pub fn create(
+        path: &Path,
+        header: &TraceHeader,
+        sync_interval: Duration,
+    ) -> Result<Self, InterpolError> {
+        let mut journal = Self {
+            writer: BufWriter::new(File::create(path)?),
+            sync_interval,
+            last_sync: Instant::now(),
+            record: Vec::new(),
+        };
+        binary::write_header(&mut journal.writer, &JOURNAL_MAGIC, header)?;
+        journal.sync()?;
+        Ok(journal)
+    }
+
+    /// Appends an event recorded by the given thread to the journal, syncing it to disk if the
+    /// sync interval has elapsed.
+    pub fn append(&mut self, thread: u32, event: &MpiEvent) -> Result<(), InterpolError> {
+        self.record.clear();
+        self.record.extend(thread.to_le_bytes());
+        event
+            .write_record(&mut self.record)
+            .map_err(|e| binary::from_bincode(*e, InterpolErrorKind::Serialization))?;
+        self.write_record()?;
+
+        if self.last_sync.elapsed() >= self.sync_interval {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Appends the metadata of the rank to the journal, which replaces the one of its header and
+    /// of the previous metadata records, and syncs it to disk.
+    pub fn append_metadata(&mut self, metadata: &Metadata) -> Result<(), InterpolError> {
+        self.record.clear();
+        self.record.extend(0u32.to_le_bytes());
+        self.record.push(METADATA_RECORD);
+        serde_json::to_writer(&mut self.record, metadata)
+            .map_err(|e| InterpolError::new(InterpolErrorKind::Serialization, e.to_string()))?;
+        self.write_record()?;
+        self.sync()
+    }
+
+    /// Writes the pending record, prefixed by its length and its checksum.
+    fn write_record(&mut self) -> Result<(), InterpolError> {
+        self.writer
+            .write_all(&(self.record.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&crc32(&self.record).to_le_bytes())?;
+        self.writer.write_all(&self.record)?;
+        Ok(())
+    }
+
+    /// Writes the buffered records to the journal and syncs it to disk.
+    pub fn sync(&mut self) -> Result<(), InterpolError> {
+        self.writer.flush()?;
+        self.writer.get_ref().sync_data()?;
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+}
+
+/// A summary of a journal, read without decoding its events (see `index_journal`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct JournalIndex {
+    /// The header of the journal, with the metadata of its last metadata record.
+    pub header: TraceHeader,
+    /// The number of events recorded by each thread, by thread index.
+    pub threads: BTreeMap<u32, u64>,
+    /// Whether the journal ended with an incomplete or corrupted record, which was dropped.
+    pub torn_tail: bool,
+}
+
+/// Reads through a journal written with `JournalWriter` from `reader`, to find its latest metadata
+/// and the threads whose events it holds.
+pub fn index_journal<R: Read>(reader: R) -> Result<JournalIndex, InterpolError> {
+    let (header, mut reader) = JournalReader::new(reader)?;
+    let mut threads = BTreeMap::new();
+    while let Some((thread, _)) = reader.next_event_record()? {
+        *threads.entry(thread).or_default() += 1;
+    }
+
+    Ok(JournalIndex {
+        header: TraceHeader {
+            metadata: reader.metadata,
+            ..header
+        },
+        threads,
+        torn_tail: reader.torn_tail,
+    })
+}
+
+/// A journal read one event at a time.
+///
+/// Every complete record is recovered: reading stops at the first record that is cut short by the
+/// end of the journal or whose checksum does not match, as it can only have been torn by a crash of
+/// the process. A record that is intact but cannot be decoded is an error.
+pub struct JournalReader<R> {
+    reader: BufReader<R>,
+    record: Vec<u8>,
+    /// The thread whose events are read, or `None` to read the events of every thread.
+    thread: Option<u32>,
+    /// The metadata of the last metadata record read so far, or of the header.
+    metadata: Option<Metadata>,
+    done: bool,
+    torn_tail: bool,
+}
+
+impl<R: Read> JournalReader<R> {
+    /// Reads the header of a journal from `reader`, and returns it along with a reader of the
+    /// records that follow it.
+    pub fn new(reader: R) -> Result<(TraceHeader, Self), InterpolError> {
+        let mut reader = BufReader::new(reader);
+        let header = binary::read_header(&mut reader, &JOURNAL_MAGIC, "journal")?;
+        let metadata = header.metadata.clone();
+        Ok((
+            header,
+            Self {
+                reader,
+                record: Vec::new(),
+                thread: None,
+                metadata,
+                done: false,
+                torn_tail: false,
+            },
+        ))
+    }
+
+    /// Only reads the events recorded by the given thread, which are sorted by TSC.
+    pub fn of_thread(self, thread: u32) -> Self {
+        Self {
+            thread: Some(thread),
+            ..self
+        }
+    }
+
+    /// Returns the metadata of the last metadata record read so far, or of the header if there is
+    /// none. Only complete once every record has been read.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns whether a torn record was dropped at the end of the journal. Only meaningful once
+    /// every record has been read.
+    pub fn torn_tail(&self) -> bool {
+        self.torn_tail
+    }
+
+    /// Reads the next event of the threads that are read. Returns `Ok(None)` at the end of the
+    /// journal or at its torn tail.
+    fn read_next(&mut self) -> Result<Option<MpiEvent>, InterpolError> {
+        while let Some((thread, kind)) = self.next_event_record()? {
+            if self.thread.is_some_and(|read| read != thread) {
+                continue;
+            }
+
+            // A record must hold exactly one event
+            let mut payload = &self.record[RECORD_PREFIX_LEN..];
+            let event = MpiEvent::read_record(kind, &mut payload)
+                .map_err(|e| binary::from_bincode(*e, InterpolErrorKind::Deserialization))?;
+            if !payload.is_empty() {
+                return Err(InterpolError::new(
+                    InterpolErrorKind::Deserialization,
+                    "trailing bytes after the event of a journal record",
+                ));
+            }
+            return Ok(Some(event));
+        }
+        Ok(None)
+    }
+
+    /// Reads the next record that holds an event, keeping track of the metadata records read
+    /// along the way, and returns its thread and the kind of its event. The fields of the event
+    /// are left undecoded in `record`.
+    fn next_event_record(&mut self) -> Result<Option<(u32, u8)>, InterpolError> {
+        while self.read_record()? {
+            let (thread, kind) = match self.record.get(..RECORD_PREFIX_LEN) {
+                Some(&[a, b, c, d, kind]) => (u32::from_le_bytes([a, b, c, d]), kind),
+                _ => {
+                    return Err(InterpolError::new(
+                        InterpolErrorKind::Deserialization,
+                        "truncated journal record",
+                    ))
+                }
+            };
+            if kind != METADATA_RECORD {
+                return Ok(Some((thread, kind)));
+            }
+
+            let metadata =
+                serde_json::from_slice(&self.record[RECORD_PREFIX_LEN..]).map_err(|e| {
+                    InterpolError::new(InterpolErrorKind::Deserialization, e.to_string())
+                })?;
+            self.metadata = Some(metadata);
+        }
+        Ok(None)
+    }
+
+    /// Reads the next record into `record`, returning `Ok(false)` at the end of the journal or at
+    /// its torn tail.
+    fn read_record(&mut self) -> Result<bool, InterpolError> {
+        let mut prefix = [0; 8];
+        match read_full(&mut self.reader, &mut prefix)? {
+            0 => return Ok(false),
+            n if n < prefix.len() => return Ok(self.tear()),
+            _ => {}
+        }
+
+        let len = u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as u64;
+        let checksum = u32::from_le_bytes([prefix[4], prefix[5], prefix[6], prefix[7]]);
+        // The length of a torn record may be garbage, so the record is only allocated as it is read
+        self.record.clear();
+        if self
+            .reader
+            .by_ref()
+            .take(len)
+            .read_to_end(&mut self.record)?
+            < len as usize
+            || crc32(&self.record) != checksum
+        {
+            return Ok(self.tear());
+        }
+        Ok(true)
+    }
+
+    fn tear(&mut self) -> bool {
+        self.torn_tail = true;
+        false
+    }
+}
+
+impl<R: Read> Iterator for JournalReader<R> {
+    type Item = Result<MpiEvent, InterpolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let event = self.read_next().transpose();
+        self.done = !matches!(event, Some(Ok(_)));
+        event
+    }
+}
+
+/// Computes the CRC-32 (IEEE) of `bytes`.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Reads from `reader` until `buf` is full or the end of the input is reached, and returns the
+/// number of bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, InterpolError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpi_events::{
+        management::mpi_init::MpiInit, markers::region_begin::RegionBegin,
+        point_to_point::mpi_isend::MpiIsend, synchronization::mpi_wait::MpiWait,
+    };
+    use std::fs;
+
+    fn events() -> Vec<MpiEvent> {
+        vec![
+            MpiInit::new(0, 512, 0.1).into(),
+            RegionBegin::new(0, 7, None, String::from("solver"), 768).into(),
+            MpiIsend::new(0, 1, 8, 0, 7, 42, 1024, 2048).into(),
+            MpiWait::new(0, 7, 4096, 128).into(),
+        ]
+    }
+
+    /// Writes a journal of every event, recorded alternately by two threads, and returns its
+    /// contents.
+    fn journal(name: &str) -> Vec<u8> {
+        let path = std::env::temp_dir().join(format!("{name}-{}.journal", std::process::id()));
+        let mut writer =
+            JournalWriter::create(&path, &TraceHeader::new(Some(0), None), Duration::ZERO)
+                .expect("failed to create journal");
+        for (i, event) in events().iter().enumerate() {
+            writer
+                .append(i as u32 % 2, event)
+                .expect("failed to append to journal");
+        }
+        drop(writer);
+
+        let bytes = fs::read(&path).expect("failed to read journal");
+        fs::remove_file(&path).expect("failed to remove journal");
+        bytes
+    }
+
+    /// Reads back the events of every thread of a journal, along with its index.
+    fn read(bytes: &[u8]) -> Result<(Vec<MpiEvent>, JournalIndex), InterpolError> {
+        let index = index_journal(bytes)?;
+        let (_, reader) = JournalReader::new(bytes)?;
+        Ok((reader.collect::<Result<_, _>>()?, index))
+    }
+
+    #[test]
+    fn recovers_every_record() {
+        let (recovered, index) = read(&journal("recovers")).expect("failed to read journal");
+
+        assert_eq!(index.header, TraceHeader::new(Some(0), None));
+        assert_eq!(index.threads, BTreeMap::from([(0, 2), (1, 2)]));
+        assert!(!index.torn_tail);
+        assert_eq!(recovered, events());
+    }
+
+    #[test]
+    fn reads_threads_separately() {
+        let bytes = journal("threads");
+
+        for thread in 0..2 {
+            let (_, reader) = JournalReader::new(bytes.as_slice()).expect("failed to read header");
+            let read: Vec<MpiEvent> = reader
+                .of_thread(thread)
+                .collect::<Result<_, _>>()
+                .expect("failed to read journal");
+            let written: Vec<MpiEvent> = events()
+                .into_iter()
+                .skip(thread as usize)
+                .step_by(2)
+                .collect();
+            assert_eq!(read, written);
+        }
+    }
+
+    #[test]
+    fn reads_the_last_metadata() {
+        let path = std::env::temp_dir().join(format!("metadata-{}.journal", std::process::id()));
+        let mut metadata: Metadata = serde_json::from_str(
+            "{\"interpol_version\":\"0.3.0\",\"command_line\":[],\"start_date\":\"\",\"mpi_library_version\":null,\"world_size\":null,\"provided_thread_lvl\":null,\"ranks\":[]}",
+        )
+        .expect("failed to deserialize metadata");
+        let mut writer = JournalWriter::create(
+            &path,
+            &TraceHeader::new(Some(0), Some(metadata.clone())),
+            Duration::ZERO,
+        )
+        .expect("failed to create journal");
+        for (i, event) in events().iter().enumerate() {
+            writer
+                .append(0, event)
+                .expect("failed to append to journal");
+            // The metadata is completed once MPI is initialized
+            metadata.world_size = Some(i as i32 + 1);
+            writer
+                .append_metadata(&metadata)
+                .expect("failed to append metadata to journal");
+        }
+        drop(writer);
+
+        let bytes = fs::read(&path).expect("failed to read journal");
+        fs::remove_file(&path).expect("failed to remove journal");
+        let (recovered, index) = read(&bytes).expect("failed to read journal");
+        assert_eq!(recovered, events());
+        assert_eq!(index.header.metadata, Some(metadata));
+    }
+
+    #[test]
+    fn computes_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn rejects_undecodable_records() {
+        let mut bytes = journal("undecodable");
+        let record = [0, 0, 0, 0, 0xfe];
+        bytes.extend((record.len() as u32).to_le_bytes());
+        bytes.extend(crc32(&record).to_le_bytes());
+        bytes.extend(record);
+
+        // The record is intact, so it was not torn by a crash
+        let error = read(&bytes).expect_err("read an unknown record");
+        assert_eq!(error.kind(), InterpolErrorKind::Deserialization);
+    }
+
+    #[test]
+    fn drops_torn_tail() {
+        let bytes = journal("torn");
+
+        // The last record was only partially written
+        let truncated = &bytes[..bytes.len() - 3];
+        let (recovered, index) = read(truncated).expect("failed to read journal");
+        assert_eq!(recovered, events()[..3]);
+        assert!(index.torn_tail);
+
+        // Garbage was left after the last record
+        let mut garbage = bytes.clone();
+        garbage.extend([0xff; 6]);
+        let (recovered, index) = read(&garbage).expect("failed to read journal");
+        assert_eq!(recovered, events());
+        assert!(index.torn_tail);
+
+        // The last record was corrupted
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().expect("empty journal") ^= 0xff;
+        let (recovered, index) = read(&corrupted).expect("failed to read journal");
+        assert_eq!(recovered, events()[..3]);
+        assert!(index.torn_tail);
+
+        // The header itself was torn
+        let error = read(&bytes[..10]).expect_err("read a torn header");
+        assert_eq!(error.kind(), InterpolErrorKind::Deserialization);
+    }
+}
@@ -1,21 +1,82 @@
 #![feature(try_reserve_kind)]
 
+pub mod binary;
+pub mod causality;
+pub mod clock;
+pub mod config;
+pub mod filter;
 pub mod interpol;
+pub mod journal;
+pub mod manifest;
+pub mod merge;
+pub mod metadata;
 pub mod mpi_events;
+pub mod region;
+pub mod trace_file;
 pub mod types;
 
+use std::path::{Path, PathBuf};
+use types::MpiRank;
+
 #[non_exhaustive]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InterpolErrorKind {
     Io,
     TryReserve,
     DeriveBuilder,
+    Config,
+    Serialization,
+    Deserialization,
+    Merge,
 }
 
 #[derive(Debug)]
 pub struct InterpolError {
     kind: InterpolErrorKind,
     reason: String,
+    /// The file that was being read or written, if any.
+    file: Option<PathBuf>,
+    /// The rank whose trace was being read or written, if known.
+    rank: Option<MpiRank>,
+}
+
+impl InterpolError {
+    /// Creates a new `InterpolError` of the given kind.
+    pub fn new(kind: InterpolErrorKind, reason: impl Into<String>) -> Self {
+        Self {
+            kind,
+            reason: reason.into(),
+            file: None,
+            rank: None,
+        }
+    }
+
+    /// Sets the file that was being read or written when the error occurred.
+    pub fn with_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Sets the rank whose trace was being read or written when the error occurred, if known.
+    pub fn with_rank(mut self, rank: Option<MpiRank>) -> Self {
+        self.rank = rank;
+        self
+    }
+
+    /// Returns the kind of the error.
+    pub fn kind(&self) -> InterpolErrorKind {
+        self.kind
+    }
+
+    /// Returns the file that was being read or written when the error occurred, if any.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// Returns the rank whose trace was being read or written when the error occurred, if known.
+    pub fn rank(&self) -> Option<MpiRank> {
+        self.rank
+    }
 }
 
 impl std::fmt::Display for InterpolError {
@@ -24,28 +85,34 @@ impl std::fmt::Display for InterpolError {
             InterpolErrorKind::Io => format!("I/O error: {}", self.reason),
             InterpolErrorKind::TryReserve => format!("Memory allocation error: {}", self.reason),
             InterpolErrorKind::DeriveBuilder => format!("Builder error: {}", self.reason),
+            InterpolErrorKind::Config => format!("Configuration error: {}", self.reason),
+            InterpolErrorKind::Serialization => format!("Serialization error: {}", self.reason),
+            InterpolErrorKind::Deserialization => {
+                format!("Deserialization error: {}", self.reason)
+            }
+            InterpolErrorKind::Merge => format!("Merge error: {}", self.reason),
             // _ => String::from("Unknown error kind"),
         };
 
-        write!(f, "{err_msg}")
+        write!(f, "{err_msg}")?;
+        match (&self.file, self.rank) {
+            (Some(file), Some(rank)) => write!(f, " (rank {rank}, `{}`)", file.display()),
+            (Some(file), None) => write!(f, " (`{}`)", file.display()),
+            (None, Some(rank)) => write!(f, " (rank {rank})"),
+            (None, None) => Ok(()),
+        }
     }
 }
 
 impl From<std::io::Error> for InterpolError {
     fn from(error: std::io::Error) -> Self {
-        InterpolError {
-            kind: InterpolErrorKind::Io,
-            reason: error.to_string(),
-        }
+        InterpolError::new(InterpolErrorKind::Io, error.to_string())
     }
 }
 
 impl From<std::collections::TryReserveError> for InterpolError {
     fn from(error: std::collections::TryReserveError) -> Self {
-        InterpolError {
-            kind: InterpolErrorKind::TryReserve,
-            reason: error.to_string(),
-        }
+        InterpolError::new(InterpolErrorKind::TryReserve, error.to_string())
     }
 }
 
@@ -56,10 +123,7 @@ macro_rules! impl_builder_error {
 
         impl From<$t> for InterpolError {
             fn from(error: $t) -> Self {
-                InterpolError {
-                    kind: InterpolErrorKind::DeriveBuilder,
-                    reason: error.to_string(),
-                }
+                InterpolError::new(InterpolErrorKind::DeriveBuilder, error.to_string())
             }
         }
     };
@@ -0,0 +1,135 @@
+use crate::types::MpiRank;
+use crate::{InterpolError, InterpolErrorKind};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The trace files written by a rank, named relative to the directory of the run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankFiles {
+    pub rank: MpiRank,
+    pub files: Vec<String>,
+}
+
+/// The list of the trace files that belong to a run.
+///
+/// It is written by the merging rank at `MPI_Finalize`, once the files of every rank have been
+/// gathered, and only the files it lists are merged: files left in the directory by another run
+/// that used the same run id (e.g. a rerun with fewer ranks) are ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The name of the directory of the run, if it was known.
+    pub run_id: Option<String>,
+    /// The size of `MPI_COMM_WORLD`.
+    pub world_size: usize,
+    /// The files of every rank, sorted by rank.
+    pub ranks: Vec<RankFiles>,
+}
+
+impl Manifest {
+    /// Builds the manifest of a run from the lists of files of every rank, indexed by rank, as
+    /// returned by `file_list`.
+    pub fn from_file_lists(run_id: Option<&str>, lists: &[&[u8]]) -> Self {
+        let ranks = lists
+            .iter()
+            .enumerate()
+            .map(|(rank, list)| RankFiles {
+                rank: rank as MpiRank,
+                files: String::from_utf8_lossy(list)
+                    .lines()
+                    .filter(|file| !file.is_empty())
+                    .map(String::from)
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            run_id: run_id.map(String::from),
+            world_size: lists.len(),
+            ranks,
+        }
+    }
+
+    /// Returns the ranks that did not list any file.
+    pub fn missing_ranks(&self) -> Vec<MpiRank> {
+        self.ranks
+            .iter()
+            .filter(|rank| rank.files.is_empty())
+            .map(|rank| rank.rank)
+            .collect()
+    }
+
+    /// Returns the path of every file of the manifest, along with its rank.
+    pub fn paths(&self, run_dir: &Path) -> Vec<(PathBuf, MpiRank)> {
+        self.ranks
+            .iter()
+            .flat_map(|rank| {
+                rank.files
+                    .iter()
+                    .map(move |file| (run_dir.join(file), rank.rank))
+            })
+            .collect()
+    }
+
+    /// Reads the manifest at `path`, or returns `None` if there is none.
+    pub fn read(path: &Path) -> Result<Option<Self>, InterpolError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(InterpolError::from(e).with_file(path)),
+        };
+        serde_json::from_str(&contents).map(Some).map_err(|e| {
+            InterpolError::new(InterpolErrorKind::Deserialization, e.to_string()).with_file(path)
+        })
+    }
+
+    /// Writes the manifest to `path`, replacing the manifest of a previous run if any.
+    pub fn write(&self, path: &Path) -> Result<(), InterpolError> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| {
+            InterpolError::new(InterpolErrorKind::Serialization, e.to_string()).with_file(path)
+        })?;
+        fs::write(path, json).map_err(|e| InterpolError::from(e).with_file(path))
+    }
+}
+
+/// Joins the names of the files written by a rank into a list to be gathered on the merging rank,
+/// with one name per line.
+pub fn file_list(files: &[String]) -> Vec<u8> {
+    files.join("\n").into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_manifest_from_file_lists() {
+        let first = file_list(&[
+            String::from("interpol_rank0_segment0.json"),
+            String::from("interpol_rank0_traces.json"),
+        ]);
+        let second = file_list(&[]);
+        let manifest = Manifest::from_file_lists(Some("1234.0"), &[&first, &second]);
+
+        assert_eq!(manifest.world_size, 2);
+        assert_eq!(manifest.missing_ranks(), vec![1]);
+        assert_eq!(
+            manifest.paths(Path::new("traces/1234.0")),
+            vec![
+                (
+                    PathBuf::from("traces/1234.0/interpol_rank0_segment0.json"),
+                    0
+                ),
+                (PathBuf::from("traces/1234.0/interpol_rank0_traces.json"), 0),
+            ]
+        );
+
+        let path = std::env::temp_dir().join(format!("interpol-manifest-{}", std::process::id()));
+        assert_eq!(Manifest::read(&path).unwrap(), None);
+        manifest.write(&path).unwrap();
+        let read = Manifest::read(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), Some(manifest));
+    }
+}
@@ -0,0 +1,685 @@
+use crate::binary::{self, TraceHeader};
+use crate::causality::{CausalMerge, CausalityReport};
+use crate::clock::GlobalTimebase;
+use crate::config::{Config, OutputFormat};
+use crate::journal::{self, JournalReader};
+use crate::manifest::Manifest;
+use crate::metadata::{self, Metadata};
+use crate::mpi_events::MpiEvent;
+use crate::trace_file::{JsonTraceReader, JsonTraceWriter};
+use crate::types::{MpiRank, Tsc};
+use crate::{InterpolError, InterpolErrorKind};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// The events of a trace file, or of a merge of trace files, read one at a time.
+type Events<'a> = Box<dyn Iterator<Item = Result<MpiEvent, InterpolError>> + 'a>;
+
+/// The traces of every rank, opened to be merged with `write_merged`.
+pub struct TraceSet<'a> {
+    metadata: Option<Metadata>,
+    /// The events of every trace of each rank. Traces whose rank cannot be told are grouped
+    /// together.
+    ranks: BTreeMap<Option<MpiRank>, Vec<Events<'a>>>,
+    failures: Vec<InterpolError>,
+}
+
+impl TraceSet<'_> {
+    /// Returns the metadata of the whole run, merged from the metadata of every rank.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns the errors raised while opening the traces that are left out of the merge.
+    pub fn failures(&self) -> &[InterpolError] {
+        &self.failures
+    }
+}
+
+/// The outcome of `write_merged`.
+#[derive(Debug)]
+pub struct Merged {
+    /// The number of events written to the merged trace.
+    pub events: u64,
+    /// The corrections applied to preserve causality, if the TSC of every rank was translated to
+    /// the timebase of rank 0.
+    pub causality: Option<CausalityReport>,
+    /// The errors that ended the reading of a file early, whose remaining events are missing
+    /// from the merged trace.
+    pub errors: Vec<InterpolError>,
+}
+
+/// Opens a trace file of a rank, in JSON or binary format, or a journal, and reads its metadata.
+///
+/// Returns `None` if the file is not a trace file.
+pub(crate) fn open_trace_file(
+    path: &Path,
+) -> Option<Result<(Option<Metadata>, Events<'static>), InterpolError>> {
+    let trace = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => File::open(path)
+            .map_err(InterpolError::from)
+            .and_then(|file| JsonTraceReader::new(BufReader::new(file)))
+            .map(|(metadata, events)| (metadata, Box::new(events) as Events)),
+        Some("bin") => File::open(path)
+            .map_err(InterpolError::from)
+            .and_then(binary::TraceReader::new)
+            .map(|(header, events)| (header.metadata, Box::new(events) as Events)),
+        Some("journal") => open_journal(path),
+        _ => return None,
+    };
+
+    Some(trace)
+}
+
+/// Opens a journal, which is only left behind by a rank that did not reach `MPI_Finalize`.
+///
+/// Journals are written in the order in which calls complete, but the events of each thread are
+/// sorted: the journal is read once to find its threads and its latest metadata, then the events
+/// of its threads are read side by side and merged.
+fn open_journal(path: &Path) -> Result<(Option<Metadata>, Events<'static>), InterpolError> {
+    let index = journal::index_journal(File::open(path)?)?;
+    eprintln!(
+        "[interpol]: recovered {} events from the journal `{}`",
+        index.threads.values().sum::<u64>(),
+        path.display()
+    );
+    let reason = if index.torn_tail {
+        "recovered from its journal, whose torn tail was dropped"
+    } else {
+        "recovered from its journal"
+    };
+    let mut metadata = index.header.metadata;
+    for info in metadata.iter_mut().flat_map(|metadata| &mut metadata.ranks) {
+        info.incomplete.get_or_insert_with(|| String::from(reason));
+    }
+
+    let threads = index
+        .threads
+        .keys()
+        .map(|&thread| {
+            let (_, reader) = JournalReader::new(File::open(path)?)?;
+            Ok(reader.of_thread(thread))
+        })
+        .collect::<Result<Vec<_>, InterpolError>>()?;
+    Ok((
+        metadata,
+        Box::new(CausalMerge::new(threads, false)) as Events,
+    ))
+}
+
+/// Opens the trace files of every rank, including the segment files flushed during the run, and
+/// the journals left behind by ranks that did not reach `MPI_Finalize`, which replace every other
+/// file of their rank.
+///
+/// If the run has a manifest, only the files it lists are opened. Otherwise (e.g. if the run did
+/// not reach `MPI_Finalize`), every file of the configured prefix in the directory of the run is
+/// opened. The metadata of every rank is merged into the metadata of the whole run. Files that
+/// cannot be opened are kept as failures, and every rank that has one is left out of the merge,
+/// so that no rank is partially merged. Fails if the trace of no rank could be opened.
+///
+/// Every file is kept open until the merge, and the limit on open files is raised as far as
+/// allowed so that runs with many ranks can be merged.
+pub fn open_traces(config: &Config) -> Result<TraceSet<'static>, InterpolError> {
+    raise_open_files_limit();
+
+    let run_dir = config.run_dir();
+    let paths = match Manifest::read(&config.manifest_file())? {
+        Some(manifest) => manifest
+            .paths(&run_dir)
+            .into_iter()
+            .map(|(path, rank)| (path, Some(rank)))
+            .collect(),
+        None => list_trace_files(&run_dir, config)?,
+    };
+
+    let mut files = Vec::new();
+    let mut failures = Vec::new();
+    for (path, rank) in paths {
+        match open_trace_file(&path) {
+            Some(Ok((metadata, events))) => {
+                let journal = path.extension().is_some_and(|ext| ext == "journal");
+                let file = path.clone();
+                let events = Box::new(
+                    events.map(move |event| event.map_err(|e| e.with_file(&file).with_rank(rank))),
+                ) as Events;
+                files.push((rank, journal, metadata, events));
+            }
+            Some(Err(e)) => failures.push(e.with_file(path).with_rank(rank)),
+            None => continue,
+        }
+    }
+
+    let skipped: Vec<MpiRank> = failures.iter().filter_map(|e| e.rank()).collect();
+    // The journal of a rank holds every event it recorded, including those of its other files
+    let journaled: Vec<MpiRank> = files
+        .iter()
+        .filter(|(_, journal, _, _)| *journal)
+        .filter_map(|(rank, _, _, _)| *rank)
+        .collect();
+    let mut ranks: BTreeMap<Option<MpiRank>, Vec<Events>> = BTreeMap::new();
+    let mut all_metadata = Vec::new();
+    for (rank, journal, metadata, events) in files
+        .into_iter()
+        .filter(|(rank, _, _, _)| rank.is_none_or(|rank| !skipped.contains(&rank)))
+    {
+        if journal || rank.is_none_or(|rank| !journaled.contains(&rank)) {
+            ranks.entry(rank).or_default().push(events);
+        }
+        all_metadata.extend(metadata);
+    }
+    if ranks.is_empty() {
+        return Err(
+            InterpolError::new(InterpolErrorKind::Merge, "no trace could be read")
+                .with_file(run_dir),
+        );
+    }
+
+    Ok(TraceSet {
+        metadata: metadata::merge(all_metadata),
+        ranks,
+        failures,
+    })
+}
+
+/// Lists the files of the configured prefix in the directory of a run that has no manifest, along
+/// with the rank each belongs to, if it can be told from its name.
+fn list_trace_files(
+    run_dir: &Path,
+    config: &Config,
+) -> Result<Vec<(PathBuf, Option<MpiRank>)>, InterpolError> {
+    let prefix = format!("{}_rank", config.file_prefix);
+    let entries = fs::read_dir(run_dir).map_err(|e| InterpolError::from(e).with_file(run_dir))?;
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let file_name = match path.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => continue,
+        };
+        if file_name.starts_with(&prefix) {
+            paths.push((path, config.rank_of_file(&file_name)));
+        }
+    }
+    Ok(paths)
+}
+
+/// Appends the binary trace of a single rank to a bundle.
+///
+/// Traces aggregated in-band are passed around as bundles: sequences of binary traces (see
+/// `binary::write_trace`), each prefixed by its length as a little-endian `u64`. Bundles are thus
+/// merged by concatenating them, and an empty buffer is an empty bundle.
+pub fn bundle(trace: &[u8], bundle: &mut Vec<u8>) {
+    bundle.extend_from_slice(&(trace.len() as u64).to_le_bytes());
+    bundle.extend_from_slice(trace);
+}
+
+/// Splits a bundle into the binary traces it holds (see `bundle`).
+pub fn unbundle(mut bundle: &[u8]) -> Result<Vec<&[u8]>, InterpolError> {
+    let mut traces = Vec::new();
+    while !bundle.is_empty() {
+        let (len, rest) = bundle
+            .split_first_chunk::<8>()
+            .ok_or_else(|| binary::invalid_data("truncated bundle"))?;
+        let len = usize::try_from(u64::from_le_bytes(*len))
+            .ok()
+            .filter(|&len| len <= rest.len())
+            .ok_or_else(|| binary::invalid_data("truncated bundle"))?;
+        let (trace, rest) = rest.split_at(len);
+        traces.push(trace);
+        bundle = rest;
+    }
+    Ok(traces)
+}
+
+/// Opens the traces held by bundles aggregated in-band (see `bundle`), like `open_traces` opens
+/// the trace files of every rank.
+pub fn open_bundles<'a>(bundles: &[&'a [u8]]) -> Result<TraceSet<'a>, InterpolError> {
+    let mut traces = Vec::new();
+    let mut failures = Vec::new();
+    for &bundle in bundles {
+        match unbundle(bundle) {
+            Ok(bundled) => traces.extend(bundled),
+            Err(e) => failures.push(e),
+        }
+    }
+
+    let mut ranks: BTreeMap<Option<MpiRank>, Vec<Events>> = BTreeMap::new();
+    let mut all_metadata = Vec::new();
+    for trace in traces {
+        match binary::TraceReader::new(trace) {
+            Ok((header, events)) => {
+                let rank = header.rank;
+                let events = events.map(move |event| event.map_err(|e| e.with_rank(rank)));
+                ranks.entry(rank).or_default().push(Box::new(events));
+                all_metadata.extend(header.metadata);
+            }
+            Err(e) => failures.push(e),
+        }
+    }
+    if ranks.is_empty() {
+        return Err(InterpolError::new(
+            InterpolErrorKind::Merge,
+            "no trace could be read",
+        ));
+    }
+
+    Ok(TraceSet {
+        metadata: metadata::merge(all_metadata),
+        ranks,
+        failures,
+    })
+}
+
+/// Merges the events of every rank into a single trace sorted by TSC, written incrementally to
+/// `output` (usually `Config::merged_file`) in the configured output format.
+///
+/// The files of every rank are sorted, so they are merged without ever holding more than a few
+/// events per rank in memory. If every rank was synchronized with rank 0, events are translated
+/// to its timebase and corrected to preserve causality along the way (see `CausalMerge`). As the
+/// corrections are only known once every event was written, the metadata is written first with
+/// room for them, and rewritten at the end.
+pub fn write_merged(
+    traces: TraceSet,
+    config: &Config,
+    output: &Path,
+) -> Result<Merged, InterpolError> {
+    if let Some(dir) = output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let TraceSet {
+        mut metadata,
+        ranks,
+        ..
+    } = traces;
+
+    let timebase = metadata
+        .as_ref()
+        .and_then(GlobalTimebase::new)
+        .filter(|timebase| {
+            ranks
+                .keys()
+                .all(|rank| rank.is_some_and(|rank| timebase.covers(rank)))
+        });
+    let placeholder = match (&timebase, &mut metadata) {
+        (Some(timebase), Some(metadata)) => {
+            timebase.record(metadata);
+            let mut placeholder = metadata.clone();
+            placeholder.causality = Some(CausalityReport {
+                matched_messages: usize::MAX,
+                violations: usize::MAX,
+                shifted_events: usize::MAX,
+                max_shift: Tsc::MAX,
+                total_shift: u64::MAX,
+            });
+            Some(placeholder)
+        }
+        _ => metadata.clone(),
+    };
+
+    let timebase = timebase.as_ref();
+    let ranks = ranks.into_values().map(|files| {
+        Box::new(CausalMerge::new(files, false).map(move |event| {
+            event.map(|mut event| {
+                if let Some(timebase) = timebase {
+                    timebase.translate(&mut event);
+                }
+                event
+            })
+        })) as Events
+    });
+    let mut merge = CausalMerge::new(ranks, timebase.is_some());
+
+    let file = File::options()
+        .write(true)
+        .truncate(true)
+        .create(true)
+        .open(output)?;
+    let mut output = match config.format {
+        OutputFormat::Json => Output::Json(JsonTraceWriter::new(
+            file,
+            placeholder.as_ref(),
+            config.pretty,
+            config.ns_timestamps,
+        )?),
+        OutputFormat::Binary => Output::Binary(binary::TraceWriter::new(
+            file,
+            &TraceHeader::new(None, placeholder),
+        )?),
+    };
+
+    let mut merged = Merged {
+        events: 0,
+        causality: None,
+        errors: Vec::new(),
+    };
+    for event in merge.by_ref() {
+        match event {
+            Ok(event) => {
+                output.write_event(&event)?;
+                merged.events += 1;
+            }
+            Err(e) => merged.errors.push(e),
+        }
+    }
+
+    if timebase.is_some() {
+        merged.causality = Some(merge.report().clone());
+        if let Some(metadata) = metadata.as_mut() {
+            metadata.causality = merged.causality.clone();
+        }
+    }
+    output.finish(metadata)?;
+    Ok(merged)
+}
+
+/// A merged trace being written.
+enum Output {
+    Json(JsonTraceWriter<File>),
+    Binary(binary::TraceWriter<File>),
+}
+
+impl Output {
+    fn write_event(&mut self, event: &MpiEvent) -> Result<(), InterpolError> {
+        match self {
+            Output::Json(writer) => writer.write_event(event),
+            Output::Binary(writer) => writer.write_event(event),
+        }
+    }
+
+    fn finish(self, metadata: Option<Metadata>) -> Result<(), InterpolError> {
+        match self {
+            Output::Json(writer) => writer.finish(metadata.as_ref())?,
+            Output::Binary(writer) => writer.finish(&TraceHeader::new(None, metadata))?,
+        };
+        Ok(())
+    }
+}
+
+/// Raises the soft limit on the number of open files to its hard limit.
+fn raise_open_files_limit() {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: `limit` is valid for writes, and only read back once filled by `getrlimit`
+    unsafe {
+        if libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) == 0 && limit.rlim_cur < limit.rlim_max
+        {
+            limit.rlim_cur = limit.rlim_max;
+            libc::setrlimit(libc::RLIMIT_NOFILE, &limit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ClockSync;
+    use crate::journal::JournalWriter;
+    use crate::manifest;
+    use crate::mpi_events::{management::mpi_init::MpiInit, synchronization::mpi_wait::MpiWait};
+    use crate::trace_file::{self, TraceFile};
+
+    /// Returns a configuration writing to a fresh directory named after `name`.
+    fn config(name: &str, format: OutputFormat) -> Config {
+        let config = Config {
+            output_dir: std::env::temp_dir()
+                .join(format!("interpol-{name}-{}", std::process::id())),
+            format,
+            ..Config::default()
+        };
+        fs::create_dir_all(&config.output_dir).unwrap();
+        config
+    }
+
+    /// Merges the traces of `config`, and reads the merged trace back.
+    fn merge(config: &Config) -> Result<(Vec<InterpolError>, Merged, TraceFile), InterpolError> {
+        let mut traces = open_traces(config)?;
+        let failures = std::mem::take(&mut traces.failures);
+        let merged = write_merged(traces, config, &config.merged_file())?;
+        let trace = match config.format {
+            OutputFormat::Json => {
+                TraceFile::from_json(&fs::read_to_string(config.merged_file())?).unwrap()
+            }
+            OutputFormat::Binary => {
+                let (header, events) = binary::read_trace(File::open(config.merged_file())?)?;
+                TraceFile {
+                    metadata: header.metadata,
+                    events,
+                }
+            }
+        };
+        Ok((failures, merged, trace))
+    }
+
+    #[test]
+    fn merges_sorted_files() {
+        for format in [OutputFormat::Json, OutputFormat::Binary] {
+            let config = config("merge", format);
+            let events = |rank, tscs: &[Tsc]| -> Vec<MpiEvent> {
+                tscs.iter()
+                    .map(|&tsc| MpiWait::new(rank, 7, tsc, 8).into())
+                    .collect()
+            };
+            // A long call of rank 0 completed after its first segment was flushed
+            let files = [
+                (0, "segment0", events(0, &[100, 400])),
+                (0, "traces", events(0, &[300, 500])),
+                (1, "traces", events(1, &[200, 600])),
+            ];
+            for (rank, name, events) in &files {
+                let path = config.rank_file(*rank, name);
+                let header = TraceHeader::new(Some(*rank), None);
+                match format {
+                    OutputFormat::Json => fs::write(
+                        path,
+                        trace_file::to_json(None, events, false, false).unwrap(),
+                    )
+                    .unwrap(),
+                    OutputFormat::Binary => {
+                        binary::write_trace(File::create(path).unwrap(), &header, events).unwrap()
+                    }
+                }
+            }
+
+            let result = merge(&config);
+            fs::remove_dir_all(&config.output_dir).unwrap();
+
+            let (failures, merged, trace) = result.expect("failed to merge traces");
+            assert!(failures.is_empty());
+            assert!(merged.errors.is_empty());
+            assert_eq!(merged.events, 6);
+            assert_eq!(merged.causality, None);
+            let tscs: Vec<Tsc> = trace.events.iter().map(MpiEvent::tsc).collect();
+            assert_eq!(tscs, vec![100, 200, 300, 400, 500, 600]);
+        }
+    }
+
+    #[test]
+    fn merges_bundles() {
+        let config = config("bundle", OutputFormat::Json);
+        let trace = |rank| {
+            let mut trace = Vec::new();
+            let events = [MpiWait::new(rank, 7, 100 * (3 - rank as Tsc), 8).into()];
+            binary::write_trace(&mut trace, &TraceHeader::new(Some(rank), None), &events).unwrap();
+            trace
+        };
+        let (mut first, mut second) = (Vec::new(), Vec::new());
+        bundle(&trace(0), &mut first);
+        bundle(&trace(1), &mut second);
+        bundle(&trace(2), &mut second);
+        assert_eq!(unbundle(&second).unwrap().len(), 2);
+        assert!(unbundle(&second[..second.len() - 1]).is_err());
+
+        let traces = open_bundles(&[&first, &second, &[]]).expect("failed to open bundles");
+        assert!(traces.failures().is_empty());
+        let result = write_merged(traces, &config, &config.merged_file()).and_then(|merged| {
+            let trace = TraceFile::from_json(&fs::read_to_string(config.merged_file())?);
+            Ok((merged, trace.unwrap()))
+        });
+        fs::remove_dir_all(&config.output_dir).unwrap();
+
+        let (merged, trace) = result.expect("failed to merge bundles");
+        assert_eq!(merged.events, 3);
+        let ranks: Vec<MpiRank> = trace.events.iter().map(MpiEvent::current_rank).collect();
+        assert_eq!(ranks, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn skips_unreadable_trace_files() {
+        let config = config("unreadable", OutputFormat::Json);
+        let events = |rank| -> Vec<MpiEvent> {
+            vec![
+                MpiInit::new(rank, 512, 0.1).into(),
+                MpiWait::new(rank, 7, 1024, 128).into(),
+            ]
+        };
+        for rank in 0..3 {
+            let json = trace_file::to_json(None, &events(rank), false, false).unwrap();
+            fs::write(config.rank_file(rank, "traces"), json).unwrap();
+        }
+        // A truncated segment of rank 1, and a stray file
+        fs::write(config.rank_file(1, "segment0"), "{\"metadata\":null,\"ev").unwrap();
+        fs::write(config.output_dir.join("interpol_rank2.txt"), "notes").unwrap();
+
+        let result = merge(&config);
+        fs::remove_dir_all(&config.output_dir).unwrap();
+
+        let (failures, _, trace) = result.expect("failed to merge traces");
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].kind(), InterpolErrorKind::Deserialization);
+        assert_eq!(failures[0].rank(), Some(1));
+        assert_eq!(
+            failures[0].file(),
+            Some(config.rank_file(1, "segment0").as_path())
+        );
+
+        let mut ranks: Vec<MpiRank> = trace.events.iter().map(MpiEvent::current_rank).collect();
+        ranks.sort_unstable();
+        assert_eq!(ranks, vec![0, 0, 2, 2]);
+    }
+
+    #[test]
+    fn only_merges_files_of_the_manifest() {
+        let config = config("manifest", OutputFormat::Json);
+        for rank in 0..3 {
+            let events = [MpiInit::new(rank, 512, 0.1).into()];
+            let json = trace_file::to_json(None, &events, false, false).unwrap();
+            fs::write(config.rank_file(rank, "traces"), json).unwrap();
+        }
+        // Rank 2 was written by a previous run with more ranks
+        let lists = [
+            manifest::file_list(&[String::from("interpol_rank0_traces.json")]),
+            manifest::file_list(&[String::from("interpol_rank1_traces.json")]),
+        ];
+        let lists: Vec<&[u8]> = lists.iter().map(Vec::as_slice).collect();
+        Manifest::from_file_lists(None, &lists)
+            .write(&config.manifest_file())
+            .unwrap();
+
+        let result = merge(&config);
+        fs::remove_dir_all(&config.output_dir).unwrap();
+
+        let (failures, merged, trace) = result.expect("failed to merge traces");
+        assert!(failures.is_empty());
+        assert_eq!(merged.events, 2);
+        let ranks: Vec<MpiRank> = trace.events.iter().map(MpiEvent::current_rank).collect();
+        assert_eq!(ranks, vec![0, 1]);
+    }
+
+    #[test]
+    fn merges_journals() {
+        let config = config("journal", OutputFormat::Json);
+        let json = trace_file::to_json(None, &[MpiInit::new(0, 512, 0.1).into()], false, false);
+        fs::write(config.rank_file(0, "traces"), json.unwrap()).unwrap();
+        // Rank 1 crashed after flushing a segment, which its journal also holds
+        let events: Vec<MpiEvent> = vec![
+            MpiInit::new(1, 512, 0.1).into(),
+            MpiWait::new(1, 8, 900, 512).into(),
+            MpiWait::new(1, 7, 1024, 128).into(),
+        ];
+        let json = trace_file::to_json(None, &events[..1], false, false);
+        fs::write(config.rank_file(1, "segment0"), json.unwrap()).unwrap();
+        let mut journal = JournalWriter::create(
+            &config.journal_file(1),
+            &TraceHeader::new(Some(1), None),
+            config.journal_sync,
+        )
+        .unwrap();
+        // The call of the second thread started first, but completed last
+        for (thread, event) in [(0, &events[0]), (0, &events[2]), (1, &events[1])] {
+            journal.append(thread, event).unwrap();
+        }
+        journal.sync().unwrap();
+
+        let result = merge(&config);
+        fs::remove_dir_all(&config.output_dir).unwrap();
+
+        let (failures, merged, trace) = result.expect("failed to merge traces");
+        assert!(failures.is_empty());
+        assert!(merged.errors.is_empty());
+        let ranks: Vec<(MpiRank, Tsc)> = trace
+            .events
+            .iter()
+            .map(|event| (event.current_rank(), event.tsc()))
+            .collect();
+        assert_eq!(ranks, vec![(0, 512), (1, 512), (1, 900), (1, 1024)]);
+        assert_eq!(trace.events[1..], events);
+    }
+
+    #[test]
+    fn corrects_clocks_of_journaled_ranks() {
+        let config = config("journal-clocks", OutputFormat::Json);
+        let metadata = |rank: MpiRank, synced: bool| -> Metadata {
+            let mut metadata: Metadata = serde_json::from_str(&format!(
+                "{{\"interpol_version\":\"0.3.0\",\"command_line\":[],\"start_date\":\"\",\"mpi_library_version\":null,\"world_size\":2,\"provided_thread_lvl\":null,\"ranks\":[{{\"rank\":{rank},\"hostname\":\"node{rank}\",\"pid\":1000}}]}}"
+            ))
+            .unwrap();
+            if synced {
+                metadata.ranks[0].init_sync = Some(ClockSync {
+                    tsc: 0,
+                    offset: 1_000 * rank as i64,
+                    round_trip: 40,
+                });
+            }
+            metadata
+        };
+        let json = trace_file::to_json(
+            Some(&metadata(0, true)),
+            &[MpiWait::new(0, 7, 1_500, 8).into()],
+            false,
+            false,
+        );
+        fs::write(config.rank_file(0, "traces"), json.unwrap()).unwrap();
+        // The journal of rank 1 was created before its clock was synchronized
+        let mut journal = JournalWriter::create(
+            &config.journal_file(1),
+            &TraceHeader::new(Some(1), Some(metadata(1, false))),
+            config.journal_sync,
+        )
+        .unwrap();
+        journal
+            .append(0, &MpiInit::new(1, 100, 0.1).into())
+            .unwrap();
+        journal.append_metadata(&metadata(1, true)).unwrap();
+        journal
+            .append(0, &MpiWait::new(1, 7, 1_000, 8).into())
+            .unwrap();
+        journal.sync().unwrap();
+
+        let result = merge(&config);
+        fs::remove_dir_all(&config.output_dir).unwrap();
+
+        let (failures, merged, trace) = result.expect("failed to merge traces");
+        assert!(failures.is_empty());
+        assert!(merged.causality.is_some());
+        let metadata = trace.metadata.expect("missing metadata");
+        assert!(metadata.global_timebase);
+        assert!(metadata.ranks[1].incomplete.is_some());
+        let tscs: Vec<Tsc> = trace.events.iter().map(MpiEvent::tsc).collect();
+        assert_eq!(tscs, vec![1_100, 1_500, 2_000]);
+    }
+}
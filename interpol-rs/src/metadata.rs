@@ -0,0 +1,304 @@
+use crate::causality::CausalityReport;
+use crate::clock::{ClockSync, TscClock};
+use crate::filter::Filters;
+use crate::types::{ClockSyncPoint, MpiRank, Tsc, Usecs};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// Information about the process that ran a given rank.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RankInfo {
+    pub rank: MpiRank,
+    pub hostname: String,
+    pub pid: u32,
+    /// The calibrated TSC of the rank, set at `MPI_Finalize`.
+    #[serde(default)]
+    pub tsc_clock: Option<TscClock>,
+    /// The offset of the TSC of the rank to the TSC of rank 0, measured at `MPI_Init`.
+    #[serde(default)]
+    pub init_sync: Option<ClockSync>,
+    /// The offset of the TSC of the rank to the TSC of rank 0, measured at `MPI_Finalize`.
+    #[serde(default)]
+    pub finalize_sync: Option<ClockSync>,
+    /// The drift of the TSC of the rank relative to the TSC of rank 0, in cycles per cycle, used
+    /// to translate its events to the timebase of rank 0.
+    #[serde(default)]
+    pub drift: Option<f64>,
+    /// Why the trace of the rank was written before `MPI_Finalize` (e.g. `SIGTERM` or
+    /// `MPI_Abort`), in which case it is missing the events that followed.
+    #[serde(default)]
+    pub incomplete: Option<String>,
+}
+
+/// Information about the run that produced a trace.
+///
+/// It is collected by each rank at `MPI_Init`/`MPI_Init_thread` and written alongside its events.
+/// When traces are merged, the information of every rank is gathered in `ranks`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// The version of `interpol-rs` that recorded the trace.
+    pub interpol_version: String,
+    /// The command line of the traced program.
+    pub command_line: Vec<String>,
+    /// The date at which `MPI_Init` returned, in UTC and formatted according to RFC 3339.
+    pub start_date: String,
+    /// The output of `MPI_Get_library_version`.
+    pub mpi_library_version: Option<String>,
+    /// The size of `MPI_COMM_WORLD`.
+    pub world_size: Option<i32>,
+    /// The thread level provided by `MPI_Init_thread`, if the program initialized MPI with it.
+    pub provided_thread_lvl: Option<i32>,
+    /// The host and process of each rank.
+    pub ranks: Vec<RankInfo>,
+    /// Whether the TSC of the events has been translated to the timebase of rank 0.
+    #[serde(default)]
+    pub global_timebase: bool,
+    /// The corrections applied to the events to preserve causality, if they were corrected.
+    #[serde(default)]
+    pub causality: Option<CausalityReport>,
+    /// The filters that were applied to MPI calls when recording, if any: when set, the trace
+    /// does not hold every call of the run.
+    #[serde(default)]
+    pub filters: Option<Filters>,
+}
+
+/// The metadata of the current process, collected when MPI is initialized.
+static METADATA: Mutex<Option<Metadata>> = Mutex::new(None);
+
+/// The TSC and wall-clock time measured at `MPI_Init`, used to calibrate the TSC at `MPI_Finalize`.
+static INIT_CLOCK: Mutex<Option<(Tsc, Usecs)>> = Mutex::new(None);
+
+/// Collects the metadata of the current process at `MPI_Init`/`MPI_Init_thread`.
+///
+/// `tsc` and `time` are the TSC and wall-clock time in seconds at which MPI was initialized.
+pub(crate) fn collect(
+    current_rank: MpiRank,
+    tsc: Tsc,
+    time: Usecs,
+    provided_thread_lvl: Option<i32>,
+    filters: Option<Filters>,
+) {
+    let metadata = Metadata {
+        interpol_version: env!("CARGO_PKG_VERSION").to_string(),
+        command_line: std::env::args().collect(),
+        start_date: format_date(time),
+        mpi_library_version: None,
+        world_size: None,
+        provided_thread_lvl,
+        ranks: vec![RankInfo {
+            rank: current_rank,
+            hostname: hostname(),
+            pid: std::process::id(),
+            tsc_clock: None,
+            init_sync: None,
+            finalize_sync: None,
+            drift: None,
+            incomplete: None,
+        }],
+        global_timebase: false,
+        causality: None,
+        filters,
+    };
+
+    *METADATA
+        .lock()
+        .expect("failed to take the lock on the metadata") = Some(metadata);
+    *INIT_CLOCK
+        .lock()
+        .expect("failed to take the lock on the initial clock") = Some((tsc, time));
+}
+
+/// Calibrates the TSC of the current process at `MPI_Finalize` and stores it in the metadata.
+pub(crate) fn calibrate_tsc(finalize_tsc: Tsc, finalize_time: Usecs) {
+    let init_clock = *INIT_CLOCK
+        .lock()
+        .expect("failed to take the lock on the initial clock");
+    let (init_tsc, init_time) = match init_clock {
+        Some(init_clock) => init_clock,
+        None => return,
+    };
+
+    let tsc_clock = TscClock::calibrate(init_tsc, init_time, finalize_tsc, finalize_time);
+    let mut guard = METADATA
+        .lock()
+        .expect("failed to take the lock on the metadata");
+    if let Some(info) = guard
+        .as_mut()
+        .and_then(|metadata| metadata.ranks.first_mut())
+    {
+        info.tsc_clock = tsc_clock;
+    }
+}
+
+/// Stores the clock synchronization of the current process, measured at `MPI_Init` or
+/// `MPI_Finalize`.
+pub(crate) fn set_clock_sync(point: ClockSyncPoint, sync: ClockSync) {
+    let mut guard = METADATA
+        .lock()
+        .expect("failed to take the lock on the metadata");
+    if let Some(info) = guard
+        .as_mut()
+        .and_then(|metadata| metadata.ranks.first_mut())
+    {
+        match point {
+            ClockSyncPoint::AtInit => info.init_sync = Some(sync),
+            ClockSyncPoint::AtFinalize => info.finalize_sync = Some(sync),
+        }
+    }
+}
+
+/// Completes the metadata of the current process with information given by the MPI library.
+pub(crate) fn set_mpi_info(world_size: i32, mpi_library_version: String) {
+    let mut guard = METADATA
+        .lock()
+        .expect("failed to take the lock on the metadata");
+    if let Some(metadata) = guard.as_mut() {
+        metadata.world_size = Some(world_size);
+        metadata.mpi_library_version = Some(mpi_library_version);
+    }
+}
+
+/// Returns a copy of the metadata of the current process, if MPI has been initialized.
+pub(crate) fn current() -> Option<Metadata> {
+    METADATA
+        .lock()
+        .expect("failed to take the lock on the metadata")
+        .clone()
+}
+
+/// Returns a copy of the metadata of the current process that marks its trace as incomplete for
+/// the given reason.
+///
+/// As it is called while the process is terminating or while other threads keep recording
+/// events, `None` is returned if the lock on the metadata is held.
+pub(crate) fn incomplete(reason: &str) -> Option<Metadata> {
+    let mut metadata = METADATA.try_lock().ok()?.clone()?;
+    if let Some(info) = metadata.ranks.first_mut() {
+        info.incomplete = Some(String::from(reason));
+    }
+    Some(metadata)
+}
+
+/// Merges the metadata of several ranks into the metadata of the whole run.
+///
+/// The run-wide information is taken from the first metadata, while the information of every
+/// rank is gathered and sorted by rank. When a rank appears several times (e.g. in its segment
+/// files), the information written at `MPI_Finalize`, which holds its calibrated TSC and final
+/// clock synchronization, is kept; if the rank never reached `MPI_Finalize`, the information that
+/// tells why its trace is incomplete is kept instead.
+pub fn merge(all_metadata: impl IntoIterator<Item = Metadata>) -> Option<Metadata> {
+    let mut all_metadata = all_metadata.into_iter();
+    let mut merged = all_metadata.next()?;
+    for metadata in all_metadata {
+        merged.ranks.extend(metadata.ranks);
+    }
+
+    merged.ranks.sort_by_key(|info| {
+        (
+            info.rank,
+            info.finalize_sync.is_none(),
+            info.incomplete.is_none(),
+            info.tsc_clock.is_none(),
+        )
+    });
+    merged.ranks.dedup_by_key(|info| info.rank);
+    Some(merged)
+}
+
+/// Returns the hostname of the machine running the current process.
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    // SAFETY: the buffer is valid for writes of its whole length, and is null-terminated by
+    // `gethostname` unless the name was truncated, which is handled below
+    let ret = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if ret != 0 {
+        return String::from("unknown");
+    }
+
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+/// Formats a number of seconds since the Unix epoch as an RFC 3339 date in UTC.
+pub(crate) fn format_date(time: Usecs) -> String {
+    let secs = time.max(0.0) as i64;
+    let (days, secs_of_day) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Converts a number of days since the epoch to a civil date (see Howard Hinnant's
+    // `civil_from_days` algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(rank: MpiRank) -> Metadata {
+        Metadata {
+            interpol_version: String::from("0.3.0"),
+            command_line: vec![String::from("./a.out")],
+            start_date: String::from("2022-11-04T10:20:30Z"),
+            mpi_library_version: Some(String::from("Open MPI v4.1.4")),
+            world_size: Some(2),
+            provided_thread_lvl: None,
+            ranks: vec![RankInfo {
+                rank,
+                hostname: format!("node{rank}"),
+                pid: 1000 + rank as u32,
+                tsc_clock: None,
+                init_sync: None,
+                finalize_sync: None,
+                drift: None,
+                incomplete: None,
+            }],
+            global_timebase: false,
+            causality: None,
+            filters: None,
+        }
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0.0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_date(951_782_400.5), "2000-02-29T00:00:00Z");
+        assert_eq!(format_date(1_667_557_230.0), "2022-11-04T10:20:30Z");
+    }
+
+    #[test]
+    fn merges_rank_info() {
+        let merged =
+            merge(vec![metadata(1), metadata(0), metadata(1)]).expect("failed to merge metadata");
+
+        assert_eq!(merged.world_size, Some(2));
+        assert_eq!(
+            merged.ranks,
+            vec![metadata(0).ranks[0].clone(), metadata(1).ranks[0].clone()]
+        );
+        assert_eq!(merge(Vec::new()), None);
+    }
+
+    #[test]
+    fn keeps_incomplete_rank_info() {
+        let segment = metadata(1);
+        let mut aborted = metadata(1);
+        aborted.ranks[0].incomplete = Some(String::from("MPI_Abort"));
+
+        let merged = merge(vec![segment, aborted.clone()]).expect("failed to merge metadata");
+        assert_eq!(merged.ranks, aborted.ranks);
+    }
+}
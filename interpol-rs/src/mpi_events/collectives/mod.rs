@@ -1,4 +1,25 @@
+pub mod mpi_allgather;
+pub mod mpi_allgatherv;
+pub mod mpi_allreduce;
+pub mod mpi_alltoall;
+pub mod mpi_alltoallv;
+pub mod mpi_bcast;
+pub mod mpi_gather;
+pub mod mpi_gatherv;
+pub mod mpi_iallgather;
+pub mod mpi_iallgatherv;
+pub mod mpi_iallreduce;
+pub mod mpi_ialltoall;
+pub mod mpi_ialltoallv;
 pub mod mpi_ibcast;
+pub mod mpi_iexscan;
 pub mod mpi_igather;
+pub mod mpi_igatherv;
 pub mod mpi_ireduce;
+pub mod mpi_ireduce_scatter;
+pub mod mpi_iscan;
 pub mod mpi_iscatter;
+pub mod mpi_iscatterv;
+pub mod mpi_reduce;
+pub mod mpi_scatter;
+pub mod mpi_scatterv;
@@ -0,0 +1,103 @@
+use crate::types::{MpiComm, MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Allgather` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Allgather`;
+/// - the number of bytes sent;
+/// - the number of bytes received;
+/// - the identifier of the MPI communicator;
+/// - the current value of the Time Stamp counter before the call to `MPI_Allgather`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiAllgather {
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiAllgather {
+    /// Creates a new `MpiAllgather` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        comm: MpiComm,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiAllgather {
+            current_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            comm,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiAllgatherBuilderError);
+impl_register!(MpiAllgather);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let allgather_new = MpiAllgather::new(0, 8, 64, MPI_COMM_WORLD, 1024, 2048);
+        let allgather_builder = MpiAllgatherBuilder::default()
+            .current_rank(0)
+            .nb_bytes_send(8)
+            .nb_bytes_recv(64)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiAllgather`");
+
+        assert_eq!(allgather_new, allgather_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let allgather = MpiAllgather::new(0, 8, 64, MPI_COMM_WORLD, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes_send\":8,\"nb_bytes_recv\":64,\"comm\":0,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&allgather).expect("failed to serialize `MpiAllgather`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let allgather = MpiAllgatherBuilder::default()
+            .current_rank(1)
+            .nb_bytes_send(64)
+            .nb_bytes_recv(8)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiAllgather`");
+        let serialized =
+            serde_json::to_string_pretty(&allgather).expect("failed to serialize `MpiAllgather`");
+        let deserialized: MpiAllgather =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiAllgather`");
+
+        assert_eq!(allgather, deserialized);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::types::{MpiComm, MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Allgatherv` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Allgatherv`;
+/// - the total number of bytes sent;
+/// - the total number of bytes received;
+/// - the number of bytes received from each rank, indexed by rank;
+/// - the identifier of the MPI communicator;
+/// - the current value of the Time Stamp counter before the call to `MPI_Allgatherv`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiAllgatherv {
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_recv: Vec<u32>,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiAllgatherv {
+    /// Creates a new `MpiAllgatherv` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        peer_bytes_recv: Vec<u32>,
+        comm: MpiComm,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiAllgatherv {
+            current_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            peer_bytes_recv,
+            comm,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiAllgathervBuilderError);
+impl_register!(MpiAllgatherv);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let allgatherv_new = MpiAllgatherv::new(0, 24, 24, vec![8, 16], MPI_COMM_WORLD, 1024, 2048);
+        let allgatherv_builder = MpiAllgathervBuilder::default()
+            .current_rank(0)
+            .nb_bytes_send(24)
+            .nb_bytes_recv(24)
+            .peer_bytes_recv(vec![8, 16])
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiAllgatherv`");
+
+        assert_eq!(allgatherv_new, allgatherv_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let allgatherv = MpiAllgatherv::new(0, 24, 24, vec![8, 16], MPI_COMM_WORLD, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes_send\":24,\"nb_bytes_recv\":24,\"peer_bytes_recv\":[8,16],\"comm\":0,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&allgatherv).expect("failed to serialize `MpiAllgatherv`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let allgatherv = MpiAllgathervBuilder::default()
+            .current_rank(1)
+            .nb_bytes_send(16)
+            .nb_bytes_recv(40)
+            .peer_bytes_recv(vec![16, 24])
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiAllgatherv`");
+        let serialized =
+            serde_json::to_string_pretty(&allgatherv).expect("failed to serialize `MpiAllgatherv`");
+        let deserialized: MpiAllgatherv =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiAllgatherv`");
+
+        assert_eq!(allgatherv, deserialized);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::types::{MpiComm, MpiOp, MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Allreduce` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Allreduce`;
+/// - the number of bytes reduced;
+/// - the type of MPI reduction operation;
+/// - the identifier of the MPI communicator;
+/// - the current value of the Time Stamp counter before the call to `MPI_Allreduce`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiAllreduce {
+    current_rank: MpiRank,
+    nb_bytes: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiAllreduce {
+    /// Creates a new `MpiAllreduce` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes: u32,
+        op_type: MpiOp,
+        comm: MpiComm,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiAllreduce {
+            current_rank,
+            nb_bytes,
+            op_type,
+            comm,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiAllreduceBuilderError);
+impl_register!(MpiAllreduce);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let allreduce_new = MpiAllreduce::new(0, 64, MpiOp::Sum, MPI_COMM_WORLD, 1024, 2048);
+        let allreduce_builder = MpiAllreduceBuilder::default()
+            .current_rank(0)
+            .nb_bytes(64)
+            .op_type(MpiOp::Sum)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiAllreduce`");
+
+        assert_eq!(allreduce_new, allreduce_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let allreduce = MpiAllreduce::new(0, 64, MpiOp::Sum, MPI_COMM_WORLD, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes\":64,\"op_type\":\"Sum\",\"comm\":0,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&allreduce).expect("failed to serialize `MpiAllreduce`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let allreduce = MpiAllreduceBuilder::default()
+            .current_rank(1)
+            .nb_bytes(64)
+            .op_type(MpiOp::Max)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiAllreduce`");
+        let serialized =
+            serde_json::to_string_pretty(&allreduce).expect("failed to serialize `MpiAllreduce`");
+        let deserialized: MpiAllreduce =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiAllreduce`");
+
+        assert_eq!(allreduce, deserialized);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::types::{MpiComm, MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Alltoallv` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Alltoallv`;
+/// - the total number of bytes sent;
+/// - the total number of bytes received;
+/// - the number of bytes sent to each rank, indexed by rank;
+/// - the number of bytes received from each rank, indexed by rank;
+/// - the identifier of the MPI communicator;
+/// - the current value of the Time Stamp counter before the call to `MPI_Alltoallv`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiAlltoallv {
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_send: Vec<u32>,
+    peer_bytes_recv: Vec<u32>,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiAlltoallv {
+    /// Creates a new `MpiAlltoallv` structure from the specified parameters.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        peer_bytes_send: Vec<u32>,
+        peer_bytes_recv: Vec<u32>,
+        comm: MpiComm,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiAlltoallv {
+            current_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            peer_bytes_send,
+            peer_bytes_recv,
+            comm,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiAlltoallvBuilderError);
+impl_register!(MpiAlltoallv);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let alltoallv_new = MpiAlltoallv::new(
+            0,
+            24,
+            24,
+            vec![8, 16],
+            vec![8, 16],
+            MPI_COMM_WORLD,
+            1024,
+            2048,
+        );
+        let alltoallv_builder = MpiAlltoallvBuilder::default()
+            .current_rank(0)
+            .nb_bytes_send(24)
+            .nb_bytes_recv(24)
+            .peer_bytes_send(vec![8, 16])
+            .peer_bytes_recv(vec![8, 16])
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiAlltoallv`");
+
+        assert_eq!(alltoallv_new, alltoallv_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let alltoallv = MpiAlltoallv::new(
+            0,
+            24,
+            24,
+            vec![8, 16],
+            vec![8, 16],
+            MPI_COMM_WORLD,
+            1024,
+            2048,
+        );
+        let json = String::from("{\"current_rank\":0,\"nb_bytes_send\":24,\"nb_bytes_recv\":24,\"peer_bytes_send\":[8,16],\"peer_bytes_recv\":[8,16],\"comm\":0,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&alltoallv).expect("failed to serialize `MpiAlltoallv`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let alltoallv = MpiAlltoallvBuilder::default()
+            .current_rank(1)
+            .nb_bytes_send(16)
+            .nb_bytes_recv(40)
+            .peer_bytes_send(vec![4, 12])
+            .peer_bytes_recv(vec![16, 24])
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiAlltoallv`");
+        let serialized =
+            serde_json::to_string_pretty(&alltoallv).expect("failed to serialize `MpiAlltoallv`");
+        let deserialized: MpiAlltoallv =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiAlltoallv`");
+
+        assert_eq!(alltoallv, deserialized);
+    }
+}
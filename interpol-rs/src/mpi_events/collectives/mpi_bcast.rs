@@ -0,0 +1,102 @@
+use crate::types::{MpiComm, MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Bcast` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Bcast`;
+/// - the rank of the root process;
+/// - the number of bytes broadcast;
+/// - the identifier of the MPI communicator;
+/// - the current value of the Time Stamp counter before the call to `MPI_Bcast`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiBcast {
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiBcast {
+    /// Creates a new `MpiBcast` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        partner_rank: MpiRank,
+        nb_bytes: u32,
+        comm: MpiComm,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiBcast {
+            current_rank,
+            partner_rank,
+            nb_bytes,
+            comm,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiBcastBuilderError);
+impl_register!(MpiBcast);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let bcast_new = MpiBcast::new(0, 1, 64, MPI_COMM_WORLD, 1024, 2048);
+        let bcast_builder = MpiBcastBuilder::default()
+            .current_rank(0)
+            .partner_rank(1)
+            .nb_bytes(64)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiBcast`");
+
+        assert_eq!(bcast_new, bcast_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let bcast = MpiBcast::new(0, 1, 64, MPI_COMM_WORLD, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"partner_rank\":1,\"nb_bytes\":64,\"comm\":0,\"tsc\":1024,\"duration\":2048}");
+        let serialized = serde_json::to_string(&bcast).expect("failed to serialize `MpiBcast`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let bcast = MpiBcastBuilder::default()
+            .current_rank(1)
+            .partner_rank(0)
+            .nb_bytes(64)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiBcast`");
+        let serialized =
+            serde_json::to_string_pretty(&bcast).expect("failed to serialize `MpiBcast`");
+        let deserialized: MpiBcast =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiBcast`");
+
+        assert_eq!(bcast, deserialized);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::types::{MpiComm, MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Gather` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Gather`;
+/// - the rank of the root process;
+/// - the number of bytes sent;
+/// - the number of bytes received;
+/// - the identifier of the MPI communicator;
+/// - the current value of the Time Stamp counter before the call to `MPI_Gather`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiGather {
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiGather {
+    /// Creates a new `MpiGather` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        partner_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        comm: MpiComm,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiGather {
+            current_rank,
+            partner_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            comm,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiGatherBuilderError);
+impl_register!(MpiGather);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let gather_new = MpiGather::new(0, 1, 8, 64, MPI_COMM_WORLD, 1024, 2048);
+        let gather_builder = MpiGatherBuilder::default()
+            .current_rank(0)
+            .partner_rank(1)
+            .nb_bytes_send(8)
+            .nb_bytes_recv(64)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiGather`");
+
+        assert_eq!(gather_new, gather_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let gather = MpiGather::new(0, 1, 8, 64, MPI_COMM_WORLD, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"partner_rank\":1,\"nb_bytes_send\":8,\"nb_bytes_recv\":64,\"comm\":0,\"tsc\":1024,\"duration\":2048}");
+        let serialized = serde_json::to_string(&gather).expect("failed to serialize `MpiGather`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let gather = MpiGatherBuilder::default()
+            .current_rank(1)
+            .partner_rank(0)
+            .nb_bytes_send(64)
+            .nb_bytes_recv(8)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiGather`");
+        let serialized =
+            serde_json::to_string_pretty(&gather).expect("failed to serialize `MpiGather`");
+        let deserialized: MpiGather =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiGather`");
+
+        assert_eq!(gather, deserialized);
+    }
+}
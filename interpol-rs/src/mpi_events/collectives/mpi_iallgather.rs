@@ -0,0 +1,109 @@
+use crate::types::{MpiComm, MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Iallgather` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Iallgather`;
+/// - the number of bytes sent;
+/// - the number of bytes received;
+/// - the identifier of the MPI communicator;
+/// - the identifier of the MPI request;
+/// - the current value of the Time Stamp counter before the call to `MPI_Iallgather`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiIallgather {
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiIallgather {
+    /// Creates a new `MpiIallgather` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        comm: MpiComm,
+        req: MpiReq,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiIallgather {
+            current_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            comm,
+            req,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiIallgatherBuilderError);
+impl_register!(MpiIallgather);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let iallgather_new = MpiIallgather::new(0, 8, 64, MPI_COMM_WORLD, 7, 1024, 2048);
+        let iallgather_builder = MpiIallgatherBuilder::default()
+            .current_rank(0)
+            .nb_bytes_send(8)
+            .nb_bytes_recv(64)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIallgather`");
+
+        assert_eq!(iallgather_new, iallgather_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let iallgather = MpiIallgather::new(0, 8, 64, MPI_COMM_WORLD, 7, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes_send\":8,\"nb_bytes_recv\":64,\"comm\":0,\"req\":7,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&iallgather).expect("failed to serialize `MpiIallgather`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let iallgather = MpiIallgatherBuilder::default()
+            .current_rank(1)
+            .nb_bytes_send(64)
+            .nb_bytes_recv(8)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIallgather`");
+        let serialized =
+            serde_json::to_string_pretty(&iallgather).expect("failed to serialize `MpiIallgather`");
+        let deserialized: MpiIallgather =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiIallgather`");
+
+        assert_eq!(iallgather, deserialized);
+    }
+}
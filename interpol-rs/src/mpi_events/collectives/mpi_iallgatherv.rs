@@ -0,0 +1,118 @@
+use crate::types::{MpiComm, MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Iallgatherv` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Iallgatherv`;
+/// - the total number of bytes sent;
+/// - the total number of bytes received;
+/// - the number of bytes received from each rank, indexed by rank;
+/// - the identifier of the MPI communicator;
+/// - the identifier of the MPI request;
+/// - the current value of the Time Stamp counter before the call to `MPI_Iallgatherv`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiIallgatherv {
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_recv: Vec<u32>,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiIallgatherv {
+    /// Creates a new `MpiIallgatherv` structure from the specified parameters.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        peer_bytes_recv: Vec<u32>,
+        comm: MpiComm,
+        req: MpiReq,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiIallgatherv {
+            current_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            peer_bytes_recv,
+            comm,
+            req,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiIallgathervBuilderError);
+impl_register!(MpiIallgatherv);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let iallgatherv_new =
+            MpiIallgatherv::new(0, 24, 24, vec![8, 16], MPI_COMM_WORLD, 7, 1024, 2048);
+        let iallgatherv_builder = MpiIallgathervBuilder::default()
+            .current_rank(0)
+            .nb_bytes_send(24)
+            .nb_bytes_recv(24)
+            .peer_bytes_recv(vec![8, 16])
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIallgatherv`");
+
+        assert_eq!(iallgatherv_new, iallgatherv_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let iallgatherv =
+            MpiIallgatherv::new(0, 24, 24, vec![8, 16], MPI_COMM_WORLD, 7, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes_send\":24,\"nb_bytes_recv\":24,\"peer_bytes_recv\":[8,16],\"comm\":0,\"req\":7,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&iallgatherv).expect("failed to serialize `MpiIallgatherv`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let iallgatherv = MpiIallgathervBuilder::default()
+            .current_rank(1)
+            .nb_bytes_send(16)
+            .nb_bytes_recv(40)
+            .peer_bytes_recv(vec![16, 24])
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIallgatherv`");
+        let serialized = serde_json::to_string_pretty(&iallgatherv)
+            .expect("failed to serialize `MpiIallgatherv`");
+        let deserialized: MpiIallgatherv =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiIallgatherv`");
+
+        assert_eq!(iallgatherv, deserialized);
+    }
+}
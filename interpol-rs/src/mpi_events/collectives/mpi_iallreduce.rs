@@ -0,0 +1,109 @@
+use crate::types::{MpiComm, MpiOp, MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Iallreduce` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Iallreduce`;
+/// - the number of bytes reduced;
+/// - the type of MPI reduction operation;
+/// - the identifier of the MPI communicator;
+/// - the identifier of the MPI request;
+/// - the current value of the Time Stamp counter before the call to `MPI_Iallreduce`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiIallreduce {
+    current_rank: MpiRank,
+    nb_bytes: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiIallreduce {
+    /// Creates a new `MpiIallreduce` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes: u32,
+        op_type: MpiOp,
+        comm: MpiComm,
+        req: MpiReq,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiIallreduce {
+            current_rank,
+            nb_bytes,
+            op_type,
+            comm,
+            req,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiIallreduceBuilderError);
+impl_register!(MpiIallreduce);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let iallreduce_new = MpiIallreduce::new(0, 64, MpiOp::Sum, MPI_COMM_WORLD, 7, 1024, 2048);
+        let iallreduce_builder = MpiIallreduceBuilder::default()
+            .current_rank(0)
+            .nb_bytes(64)
+            .op_type(MpiOp::Sum)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIallreduce`");
+
+        assert_eq!(iallreduce_new, iallreduce_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let iallreduce = MpiIallreduce::new(0, 64, MpiOp::Sum, MPI_COMM_WORLD, 7, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes\":64,\"op_type\":\"Sum\",\"comm\":0,\"req\":7,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&iallreduce).expect("failed to serialize `MpiIallreduce`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let iallreduce = MpiIallreduceBuilder::default()
+            .current_rank(1)
+            .nb_bytes(64)
+            .op_type(MpiOp::Max)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIallreduce`");
+        let serialized =
+            serde_json::to_string_pretty(&iallreduce).expect("failed to serialize `MpiIallreduce`");
+        let deserialized: MpiIallreduce =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiIallreduce`");
+
+        assert_eq!(iallreduce, deserialized);
+    }
+}
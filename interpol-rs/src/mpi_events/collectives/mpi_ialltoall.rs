@@ -0,0 +1,109 @@
+use crate::types::{MpiComm, MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Ialltoall` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Ialltoall`;
+/// - the number of bytes sent;
+/// - the number of bytes received;
+/// - the identifier of the MPI communicator;
+/// - the identifier of the MPI request;
+/// - the current value of the Time Stamp counter before the call to `MPI_Ialltoall`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiIalltoall {
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiIalltoall {
+    /// Creates a new `MpiIalltoall` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        comm: MpiComm,
+        req: MpiReq,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiIalltoall {
+            current_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            comm,
+            req,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiIalltoallBuilderError);
+impl_register!(MpiIalltoall);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let ialltoall_new = MpiIalltoall::new(0, 8, 64, MPI_COMM_WORLD, 7, 1024, 2048);
+        let ialltoall_builder = MpiIalltoallBuilder::default()
+            .current_rank(0)
+            .nb_bytes_send(8)
+            .nb_bytes_recv(64)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIalltoall`");
+
+        assert_eq!(ialltoall_new, ialltoall_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let ialltoall = MpiIalltoall::new(0, 8, 64, MPI_COMM_WORLD, 7, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes_send\":8,\"nb_bytes_recv\":64,\"comm\":0,\"req\":7,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&ialltoall).expect("failed to serialize `MpiIalltoall`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let ialltoall = MpiIalltoallBuilder::default()
+            .current_rank(1)
+            .nb_bytes_send(64)
+            .nb_bytes_recv(8)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIalltoall`");
+        let serialized =
+            serde_json::to_string_pretty(&ialltoall).expect("failed to serialize `MpiIalltoall`");
+        let deserialized: MpiIalltoall =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiIalltoall`");
+
+        assert_eq!(ialltoall, deserialized);
+    }
+}
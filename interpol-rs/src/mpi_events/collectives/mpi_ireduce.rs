@@ -1,4 +1,4 @@
-use crate::types::{MpiComm, MpiOp, MpiRank, MpiReq, Tsc};
+use crate::types::{MpiComm, MpiOp, MpiRank, MpiReq, RegionId, Tsc};
 use crate::{impl_builder_error, impl_register};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -25,6 +25,10 @@ pub struct MpiIreduce {
     req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
 }
 
 impl MpiIreduce {
@@ -48,6 +52,7 @@ impl MpiIreduce {
             req,
             tsc,
             duration,
+            region: None,
         }
     }
 }
@@ -0,0 +1,118 @@
+use crate::types::{MpiComm, MpiOp, MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Ireduce_scatter` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Ireduce_scatter`;
+/// - the number of bytes reduced, i.e. the sum of the number of bytes scattered to every rank;
+/// - the number of bytes of the result scattered to the process;
+/// - the type of MPI reduction operation;
+/// - the identifier of the MPI communicator;
+/// - the identifier of the MPI request;
+/// - the current value of the Time Stamp counter before the call to `MPI_Ireduce_scatter`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiIreduceScatter {
+    current_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiIreduceScatter {
+    /// Creates a new `MpiIreduceScatter` structure from the specified parameters.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        op_type: MpiOp,
+        comm: MpiComm,
+        req: MpiReq,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiIreduceScatter {
+            current_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            op_type,
+            comm,
+            req,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiIreduceScatterBuilderError);
+impl_register!(MpiIreduceScatter);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let ireduce_scatter_new =
+            MpiIreduceScatter::new(0, 8, 64, MpiOp::Sum, MPI_COMM_WORLD, 7, 1024, 2048);
+        let ireduce_scatter_builder = MpiIreduceScatterBuilder::default()
+            .current_rank(0)
+            .nb_bytes_send(8)
+            .nb_bytes_recv(64)
+            .op_type(MpiOp::Sum)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIreduceScatter`");
+
+        assert_eq!(ireduce_scatter_new, ireduce_scatter_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let ireduce_scatter =
+            MpiIreduceScatter::new(0, 8, 64, MpiOp::Sum, MPI_COMM_WORLD, 7, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes_send\":8,\"nb_bytes_recv\":64,\"op_type\":\"Sum\",\"comm\":0,\"req\":7,\"tsc\":1024,\"duration\":2048}");
+        let serialized = serde_json::to_string(&ireduce_scatter)
+            .expect("failed to serialize `MpiIreduceScatter`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let ireduce_scatter = MpiIreduceScatterBuilder::default()
+            .current_rank(1)
+            .nb_bytes_send(64)
+            .nb_bytes_recv(8)
+            .op_type(MpiOp::Max)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIreduceScatter`");
+        let serialized = serde_json::to_string_pretty(&ireduce_scatter)
+            .expect("failed to serialize `MpiIreduceScatter`");
+        let deserialized: MpiIreduceScatter =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiIreduceScatter`");
+
+        assert_eq!(ireduce_scatter, deserialized);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::types::{MpiComm, MpiOp, MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Iscan` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Iscan`;
+/// - the number of bytes reduced;
+/// - the type of MPI reduction operation;
+/// - the identifier of the MPI communicator;
+/// - the identifier of the MPI request;
+/// - the current value of the Time Stamp counter before the call to `MPI_Iscan`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiIscan {
+    current_rank: MpiRank,
+    nb_bytes: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiIscan {
+    /// Creates a new `MpiIscan` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        nb_bytes: u32,
+        op_type: MpiOp,
+        comm: MpiComm,
+        req: MpiReq,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiIscan {
+            current_rank,
+            nb_bytes,
+            op_type,
+            comm,
+            req,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiIscanBuilderError);
+impl_register!(MpiIscan);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let iscan_new = MpiIscan::new(0, 64, MpiOp::Sum, MPI_COMM_WORLD, 7, 1024, 2048);
+        let iscan_builder = MpiIscanBuilder::default()
+            .current_rank(0)
+            .nb_bytes(64)
+            .op_type(MpiOp::Sum)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIscan`");
+
+        assert_eq!(iscan_new, iscan_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let iscan = MpiIscan::new(0, 64, MpiOp::Sum, MPI_COMM_WORLD, 7, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"nb_bytes\":64,\"op_type\":\"Sum\",\"comm\":0,\"req\":7,\"tsc\":1024,\"duration\":2048}");
+        let serialized = serde_json::to_string(&iscan).expect("failed to serialize `MpiIscan`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let iscan = MpiIscanBuilder::default()
+            .current_rank(1)
+            .nb_bytes(64)
+            .op_type(MpiOp::Max)
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIscan`");
+        let serialized =
+            serde_json::to_string_pretty(&iscan).expect("failed to serialize `MpiIscan`");
+        let deserialized: MpiIscan =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiIscan`");
+
+        assert_eq!(iscan, deserialized);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::types::{MpiComm, MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Iscatterv` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Iscatterv`;
+/// - the rank of the root process;
+/// - the total number of bytes sent;
+/// - the total number of bytes received;
+/// - the number of bytes sent to each rank, indexed by rank (only on the root process);
+/// - the identifier of the MPI communicator;
+/// - the identifier of the MPI request;
+/// - the current value of the Time Stamp counter before the call to `MPI_Iscatterv`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiIscatterv {
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_send: Vec<u32>,
+    comm: MpiComm,
+    req: MpiReq,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiIscatterv {
+    /// Creates a new `MpiIscatterv` structure from the specified parameters.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        current_rank: MpiRank,
+        partner_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        peer_bytes_send: Vec<u32>,
+        comm: MpiComm,
+        req: MpiReq,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiIscatterv {
+            current_rank,
+            partner_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            peer_bytes_send,
+            comm,
+            req,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiIscattervBuilderError);
+impl_register!(MpiIscatterv);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let iscatterv_new =
+            MpiIscatterv::new(0, 0, 24, 24, vec![8, 16], MPI_COMM_WORLD, 7, 1024, 2048);
+        let iscatterv_builder = MpiIscattervBuilder::default()
+            .current_rank(0)
+            .partner_rank(0)
+            .nb_bytes_send(24)
+            .nb_bytes_recv(24)
+            .peer_bytes_send(vec![8, 16])
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIscatterv`");
+
+        assert_eq!(iscatterv_new, iscatterv_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let iscatterv = MpiIscatterv::new(0, 0, 24, 24, vec![8, 16], MPI_COMM_WORLD, 7, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"partner_rank\":0,\"nb_bytes_send\":24,\"nb_bytes_recv\":24,\"peer_bytes_send\":[8,16],\"comm\":0,\"req\":7,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&iscatterv).expect("failed to serialize `MpiIscatterv`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let iscatterv = MpiIscattervBuilder::default()
+            .current_rank(1)
+            .partner_rank(0)
+            .nb_bytes_send(16)
+            .nb_bytes_recv(40)
+            .peer_bytes_send(vec![4, 12])
+            .comm(MPI_COMM_WORLD)
+            .req(7)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiIscatterv`");
+        let serialized =
+            serde_json::to_string_pretty(&iscatterv).expect("failed to serialize `MpiIscatterv`");
+        let deserialized: MpiIscatterv =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiIscatterv`");
+
+        assert_eq!(iscatterv, deserialized);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::types::{MpiComm, MpiOp, MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Reduce` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Reduce`;
+/// - the rank of the root process;
+/// - the number of bytes reduced;
+/// - the type of MPI reduction operation;
+/// - the identifier of the MPI communicator;
+/// - the current value of the Time Stamp counter before the call to `MPI_Reduce`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiReduce {
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes: u32,
+    op_type: MpiOp,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiReduce {
+    /// Creates a new `MpiReduce` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        partner_rank: MpiRank,
+        nb_bytes: u32,
+        op_type: MpiOp,
+        comm: MpiComm,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiReduce {
+            current_rank,
+            partner_rank,
+            nb_bytes,
+            op_type,
+            comm,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiReduceBuilderError);
+impl_register!(MpiReduce);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let reduce_new = MpiReduce::new(0, 1, 64, MpiOp::Sum, MPI_COMM_WORLD, 1024, 2048);
+        let reduce_builder = MpiReduceBuilder::default()
+            .current_rank(0)
+            .partner_rank(1)
+            .nb_bytes(64)
+            .op_type(MpiOp::Sum)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiReduce`");
+
+        assert_eq!(reduce_new, reduce_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let reduce = MpiReduce::new(0, 1, 64, MpiOp::Sum, MPI_COMM_WORLD, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"partner_rank\":1,\"nb_bytes\":64,\"op_type\":\"Sum\",\"comm\":0,\"tsc\":1024,\"duration\":2048}");
+        let serialized = serde_json::to_string(&reduce).expect("failed to serialize `MpiReduce`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let reduce = MpiReduceBuilder::default()
+            .current_rank(1)
+            .partner_rank(0)
+            .nb_bytes(64)
+            .op_type(MpiOp::Max)
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiReduce`");
+        let serialized =
+            serde_json::to_string_pretty(&reduce).expect("failed to serialize `MpiReduce`");
+        let deserialized: MpiReduce =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiReduce`");
+
+        assert_eq!(reduce, deserialized);
+    }
+}
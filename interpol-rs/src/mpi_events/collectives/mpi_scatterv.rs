@@ -0,0 +1,116 @@
+use crate::types::{MpiComm, MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Scatterv` calls.
+///
+/// The information stored are:
+/// - the rank of the process making the call to `MPI_Scatterv`;
+/// - the rank of the root process;
+/// - the total number of bytes sent;
+/// - the total number of bytes received;
+/// - the number of bytes sent to each rank, indexed by rank (only on the root process);
+/// - the identifier of the MPI communicator;
+/// - the current value of the Time Stamp counter before the call to `MPI_Scatterv`;
+/// - the duration of the call.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiScatterv {
+    current_rank: MpiRank,
+    partner_rank: MpiRank,
+    nb_bytes_send: u32,
+    nb_bytes_recv: u32,
+    peer_bytes_send: Vec<u32>,
+    comm: MpiComm,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiScatterv {
+    /// Creates a new `MpiScatterv` structure from the specified parameters.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        current_rank: MpiRank,
+        partner_rank: MpiRank,
+        nb_bytes_send: u32,
+        nb_bytes_recv: u32,
+        peer_bytes_send: Vec<u32>,
+        comm: MpiComm,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        MpiScatterv {
+            current_rank,
+            partner_rank,
+            nb_bytes_send,
+            nb_bytes_recv,
+            peer_bytes_send,
+            comm,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+}
+
+impl_builder_error!(MpiScattervBuilderError);
+impl_register!(MpiScatterv);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const MPI_COMM_WORLD: i32 = 0;
+
+    #[test]
+    fn builds() {
+        let scatterv_new = MpiScatterv::new(0, 0, 24, 24, vec![8, 16], MPI_COMM_WORLD, 1024, 2048);
+        let scatterv_builder = MpiScattervBuilder::default()
+            .current_rank(0)
+            .partner_rank(0)
+            .nb_bytes_send(24)
+            .nb_bytes_recv(24)
+            .peer_bytes_send(vec![8, 16])
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiScatterv`");
+
+        assert_eq!(scatterv_new, scatterv_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let scatterv = MpiScatterv::new(0, 0, 24, 24, vec![8, 16], MPI_COMM_WORLD, 1024, 2048);
+        let json = String::from("{\"current_rank\":0,\"partner_rank\":0,\"nb_bytes_send\":24,\"nb_bytes_recv\":24,\"peer_bytes_send\":[8,16],\"comm\":0,\"tsc\":1024,\"duration\":2048}");
+        let serialized =
+            serde_json::to_string(&scatterv).expect("failed to serialize `MpiScatterv`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let scatterv = MpiScattervBuilder::default()
+            .current_rank(1)
+            .partner_rank(0)
+            .nb_bytes_send(16)
+            .nb_bytes_recv(40)
+            .peer_bytes_send(vec![4, 12])
+            .comm(MPI_COMM_WORLD)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiScatterv`");
+        let serialized =
+            serde_json::to_string_pretty(&scatterv).expect("failed to serialize `MpiScatterv`");
+        let deserialized: MpiScatterv =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiScatterv`");
+
+        assert_eq!(scatterv, deserialized);
+    }
+}
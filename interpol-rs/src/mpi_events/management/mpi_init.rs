@@ -1,4 +1,4 @@
-use crate::types::{MpiRank, Tsc, Usecs};
+use crate::types::{MpiRank, RegionId, Tsc, Usecs};
 use crate::{impl_builder_error, impl_register};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -16,6 +16,10 @@ pub struct MpiInit {
     current_rank: MpiRank,
     tsc: Tsc,
     time: Usecs,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
 }
 
 impl MpiInit {
@@ -26,12 +30,13 @@ impl MpiInit {
             current_rank,
             tsc,
             time,
+            region: None,
         }
     }
 }
 
 impl_builder_error!(MpiInitBuilderError);
-impl_register!(MpiInit);
+impl_register!(MpiInit, instant);
 
 #[cfg(test)]
 mod tests {
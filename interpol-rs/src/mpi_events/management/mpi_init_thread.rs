@@ -1,4 +1,4 @@
-use crate::types::{MpiRank, Tsc, Usecs};
+use crate::types::{MpiRank, RegionId, Tsc, Usecs};
 use crate::{impl_builder_error, impl_register};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -19,6 +19,10 @@ pub struct MpiInitThread {
     provided_thread_lvl: i32,
     tsc: Tsc,
     time: Usecs,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
 }
 
 impl MpiInitThread {
@@ -37,12 +41,13 @@ impl MpiInitThread {
             provided_thread_lvl,
             tsc,
             time,
+            region: None,
         }
     }
 }
 
 impl_builder_error!(MpiInitThreadBuilderError);
-impl_register!(MpiInitThread);
+impl_register!(MpiInitThread, instant);
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,70 @@
+use crate::types::{MpiRank, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A marker recorded when tracing is paused, either with `interpol_pause` or with
+/// `MPI_Pcontrol`.
+///
+/// The following data is gathered when the marker is recorded:
+/// - the rank of the process;
+/// - the current value of the Time Stamp counter.
+///
+/// No MPI call is recorded on the rank until the matching `InterpolResume` marker.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterpolPause {
+    current_rank: MpiRank,
+    tsc: Tsc,
+}
+
+impl InterpolPause {
+    /// Creates a new `InterpolPause` structure based off of a `MpiRank` and a number of CPU cycles.
+    pub fn new(current_rank: MpiRank, tsc: Tsc) -> Self {
+        Self { current_rank, tsc }
+    }
+}
+
+impl_builder_error!(InterpolPauseBuilderError);
+impl_register!(InterpolPause, marker);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let pause_new = InterpolPause::new(0, 1024);
+        let pause_builder = InterpolPauseBuilder::default()
+            .current_rank(0)
+            .tsc(1024)
+            .build()
+            .expect("failed to build `InterpolPause`");
+
+        assert_eq!(pause_new, pause_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let pause = InterpolPause::new(0, 1024);
+        let json = String::from("{\"current_rank\":0,\"tsc\":1024}");
+        let serialized =
+            serde_json::to_string(&pause).expect("failed to serialize `InterpolPause`");
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let pause = InterpolPauseBuilder::default()
+            .current_rank(0)
+            .tsc(1024)
+            .build()
+            .expect("failed to build `InterpolPause`");
+
+        let serialized =
+            serde_json::to_string_pretty(&pause).expect("failed to serialize `InterpolPause`");
+        let deserialized: InterpolPause =
+            serde_json::from_str(&serialized).expect("failed to deserialize `InterpolPause`");
+
+        assert_eq!(pause, deserialized);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::types::{MpiRank, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A marker recorded when tracing is resumed, either with `interpol_resume` or with
+/// `MPI_Pcontrol`.
+///
+/// The following data is gathered when the marker is recorded:
+/// - the rank of the process;
+/// - the current value of the Time Stamp counter.
+///
+/// It closes the interval opened by the previous `InterpolPause` marker of the rank.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterpolResume {
+    current_rank: MpiRank,
+    tsc: Tsc,
+}
+
+impl InterpolResume {
+    /// Creates a new `InterpolResume` structure based off of a `MpiRank` and a number of CPU cycles.
+    pub fn new(current_rank: MpiRank, tsc: Tsc) -> Self {
+        Self { current_rank, tsc }
+    }
+}
+
+impl_builder_error!(InterpolResumeBuilderError);
+impl_register!(InterpolResume, marker);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let resume_new = InterpolResume::new(0, 1024);
+        let resume_builder = InterpolResumeBuilder::default()
+            .current_rank(0)
+            .tsc(1024)
+            .build()
+            .expect("failed to build `InterpolResume`");
+
+        assert_eq!(resume_new, resume_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let resume = InterpolResume::new(0, 1024);
+        let json = String::from("{\"current_rank\":0,\"tsc\":1024}");
+        let serialized =
+            serde_json::to_string(&resume).expect("failed to serialize `InterpolResume`");
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let resume = InterpolResumeBuilder::default()
+            .current_rank(0)
+            .tsc(1024)
+            .build()
+            .expect("failed to build `InterpolResume`");
+
+        let serialized =
+            serde_json::to_string_pretty(&resume).expect("failed to serialize `InterpolResume`");
+        let deserialized: InterpolResume =
+            serde_json::from_str(&serialized).expect("failed to deserialize `InterpolResume`");
+
+        assert_eq!(resume, deserialized);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod interpol_pause;
+pub mod interpol_resume;
+pub mod region_begin;
+pub mod region_end;
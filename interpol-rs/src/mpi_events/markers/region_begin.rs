@@ -0,0 +1,95 @@
+use crate::types::{MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A marker recorded when a thread enters a user-defined region with `interpol_region_begin`.
+///
+/// The following data is gathered when the marker is recorded:
+/// - the rank of the process;
+/// - the identifier of the region, derived from its name;
+/// - the identifier of the region that encloses it, if any;
+/// - the name of the region;
+/// - the current value of the Time Stamp counter.
+///
+/// MPI calls made by the thread until the matching `RegionEnd` marker are tagged with the
+/// identifier of the region, unless they are made in a nested region.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionBegin {
+    current_rank: MpiRank,
+    region: RegionId,
+    parent: Option<RegionId>,
+    name: String,
+    tsc: Tsc,
+}
+
+impl RegionBegin {
+    /// Creates a new `RegionBegin` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        region: RegionId,
+        parent: Option<RegionId>,
+        name: String,
+        tsc: Tsc,
+    ) -> Self {
+        Self {
+            current_rank,
+            region,
+            parent,
+            name,
+            tsc,
+        }
+    }
+}
+
+impl_builder_error!(RegionBeginBuilderError);
+impl_register!(RegionBegin, marker);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let begin_new = RegionBegin::new(0, 7, Some(3), String::from("halo"), 1024);
+        let begin_builder = RegionBeginBuilder::default()
+            .current_rank(0)
+            .region(7)
+            .parent(Some(3))
+            .name(String::from("halo"))
+            .tsc(1024)
+            .build()
+            .expect("failed to build `RegionBegin`");
+
+        assert_eq!(begin_new, begin_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let begin = RegionBegin::new(0, 7, Some(3), String::from("halo"), 1024);
+        let json = String::from(
+            "{\"current_rank\":0,\"region\":7,\"parent\":3,\"name\":\"halo\",\"tsc\":1024}",
+        );
+        let serialized = serde_json::to_string(&begin).expect("failed to serialize `RegionBegin`");
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let begin = RegionBeginBuilder::default()
+            .current_rank(0)
+            .region(7)
+            .parent(None)
+            .name(String::from("solver"))
+            .tsc(1024)
+            .build()
+            .expect("failed to build `RegionBegin`");
+
+        let serialized =
+            serde_json::to_string_pretty(&begin).expect("failed to serialize `RegionBegin`");
+        let deserialized: RegionBegin =
+            serde_json::from_str(&serialized).expect("failed to deserialize `RegionBegin`");
+
+        assert_eq!(begin, deserialized);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::types::{MpiRank, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A marker recorded when a thread leaves a user-defined region with `interpol_region_end`.
+///
+/// The following data is gathered when the marker is recorded:
+/// - the rank of the process;
+/// - the identifier of the region;
+/// - the current value of the Time Stamp counter.
+///
+/// It closes the region opened by the last `RegionBegin` marker of the thread with the same
+/// identifier.
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionEnd {
+    current_rank: MpiRank,
+    region: RegionId,
+    tsc: Tsc,
+}
+
+impl RegionEnd {
+    /// Creates a new `RegionEnd` structure from the specified parameters.
+    pub fn new(current_rank: MpiRank, region: RegionId, tsc: Tsc) -> Self {
+        Self {
+            current_rank,
+            region,
+            tsc,
+        }
+    }
+}
+
+impl_builder_error!(RegionEndBuilderError);
+impl_register!(RegionEnd, marker);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let end_new = RegionEnd::new(0, 7, 1024);
+        let end_builder = RegionEndBuilder::default()
+            .current_rank(0)
+            .region(7)
+            .tsc(1024)
+            .build()
+            .expect("failed to build `RegionEnd`");
+
+        assert_eq!(end_new, end_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let end = RegionEnd::new(0, 7, 1024);
+        let json = String::from("{\"current_rank\":0,\"region\":7,\"tsc\":1024}");
+        let serialized = serde_json::to_string(&end).expect("failed to serialize `RegionEnd`");
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let end = RegionEndBuilder::default()
+            .current_rank(0)
+            .region(7)
+            .tsc(1024)
+            .build()
+            .expect("failed to build `RegionEnd`");
+
+        let serialized =
+            serde_json::to_string_pretty(&end).expect("failed to serialize `RegionEnd`");
+        let deserialized: RegionEnd =
+            serde_json::from_str(&serialized).expect("failed to deserialize `RegionEnd`");
+
+        assert_eq!(end, deserialized);
+    }
+}
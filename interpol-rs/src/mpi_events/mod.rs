@@ -1,4 +1,202 @@
+use crate::interpol::Register;
+use crate::types::{MpiRank, Tsc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
 pub mod collectives;
 pub mod management;
+pub mod markers;
 pub mod point_to_point;
+mod record;
 pub mod synchronization;
+
+/// Declares the `MpiEvent` enum from a list of event types, one variant per type.
+///
+/// Each variant is named after the type it holds, so that the `"type"` tag of a serialized event
+/// is the name of its structure. Each variant is also given the record kind that identifies it in
+/// binary traces; these *must* never be reused once attributed.
+macro_rules! mpi_event_enum {
+    ($($variant:ident($event:path) = $kind:literal),* $(,)?) => {
+        /// An event recorded by `interpol-rs`, stored inline in the trace buffers.
+        ///
+        /// Events are serialized as internally tagged objects (e.g.
+        /// `{"type":"MpiSend","current_rank":0,...}`), which is the format read by the Interpol
+        /// Trace Analyzer.
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(tag = "type")]
+        pub enum MpiEvent {
+            $($variant($event),)*
+        }
+
+        impl MpiEvent {
+            /// The names of every kind of event, as written in the `type` field of serialized
+            /// events.
+            pub const NAMES: &'static [&'static str] = &[$(stringify!($variant)),*];
+
+            /// Returns the value of the Time Stamp Counter at the start of the event.
+            pub fn tsc(&self) -> Tsc {
+                match self {
+                    $(MpiEvent::$variant(event) => event.tsc(),)*
+                }
+            }
+
+            /// Sets the value of the Time Stamp Counter at the start of the event.
+            pub fn set_tsc(&mut self, tsc: Tsc) {
+                match self {
+                    $(MpiEvent::$variant(event) => event.set_tsc(tsc),)*
+                }
+            }
+
+            /// Returns the duration of the event in TSC cycles, or 0 if it is instantaneous.
+            pub fn duration(&self) -> Tsc {
+                match self {
+                    $(MpiEvent::$variant(event) => event.duration(),)*
+                }
+            }
+
+            /// Returns the rank of the process that recorded the event.
+            pub fn current_rank(&self) -> MpiRank {
+                match self {
+                    $(MpiEvent::$variant(event) => event.current_rank(),)*
+                }
+            }
+
+            /// Writes the event as a binary record: its kind followed by its encoded fields.
+            pub(crate) fn write_record<W: Write>(&self, writer: &mut W) -> bincode::Result<()> {
+                match self {
+                    $(MpiEvent::$variant(event) => {
+                        writer.write_all(&[$kind])?;
+                        record::serialize_into(writer, event)
+                    })*
+                }
+            }
+
+            /// Reads the fields of a binary record of the given kind.
+            pub(crate) fn read_record<R: Read>(kind: u8, reader: &mut R) -> bincode::Result<Self> {
+                match kind {
+                    $($kind => Ok(MpiEvent::$variant(bincode::deserialize_from(reader)?)),)*
+                    _ => Err(Box::new(bincode::ErrorKind::Custom(format!(
+                        "unknown record kind {kind}"
+                    )))),
+                }
+            }
+        }
+    };
+}
+
+mpi_event_enum! {
+    MpiInit(management::mpi_init::MpiInit) = 0,
+    MpiInitThread(management::mpi_init_thread::MpiInitThread) = 1,
+    MpiFinalize(management::mpi_finalize::MpiFinalize) = 2,
+    MpiSend(point_to_point::mpi_send::MpiSend) = 3,
+    MpiRecv(point_to_point::mpi_recv::MpiRecv) = 4,
+    MpiIsend(point_to_point::mpi_isend::MpiIsend) = 5,
+    MpiIrecv(point_to_point::mpi_irecv::MpiIrecv) = 6,
+    MpiBarrier(synchronization::mpi_barrier::MpiBarrier) = 7,
+    MpiIbarrier(synchronization::mpi_ibarrier::MpiIbarrier) = 8,
+    MpiTest(synchronization::mpi_test::MpiTest) = 9,
+    MpiWait(synchronization::mpi_wait::MpiWait) = 10,
+    MpiIbcast(collectives::mpi_ibcast::MpiIbcast) = 11,
+    MpiIgather(collectives::mpi_igather::MpiIgather) = 12,
+    MpiIreduce(collectives::mpi_ireduce::MpiIreduce) = 13,
+    MpiIscatter(collectives::mpi_iscatter::MpiIscatter) = 14,
+    InterpolPause(markers::interpol_pause::InterpolPause) = 15,
+    InterpolResume(markers::interpol_resume::InterpolResume) = 16,
+    RegionBegin(markers::region_begin::RegionBegin) = 17,
+    RegionEnd(markers::region_end::RegionEnd) = 18,
+    MpiWaitall(synchronization::mpi_waitall::MpiWaitall) = 19,
+    MpiWaitany(synchronization::mpi_waitany::MpiWaitany) = 20,
+    MpiWaitsome(synchronization::mpi_waitsome::MpiWaitsome) = 21,
+    MpiTestall(synchronization::mpi_testall::MpiTestall) = 22,
+    MpiTestany(synchronization::mpi_testany::MpiTestany) = 23,
+    MpiTestsome(synchronization::mpi_testsome::MpiTestsome) = 24,
+    MpiBcast(collectives::mpi_bcast::MpiBcast) = 25,
+    MpiReduce(collectives::mpi_reduce::MpiReduce) = 26,
+    MpiAllreduce(collectives::mpi_allreduce::MpiAllreduce) = 27,
+    MpiGather(collectives::mpi_gather::MpiGather) = 28,
+    MpiScatter(collectives::mpi_scatter::MpiScatter) = 29,
+    MpiAllgather(collectives::mpi_allgather::MpiAllgather) = 30,
+    MpiAlltoall(collectives::mpi_alltoall::MpiAlltoall) = 31,
+    MpiIallreduce(collectives::mpi_iallreduce::MpiIallreduce) = 32,
+    MpiIallgather(collectives::mpi_iallgather::MpiIallgather) = 33,
+    MpiIalltoall(collectives::mpi_ialltoall::MpiIalltoall) = 34,
+    MpiIreduceScatter(collectives::mpi_ireduce_scatter::MpiIreduceScatter) = 35,
+    MpiIscan(collectives::mpi_iscan::MpiIscan) = 36,
+    MpiIexscan(collectives::mpi_iexscan::MpiIexscan) = 37,
+    MpiGatherv(collectives::mpi_gatherv::MpiGatherv) = 38,
+    MpiScatterv(collectives::mpi_scatterv::MpiScatterv) = 39,
+    MpiAllgatherv(collectives::mpi_allgatherv::MpiAllgatherv) = 40,
+    MpiAlltoallv(collectives::mpi_alltoallv::MpiAlltoallv) = 41,
+    MpiIgatherv(collectives::mpi_igatherv::MpiIgatherv) = 42,
+    MpiIscatterv(collectives::mpi_iscatterv::MpiIscatterv) = 43,
+    MpiIallgatherv(collectives::mpi_iallgatherv::MpiIallgatherv) = 44,
+    MpiIalltoallv(collectives::mpi_ialltoallv::MpiIalltoallv) = 45,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpi_events::{
+        management::mpi_init::MpiInit, point_to_point::mpi_send::MpiSend,
+        synchronization::mpi_wait::MpiWait,
+    };
+
+    #[test]
+    fn serializes_with_type_tag() {
+        let send: MpiEvent = MpiSend::new(0, 1, 8, 0, 42, 1024, 2048).into();
+        let json = String::from("{\"type\":\"MpiSend\",\"current_rank\":0,\"partner_rank\":1,\"nb_bytes\":8,\"comm\":0,\"tag\":42,\"tsc\":1024,\"duration\":2048}");
+        let serialized = serde_json::to_string(&send).expect("failed to serialize `MpiEvent`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes_trace() {
+        let events: Vec<MpiEvent> = vec![
+            MpiInit::new(0, 512, 0.1).into(),
+            MpiSend::new(0, 1, 8, 0, 42, 1024, 2048).into(),
+            MpiWait::new(0, 7, 4096, 128).into(),
+        ];
+        let serialized =
+            serde_json::to_string_pretty(&events).expect("failed to serialize `MpiEvent`s");
+        let deserialized: Vec<MpiEvent> =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiEvent`s");
+
+        assert_eq!(events, deserialized);
+        assert_eq!(
+            deserialized.iter().map(MpiEvent::tsc).collect::<Vec<_>>(),
+            vec![512, 1024, 4096]
+        );
+    }
+
+    #[test]
+    fn serializes_region_when_set() {
+        let mut wait = MpiWait::new(0, 7, 4096, 128);
+        wait.set_region(Some(3));
+        let json = String::from(
+            "{\"type\":\"MpiWait\",\"current_rank\":0,\"req\":7,\"tsc\":4096,\"duration\":128,\"region\":3}",
+        );
+        let serialized =
+            serde_json::to_string(&MpiEvent::from(wait.clone())).expect("failed to serialize");
+        assert_eq!(json, serialized);
+
+        // Binary records always encode the region, whether it is set or not
+        for event in [wait.into(), MpiWait::new(0, 7, 4096, 128).into()] {
+            let mut record = Vec::new();
+            MpiEvent::write_record(&event, &mut record).expect("failed to write record");
+            let read =
+                MpiEvent::read_record(record[0], &mut &record[1..]).expect("failed to read record");
+            assert_eq!(read, event);
+        }
+    }
+
+    #[test]
+    fn deserializes_events_without_region() {
+        let json =
+            "{\"type\":\"MpiWait\",\"current_rank\":0,\"req\":7,\"tsc\":4096,\"duration\":128}";
+        let deserialized: MpiEvent =
+            serde_json::from_str(json).expect("failed to deserialize `MpiEvent`");
+
+        assert_eq!(deserialized, MpiWait::new(0, 7, 4096, 128).into());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::types::{MpiComm, MpiRank, MpiReq, MpiTag, Tsc};
+use crate::types::{MpiComm, MpiRank, MpiReq, MpiTag, RegionId, Tsc};
 use crate::{impl_builder_error, impl_register};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -25,6 +25,10 @@ pub struct MpiIrecv {
     tag: MpiTag,
     tsc: Tsc,
     duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
 }
 
 impl MpiIrecv {
@@ -48,8 +52,29 @@ impl MpiIrecv {
             tag,
             tsc,
             duration,
+            region: None,
         }
     }
+
+    /// Returns the rank of the process on the other side of the communication.
+    pub fn partner_rank(&self) -> MpiRank {
+        self.partner_rank
+    }
+
+    /// Returns the identifier of the MPI communicator.
+    pub fn comm(&self) -> MpiComm {
+        self.comm
+    }
+
+    /// Returns the identifier of the MPI request.
+    pub fn req(&self) -> MpiReq {
+        self.req
+    }
+
+    /// Returns the tag of the communication.
+    pub fn tag(&self) -> MpiTag {
+        self.tag
+    }
 }
 
 impl_builder_error!(MpiIrecvBuilderError);
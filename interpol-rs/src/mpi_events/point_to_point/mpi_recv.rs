@@ -1,6 +1,6 @@
 use crate::{
     impl_builder_error, impl_register,
-    types::{MpiComm, MpiRank, MpiTag, Tsc},
+    types::{MpiComm, MpiRank, MpiTag, RegionId, Tsc},
 };
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -25,6 +25,10 @@ pub struct MpiRecv {
     tag: MpiTag,
     tsc: Tsc,
     duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
 }
 
 impl MpiRecv {
@@ -46,8 +50,24 @@ impl MpiRecv {
             tag,
             tsc,
             duration,
+            region: None,
         }
     }
+
+    /// Returns the rank of the process on the other side of the communication.
+    pub fn partner_rank(&self) -> MpiRank {
+        self.partner_rank
+    }
+
+    /// Returns the identifier of the MPI communicator.
+    pub fn comm(&self) -> MpiComm {
+        self.comm
+    }
+
+    /// Returns the tag of the communication.
+    pub fn tag(&self) -> MpiTag {
+        self.tag
+    }
 }
 
 impl_builder_error!(MpiRecvBuilderError);
@@ -0,0 +1,174 @@
+use crate::types::RegionId;
+use bincode::Options;
+use serde::ser::{self, Serialize, SerializeStruct, Serializer};
+
+/// Serializes an event as the fields of a binary record with the same encoding as
+/// `bincode::serialize_into`.
+///
+/// The fields of a record are read back by position, so none of them can be left out: the
+/// region of an event, which is skipped by other formats when it is not set, is written as `None`.
+pub(super) fn serialize_into<W: std::io::Write, T: Serialize>(
+    writer: W,
+    event: &T,
+) -> bincode::Result<()> {
+    let options = bincode::config::DefaultOptions::new().with_fixint_encoding();
+    event.serialize(RecordSerializer(&mut bincode::Serializer::new(
+        writer, options,
+    )))
+}
+
+/// A serializer that forwards everything to `S`, except for the fields skipped by the structures
+/// it serializes (see `RecordStruct`).
+struct RecordSerializer<S>(S);
+
+/// Forwards the serialization of values that are not structures to the inner serializer.
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(fn $method(self, $($arg: $ty),*) -> Result<$ret, Self::Error> {
+            self.0.$method($($arg),*)
+        })*
+    };
+}
+
+impl<S: Serializer> Serializer for RecordSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = RecordStruct<S::SerializeStruct>;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    forward! {
+        serialize_bool(v: bool) -> S::Ok;
+        serialize_i8(v: i8) -> S::Ok;
+        serialize_i16(v: i16) -> S::Ok;
+        serialize_i32(v: i32) -> S::Ok;
+        serialize_i64(v: i64) -> S::Ok;
+        serialize_i128(v: i128) -> S::Ok;
+        serialize_u8(v: u8) -> S::Ok;
+        serialize_u16(v: u16) -> S::Ok;
+        serialize_u32(v: u32) -> S::Ok;
+        serialize_u64(v: u64) -> S::Ok;
+        serialize_u128(v: u128) -> S::Ok;
+        serialize_f32(v: f32) -> S::Ok;
+        serialize_f64(v: f64) -> S::Ok;
+        serialize_char(v: char) -> S::Ok;
+        serialize_str(v: &str) -> S::Ok;
+        serialize_bytes(v: &[u8]) -> S::Ok;
+        serialize_none() -> S::Ok;
+        serialize_unit() -> S::Ok;
+        serialize_unit_struct(name: &'static str) -> S::Ok;
+        serialize_unit_variant(name: &'static str, index: u32, variant: &'static str) -> S::Ok;
+        serialize_seq(len: Option<usize>) -> S::SerializeSeq;
+        serialize_tuple(len: usize) -> S::SerializeTuple;
+        serialize_tuple_struct(name: &'static str, len: usize) -> S::SerializeTupleStruct;
+        serialize_tuple_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> S::SerializeTupleVariant;
+        serialize_map(len: Option<usize>) -> S::SerializeMap;
+        serialize_struct_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> S::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(value)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0
+            .serialize_newtype_variant(name, index, variant, value)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        self.0.serialize_struct(name, len).map(RecordStruct)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// The fields of a structure serialized by `RecordSerializer`, in which the region of an event is
+/// written even when it is skipped.
+struct RecordStruct<S>(S);
+
+impl<S: SerializeStruct> SerializeStruct for RecordStruct<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error> {
+        self.0.serialize_field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        match key {
+            "region" => self.0.serialize_field(key, &None::<RegionId>),
+            _ => Err(ser::Error::custom(format!(
+                "the field `{key}` cannot be left out of a binary record"
+            ))),
+        }
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpol::Register;
+    use crate::mpi_events::synchronization::mpi_wait::MpiWait;
+
+    #[test]
+    fn writes_regions_that_are_not_set() {
+        let mut wait = MpiWait::new(0, 7, 4096, 128);
+        let mut record = Vec::new();
+        serialize_into(&mut record, &wait).expect("failed to write record");
+        wait.set_region(Some(3));
+        let mut in_region = Vec::new();
+        serialize_into(&mut in_region, &wait).expect("failed to write record");
+
+        // Every field but the region is encoded the same way, followed by the tag of the region
+        let fields = record.len() - 1;
+        assert_eq!(record[fields..], [0]);
+        assert_eq!(in_region[..fields], record[..fields]);
+        assert_eq!(in_region[fields], 1);
+        assert_eq!(
+            in_region,
+            bincode::serialize(&wait).expect("failed to serialize")
+        );
+    }
+}
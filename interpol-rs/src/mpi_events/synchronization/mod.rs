@@ -1,8 +1,10 @@
 pub mod mpi_barrier;
 pub mod mpi_ibarrier;
 pub mod mpi_test;
-// pub mod mpi_testall;
-// pub mod mpi_testsome;
+pub mod mpi_testall;
+pub mod mpi_testany;
+pub mod mpi_testsome;
 pub mod mpi_wait;
-// pub mod mpi_waitall;
-// pub mod mpi_waitsome;
+pub mod mpi_waitall;
+pub mod mpi_waitany;
+pub mod mpi_waitsome;
@@ -1,4 +1,4 @@
-use crate::types::{MpiComm, MpiRank, Tsc};
+use crate::types::{MpiComm, MpiRank, RegionId, Tsc};
 use crate::{impl_builder_error, impl_register};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -18,6 +18,10 @@ pub struct MpiBarrier {
     comm: MpiComm,
     tsc: Tsc,
     duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
 }
 
 impl MpiBarrier {
@@ -28,6 +32,7 @@ impl MpiBarrier {
             comm,
             tsc,
             duration,
+            region: None,
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::types::{MpiRank, MpiReq, Tsc};
+use crate::types::{MpiRank, MpiReq, RegionId, Tsc};
 use crate::{impl_builder_error, impl_register};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -20,6 +20,10 @@ pub struct MpiTest {
     finished: bool,
     tsc: Tsc,
     duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
 }
 
 impl MpiTest {
@@ -37,8 +41,19 @@ impl MpiTest {
             finished,
             tsc,
             duration,
+            region: None,
         }
     }
+
+    /// Returns the identifier of the MPI request.
+    pub fn req(&self) -> MpiReq {
+        self.req
+    }
+
+    /// Returns whether the request has completed.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
 }
 
 impl_builder_error!(MpiTestBuilderError);
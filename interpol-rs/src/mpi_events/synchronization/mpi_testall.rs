@@ -1 +1,110 @@
+use crate::types::{MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
 
+/// A structure that stores information about `MPI_Testall` calls.
+///
+/// The following data is gathered when the MPI function is called:
+/// - the rank of the process;
+/// - the requests that were tested;
+/// - whether all the requested communications had finished;
+/// - the current value of the Time Stamp counter before the call to `MPI_Testall`;
+/// - the duration of the call.
+///
+/// The TSC is measured using the `rdtscp` and `lfence` instructions (see Intel documentation for
+/// further information).
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiTestall {
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    finished: bool,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiTestall {
+    /// Creates a new `MpiTestall` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        reqs: Vec<MpiReq>,
+        finished: bool,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        Self {
+            current_rank,
+            reqs,
+            finished,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+
+    /// Returns the identifiers of the MPI requests.
+    pub fn reqs(&self) -> &[MpiReq] {
+        &self.reqs
+    }
+
+    /// Returns whether all the requests have completed.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+}
+
+impl_builder_error!(MpiTestallBuilderError);
+impl_register!(MpiTestall);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let testall_new = MpiTestall::new(0, vec![3, 4], true, 1024, 2048);
+        let testall_builder = MpiTestallBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4])
+            .finished(true)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiTestall`");
+
+        assert_eq!(testall_new, testall_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let testall = MpiTestall::new(0, vec![3, 4], false, 1024, 2048);
+        let json = String::from(
+            "{\"current_rank\":0,\"reqs\":[3,4],\"finished\":false,\"tsc\":1024,\"duration\":2048}",
+        );
+        let serialized = serde_json::to_string(&testall).expect("failed to serialize `MpiTestall`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let testall = MpiTestallBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4])
+            .finished(true)
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiTestall`");
+        let serialized =
+            serde_json::to_string_pretty(&testall).expect("failed to serialize `MpiTestall`");
+        let deserialized: MpiTestall =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiTestall`");
+
+        assert_eq!(testall, deserialized);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::types::{MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Testany` calls.
+///
+/// The following data is gathered when the MPI function is called:
+/// - the rank of the process;
+/// - the requests that were tested;
+/// - whether one of the requested communications had finished, or every request was inactive;
+/// - the index of the request that completed, if any;
+/// - the current value of the Time Stamp counter before the call to `MPI_Testany`;
+/// - the duration of the call.
+///
+/// The TSC is measured using the `rdtscp` and `lfence` instructions (see Intel documentation for
+/// further information).
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiTestany {
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    finished: bool,
+    index: Option<u32>,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiTestany {
+    /// Creates a new `MpiTestany` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        reqs: Vec<MpiReq>,
+        finished: bool,
+        index: Option<u32>,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        Self {
+            current_rank,
+            reqs,
+            finished,
+            index,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+
+    /// Returns the identifiers of the MPI requests.
+    pub fn reqs(&self) -> &[MpiReq] {
+        &self.reqs
+    }
+
+    /// Returns whether a request has completed, or every request was inactive.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns the identifier of the MPI request that completed, if any.
+    pub fn completed(&self) -> Option<MpiReq> {
+        self.reqs.get(self.index? as usize).copied()
+    }
+}
+
+impl_builder_error!(MpiTestanyBuilderError);
+impl_register!(MpiTestany);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let testany_new = MpiTestany::new(0, vec![3, 4], true, Some(1), 1024, 2048);
+        let testany_builder = MpiTestanyBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4])
+            .finished(true)
+            .index(Some(1))
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiTestany`");
+
+        assert_eq!(testany_new, testany_builder);
+        assert_eq!(testany_new.completed(), Some(4));
+    }
+
+    #[test]
+    fn serializes() {
+        let testany = MpiTestany::new(0, vec![3, 4], false, None, 1024, 2048);
+        let json = String::from(
+            "{\"current_rank\":0,\"reqs\":[3,4],\"finished\":false,\"index\":null,\"tsc\":1024,\"duration\":2048}",
+        );
+        let serialized = serde_json::to_string(&testany).expect("failed to serialize `MpiTestany`");
+
+        assert_eq!(json, serialized);
+        assert_eq!(testany.completed(), None);
+    }
+
+    #[test]
+    fn deserializes() {
+        let testany = MpiTestanyBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4])
+            .finished(true)
+            .index(Some(0))
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiTestany`");
+        let serialized =
+            serde_json::to_string_pretty(&testany).expect("failed to serialize `MpiTestany`");
+        let deserialized: MpiTestany =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiTestany`");
+
+        assert_eq!(testany, deserialized);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::types::{MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Testsome` calls.
+///
+/// The following data is gathered when the MPI function is called:
+/// - the rank of the process;
+/// - the requests that were tested;
+/// - the indices of the requests that completed;
+/// - the current value of the Time Stamp counter before the call to `MPI_Testsome`;
+/// - the duration of the call.
+///
+/// The TSC is measured using the `rdtscp` and `lfence` instructions (see Intel documentation for
+/// further information).
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiTestsome {
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    indices: Vec<u32>,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiTestsome {
+    /// Creates a new `MpiTestsome` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        reqs: Vec<MpiReq>,
+        indices: Vec<u32>,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        Self {
+            current_rank,
+            reqs,
+            indices,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+
+    /// Returns the identifiers of the MPI requests.
+    pub fn reqs(&self) -> &[MpiReq] {
+        &self.reqs
+    }
+
+    /// Returns the identifiers of the MPI requests that completed.
+    pub fn completed(&self) -> impl Iterator<Item = MpiReq> + '_ {
+        self.indices
+            .iter()
+            .filter_map(|&index| self.reqs.get(index as usize).copied())
+    }
+}
+
+impl_builder_error!(MpiTestsomeBuilderError);
+impl_register!(MpiTestsome);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let testsome_new = MpiTestsome::new(0, vec![3, 4, 5], vec![0, 2], 1024, 2048);
+        let testsome_builder = MpiTestsomeBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4, 5])
+            .indices(vec![0, 2])
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiTestsome`");
+
+        assert_eq!(testsome_new, testsome_builder);
+        assert_eq!(testsome_new.completed().collect::<Vec<_>>(), vec![3, 5]);
+    }
+
+    #[test]
+    fn serializes() {
+        let testsome = MpiTestsome::new(0, vec![3, 4, 5], vec![0, 2], 1024, 2048);
+        let json = String::from(
+            "{\"current_rank\":0,\"reqs\":[3,4,5],\"indices\":[0,2],\"tsc\":1024,\"duration\":2048}",
+        );
+        let serialized =
+            serde_json::to_string(&testsome).expect("failed to serialize `MpiTestsome`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let testsome = MpiTestsomeBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4, 5])
+            .indices(vec![1])
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiTestsome`");
+        let serialized =
+            serde_json::to_string_pretty(&testsome).expect("failed to serialize `MpiTestsome`");
+        let deserialized: MpiTestsome =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiTestsome`");
+
+        assert_eq!(testsome, deserialized);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::types::{MpiRank, MpiReq, Tsc};
+use crate::types::{MpiRank, MpiReq, RegionId, Tsc};
 use crate::{impl_builder_error, impl_register};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -18,6 +18,10 @@ pub struct MpiWait {
     req: MpiReq,
     tsc: Tsc,
     duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
 }
 
 impl MpiWait {
@@ -28,8 +32,14 @@ impl MpiWait {
             req,
             tsc,
             duration,
+            region: None,
         }
     }
+
+    /// Returns the identifier of the MPI request.
+    pub fn req(&self) -> MpiReq {
+        self.req
+    }
 }
 
 impl_builder_error!(MpiWaitBuilderError);
@@ -1 +1,93 @@
+use crate::types::{MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
 
+/// A structure that stores information about `MPI_Waitall` calls.
+///
+/// The following data is gathered when the MPI function is called:
+/// - the rank of the process;
+/// - the requests that were waited on, all of which have completed when the call returns;
+/// - the current value of the Time Stamp counter before the call to `MPI_Waitall`;
+/// - the duration of the call.
+///
+/// The TSC is measured using the `rdtscp` and `lfence` instructions (see Intel documentation for
+/// further information).
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiWaitall {
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiWaitall {
+    /// Creates a new `MpiWaitall` structure from the specified parameters.
+    pub fn new(current_rank: MpiRank, reqs: Vec<MpiReq>, tsc: Tsc, duration: Tsc) -> Self {
+        Self {
+            current_rank,
+            reqs,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+
+    /// Returns the identifiers of the MPI requests.
+    pub fn reqs(&self) -> &[MpiReq] {
+        &self.reqs
+    }
+}
+
+impl_builder_error!(MpiWaitallBuilderError);
+impl_register!(MpiWaitall);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let waitall_new = MpiWaitall::new(0, vec![3, 4], 1024, 2048);
+        let waitall_builder = MpiWaitallBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4])
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiWaitall`");
+
+        assert_eq!(waitall_new, waitall_builder);
+    }
+
+    #[test]
+    fn serializes() {
+        let waitall = MpiWaitall::new(0, vec![3, 4], 1024, 2048);
+        let json =
+            String::from("{\"current_rank\":0,\"reqs\":[3,4],\"tsc\":1024,\"duration\":2048}");
+        let serialized = serde_json::to_string(&waitall).expect("failed to serialize `MpiWaitall`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let waitall = MpiWaitallBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4])
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiWaitall`");
+        let serialized =
+            serde_json::to_string_pretty(&waitall).expect("failed to serialize `MpiWaitall`");
+        let deserialized: MpiWaitall =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiWaitall`");
+
+        assert_eq!(waitall, deserialized);
+    }
+}
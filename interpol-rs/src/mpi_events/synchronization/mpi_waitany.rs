@@ -0,0 +1,113 @@
+use crate::types::{MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Waitany` calls.
+///
+/// The following data is gathered when the MPI function is called:
+/// - the rank of the process;
+/// - the requests that were waited on;
+/// - the index of the request that completed, if any (there is none if every request was
+///   inactive);
+/// - the current value of the Time Stamp counter before the call to `MPI_Waitany`;
+/// - the duration of the call.
+///
+/// The TSC is measured using the `rdtscp` and `lfence` instructions (see Intel documentation for
+/// further information).
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiWaitany {
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    index: Option<u32>,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiWaitany {
+    /// Creates a new `MpiWaitany` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        reqs: Vec<MpiReq>,
+        index: Option<u32>,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        Self {
+            current_rank,
+            reqs,
+            index,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+
+    /// Returns the identifiers of the MPI requests.
+    pub fn reqs(&self) -> &[MpiReq] {
+        &self.reqs
+    }
+
+    /// Returns the identifier of the MPI request that completed, if any.
+    pub fn completed(&self) -> Option<MpiReq> {
+        self.reqs.get(self.index? as usize).copied()
+    }
+}
+
+impl_builder_error!(MpiWaitanyBuilderError);
+impl_register!(MpiWaitany);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let waitany_new = MpiWaitany::new(0, vec![3, 4], Some(1), 1024, 2048);
+        let waitany_builder = MpiWaitanyBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4])
+            .index(Some(1))
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiWaitany`");
+
+        assert_eq!(waitany_new, waitany_builder);
+        assert_eq!(waitany_new.completed(), Some(4));
+    }
+
+    #[test]
+    fn serializes() {
+        let waitany = MpiWaitany::new(0, vec![3, 4], None, 1024, 2048);
+        let json = String::from(
+            "{\"current_rank\":0,\"reqs\":[3,4],\"index\":null,\"tsc\":1024,\"duration\":2048}",
+        );
+        let serialized = serde_json::to_string(&waitany).expect("failed to serialize `MpiWaitany`");
+
+        assert_eq!(json, serialized);
+        assert_eq!(waitany.completed(), None);
+    }
+
+    #[test]
+    fn deserializes() {
+        let waitany = MpiWaitanyBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4])
+            .index(Some(0))
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiWaitany`");
+        let serialized =
+            serde_json::to_string_pretty(&waitany).expect("failed to serialize `MpiWaitany`");
+        let deserialized: MpiWaitany =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiWaitany`");
+
+        assert_eq!(waitany, deserialized);
+    }
+}
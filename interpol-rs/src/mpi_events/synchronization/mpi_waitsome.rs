@@ -0,0 +1,114 @@
+use crate::types::{MpiRank, MpiReq, RegionId, Tsc};
+use crate::{impl_builder_error, impl_register};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// A structure that stores information about `MPI_Waitsome` calls.
+///
+/// The following data is gathered when the MPI function is called:
+/// - the rank of the process;
+/// - the requests that were waited on;
+/// - the indices of the requests that completed;
+/// - the current value of the Time Stamp counter before the call to `MPI_Waitsome`;
+/// - the duration of the call.
+///
+/// The TSC is measured using the `rdtscp` and `lfence` instructions (see Intel documentation for
+/// further information).
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MpiWaitsome {
+    current_rank: MpiRank,
+    reqs: Vec<MpiReq>,
+    indices: Vec<u32>,
+    tsc: Tsc,
+    duration: Tsc,
+    /// The innermost user-defined region enclosing the call, if any.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    region: Option<RegionId>,
+}
+
+impl MpiWaitsome {
+    /// Creates a new `MpiWaitsome` structure from the specified parameters.
+    pub fn new(
+        current_rank: MpiRank,
+        reqs: Vec<MpiReq>,
+        indices: Vec<u32>,
+        tsc: Tsc,
+        duration: Tsc,
+    ) -> Self {
+        Self {
+            current_rank,
+            reqs,
+            indices,
+            tsc,
+            duration,
+            region: None,
+        }
+    }
+
+    /// Returns the identifiers of the MPI requests.
+    pub fn reqs(&self) -> &[MpiReq] {
+        &self.reqs
+    }
+
+    /// Returns the identifiers of the MPI requests that completed.
+    pub fn completed(&self) -> impl Iterator<Item = MpiReq> + '_ {
+        self.indices
+            .iter()
+            .filter_map(|&index| self.reqs.get(index as usize).copied())
+    }
+}
+
+impl_builder_error!(MpiWaitsomeBuilderError);
+impl_register!(MpiWaitsome);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let waitsome_new = MpiWaitsome::new(0, vec![3, 4, 5], vec![0, 2], 1024, 2048);
+        let waitsome_builder = MpiWaitsomeBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4, 5])
+            .indices(vec![0, 2])
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiWaitsome`");
+
+        assert_eq!(waitsome_new, waitsome_builder);
+        assert_eq!(waitsome_new.completed().collect::<Vec<_>>(), vec![3, 5]);
+    }
+
+    #[test]
+    fn serializes() {
+        let waitsome = MpiWaitsome::new(0, vec![3, 4, 5], vec![0, 2], 1024, 2048);
+        let json = String::from(
+            "{\"current_rank\":0,\"reqs\":[3,4,5],\"indices\":[0,2],\"tsc\":1024,\"duration\":2048}",
+        );
+        let serialized =
+            serde_json::to_string(&waitsome).expect("failed to serialize `MpiWaitsome`");
+
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn deserializes() {
+        let waitsome = MpiWaitsomeBuilder::default()
+            .current_rank(0)
+            .reqs(vec![3, 4, 5])
+            .indices(vec![1])
+            .tsc(1024)
+            .duration(2048)
+            .build()
+            .expect("failed to build `MpiWaitsome`");
+        let serialized =
+            serde_json::to_string_pretty(&waitsome).expect("failed to serialize `MpiWaitsome`");
+        let deserialized: MpiWaitsome =
+            serde_json::from_str(&serialized).expect("failed to deserialize `MpiWaitsome`");
+
+        assert_eq!(waitsome, deserialized);
+    }
+}
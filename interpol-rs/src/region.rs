@@ -0,0 +1,79 @@
+use crate::types::RegionId;
+use std::cell::RefCell;
+
+thread_local! {
+    /// The regions entered by the current thread and not yet ended, innermost last.
+    static REGIONS: RefCell<Vec<RegionId>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the identifier of the region with the given name.
+///
+/// Identifiers are derived from the name alone (with the 32-bit FNV-1a hash), so that a region
+/// has the same identifier on every rank and in every run.
+pub fn region_id(name: &str) -> RegionId {
+    name.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ byte as RegionId).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Returns the innermost region entered by the current thread, if any.
+pub fn current() -> Option<RegionId> {
+    REGIONS.with(|regions| regions.borrow().last().copied())
+}
+
+/// Enters a region on the current thread, and returns the region that encloses it, if any.
+pub fn enter(region: RegionId) -> Option<RegionId> {
+    REGIONS.with(|regions| {
+        let mut regions = regions.borrow_mut();
+        let parent = regions.last().copied();
+        regions.push(region);
+        parent
+    })
+}
+
+/// Ends the innermost occurrence of a region on the current thread.
+///
+/// Returns the regions that were ended, innermost first: the regions nested in `region` that
+/// were not ended yet are ended along with it. Returns an empty `Vec` if `region` was not
+/// entered.
+pub fn exit(region: RegionId) -> Vec<RegionId> {
+    REGIONS.with(|regions| {
+        let mut regions = regions.borrow_mut();
+        match regions.iter().rposition(|&r| r == region) {
+            Some(i) => regions.drain(i..).rev().collect(),
+            None => Vec::new(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_identifiers_from_names() {
+        assert_eq!(region_id(""), 0x811c_9dc5);
+        assert_eq!(region_id("a"), 0xe40c_292c);
+        assert_eq!(region_id("solver"), region_id("solver"));
+        assert_ne!(region_id("solver"), region_id("halo exchange"));
+    }
+
+    #[test]
+    fn nests_regions() {
+        let (solver, halo, pack) = (region_id("solver"), region_id("halo"), region_id("pack"));
+        assert_eq!(current(), None);
+
+        assert_eq!(enter(solver), None);
+        assert_eq!(enter(halo), Some(solver));
+        assert_eq!(current(), Some(halo));
+        assert_eq!(exit(halo), vec![halo]);
+        assert_eq!(current(), Some(solver));
+
+        // Ending a region also ends the regions nested in it
+        enter(halo);
+        enter(pack);
+        assert_eq!(exit(solver), vec![pack, halo, solver]);
+        assert_eq!(current(), None);
+        assert!(exit(solver).is_empty());
+    }
+}
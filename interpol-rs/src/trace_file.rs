@@ -0,0 +1,598 @@
+use crate::clock::TscClock;
+use crate::metadata::Metadata;
+use crate::mpi_events::MpiEvent;
+use crate::types::MpiRank;
+use crate::{InterpolError, InterpolErrorKind};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::io::{BufRead, BufWriter, Seek, SeekFrom, Write};
+
+/// The contents of a JSON trace file: the metadata of the run and its events.
+///
+/// Traces are written as `{"metadata": {...}, "events": [...]}`. Traces written by older versions
+/// of `interpol-rs` are a bare array of events; they are still read, without metadata.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TraceFile {
+    pub metadata: Option<Metadata>,
+    pub events: Vec<MpiEvent>,
+}
+
+/// A borrowed view of a `TraceFile`, used to serialize events without copying them.
+#[derive(Serialize)]
+struct TraceFileRef<'a> {
+    metadata: Option<&'a Metadata>,
+    events: EventsRef<'a>,
+}
+
+/// A list of events to serialize, with the calibrated TSC of each rank if nanosecond timestamps
+/// must be added to them.
+struct EventsRef<'a> {
+    events: &'a [MpiEvent],
+    clocks: Option<HashMap<MpiRank, TscClock>>,
+    /// Whether the events are in the timebase of rank 0, whose clock must then be used for all.
+    global_timebase: bool,
+}
+
+/// An event serialized along with its start and duration in nanoseconds.
+#[derive(Serialize)]
+struct TimedEvent<'a> {
+    #[serde(flatten)]
+    event: &'a MpiEvent,
+    start_ns: u64,
+    duration_ns: u64,
+}
+
+/// An event serialized with nanosecond timestamps if the TSC of its rank is calibrated.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum MaybeTimedEvent<'a> {
+    Timed(TimedEvent<'a>),
+    Untimed(&'a MpiEvent),
+}
+
+impl<'a> MaybeTimedEvent<'a> {
+    /// Adds nanosecond timestamps to an event if the TSC of its rank (or of rank 0, if the events
+    /// are in its timebase) is calibrated.
+    fn new(
+        event: &'a MpiEvent,
+        clocks: &HashMap<MpiRank, TscClock>,
+        global_timebase: bool,
+    ) -> Self {
+        let rank = if global_timebase {
+            0
+        } else {
+            event.current_rank()
+        };
+        match clocks.get(&rank) {
+            Some(clock) => MaybeTimedEvent::Timed(TimedEvent {
+                event,
+                start_ns: clock.tsc_to_ns(event.tsc()),
+                duration_ns: clock.cycles_to_ns(event.duration()),
+            }),
+            None => MaybeTimedEvent::Untimed(event),
+        }
+    }
+}
+
+impl Serialize for EventsRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let clocks = match &self.clocks {
+            Some(clocks) => clocks,
+            None => return serializer.collect_seq(self.events),
+        };
+
+        serializer.collect_seq(
+            self.events
+                .iter()
+                .map(|event| MaybeTimedEvent::new(event, clocks, self.global_timebase)),
+        )
+    }
+}
+
+impl TraceFile {
+    /// Deserializes a trace file from JSON, with or without metadata.
+    pub fn from_json(contents: &str) -> serde_json::Result<Self> {
+        if contents.trim_start().starts_with('[') {
+            Ok(TraceFile {
+                metadata: None,
+                events: serde_json::from_str(contents)?,
+            })
+        } else {
+            serde_json::from_str(contents)
+        }
+    }
+}
+
+/// Serializes a list of events and their metadata to JSON, optionally pretty-printed.
+///
+/// If `with_ns` is set, the start of each event (in nanoseconds since the Unix epoch) and its
+/// duration (in nanoseconds) are added to it as `start_ns` and `duration_ns`, provided that the
+/// metadata holds the calibrated TSC of the rank that recorded it (or of rank 0, if the events have
+/// been translated to its timebase).
+pub fn to_json(
+    metadata: Option<&Metadata>,
+    events: &[MpiEvent],
+    pretty: bool,
+    with_ns: bool,
+) -> serde_json::Result<String> {
+    let trace = TraceFileRef {
+        metadata,
+        events: EventsRef {
+            events,
+            clocks: clocks(metadata, with_ns),
+            global_timebase: metadata.is_some_and(|metadata| metadata.global_timebase),
+        },
+    };
+    if pretty {
+        serde_json::to_string_pretty(&trace)
+    } else {
+        serde_json::to_string(&trace)
+    }
+}
+
+/// Returns the calibrated TSC of every rank, if nanosecond timestamps must be added to events.
+fn clocks(metadata: Option<&Metadata>, with_ns: bool) -> Option<HashMap<MpiRank, TscClock>> {
+    match metadata {
+        Some(metadata) if with_ns => Some(
+            metadata
+                .ranks
+                .iter()
+                .filter_map(|info| Some((info.rank, info.tsc_clock.clone()?)))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Serializes an empty trace, and returns it along with the length of its metadata (i.e. of
+/// everything before the `,` that precedes the `events` key) and the offset right after the `[`
+/// that opens its events.
+fn empty_trace(
+    metadata: Option<&Metadata>,
+    pretty: bool,
+) -> Result<(String, usize, usize), InterpolError> {
+    let trace = to_json(metadata, &[], pretty, false)
+        .map_err(|e| InterpolError::new(InterpolErrorKind::Serialization, e.to_string()))?;
+    let events_key = trace.rfind("\"events\"").unwrap_or_default();
+    let metadata_len = trace[..events_key].rfind(',').unwrap_or_default();
+    let events_start = trace.rfind("[]").unwrap_or_default() + 1;
+    Ok((trace, metadata_len, events_start))
+}
+
+/// A JSON trace written one event at a time, whose metadata can be rewritten once every event
+/// has been written.
+///
+/// The trace is byte for byte the one `to_json` would produce, except for the padding of the
+/// rewritten metadata.
+pub struct JsonTraceWriter<W: Write + Seek> {
+    writer: BufWriter<W>,
+    pretty: bool,
+    clocks: Option<HashMap<MpiRank, TscClock>>,
+    global_timebase: bool,
+    metadata_len: usize,
+    events: usize,
+}
+
+impl<W: Write + Seek> JsonTraceWriter<W> {
+    /// Writes the metadata of a trace to `writer` (see `to_json` for `pretty` and `with_ns`).
+    ///
+    /// The final metadata, given to `finish`, must not be longer once serialized than this one.
+    pub fn new(
+        writer: W,
+        metadata: Option<&Metadata>,
+        pretty: bool,
+        with_ns: bool,
+    ) -> Result<Self, InterpolError> {
+        let (trace, metadata_len, events_start) = empty_trace(metadata, pretty)?;
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(&trace.as_bytes()[..events_start])?;
+
+        Ok(Self {
+            writer,
+            pretty,
+            clocks: clocks(metadata, with_ns),
+            global_timebase: metadata.is_some_and(|metadata| metadata.global_timebase),
+            metadata_len,
+            events: 0,
+        })
+    }
+
+    /// Appends an event to the trace.
+    pub fn write_event(&mut self, event: &MpiEvent) -> Result<(), InterpolError> {
+        let serialized = match &self.clocks {
+            Some(clocks) => {
+                let event = MaybeTimedEvent::new(event, clocks, self.global_timebase);
+                self.serialize(&event)
+            }
+            None => self.serialize(event),
+        }
+        .map_err(|e| InterpolError::new(InterpolErrorKind::Serialization, e.to_string()))?;
+
+        if self.events > 0 {
+            self.writer.write_all(b",")?;
+        }
+        if self.pretty {
+            // Events are nested two levels deep in the trace
+            self.writer.write_all(b"\n    ")?;
+            self.writer
+                .write_all(serialized.replace('\n', "\n    ").as_bytes())?;
+        } else {
+            self.writer.write_all(serialized.as_bytes())?;
+        }
+        self.events += 1;
+        Ok(())
+    }
+
+    fn serialize<T: Serialize>(&self, value: &T) -> serde_json::Result<String> {
+        if self.pretty {
+            serde_json::to_string_pretty(value)
+        } else {
+            serde_json::to_string(value)
+        }
+    }
+
+    /// Closes the list of events, overwrites the metadata of the trace with `metadata`, padded
+    /// with spaces to the length of the metadata it was created with, and flushes the trace.
+    pub fn finish(mut self, metadata: Option<&Metadata>) -> Result<W, InterpolError> {
+        match (self.pretty, self.events) {
+            (false, _) => self.writer.write_all(b"]}")?,
+            (true, 0) => self.writer.write_all(b"]\n}")?,
+            (true, _) => self.writer.write_all(b"\n  ]\n}")?,
+        }
+
+        let (trace, metadata_len, _) = empty_trace(metadata, self.pretty)?;
+        if metadata_len > self.metadata_len {
+            return Err(InterpolError::new(
+                InterpolErrorKind::Serialization,
+                "the final metadata is longer than the initial one",
+            ));
+        }
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&trace.as_bytes()[..metadata_len])?;
+        self.writer
+            .write_all(" ".repeat(self.metadata_len - metadata_len).as_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer
+            .into_inner()
+            .map_err(|e| InterpolError::from(e.into_error()))
+    }
+}
+
+/// A JSON trace read one event at a time, so that it never has to fit in memory.
+///
+/// Events are split from the trace without being parsed, and only then deserialized one by one.
+/// The iterator stops after the first error.
+pub struct JsonTraceReader<R> {
+    reader: R,
+    value: Vec<u8>,
+    first: bool,
+    done: bool,
+}
+
+impl<R: BufRead> JsonTraceReader<R> {
+    /// Reads a JSON trace from `reader` up to its first event, and returns its metadata along
+    /// with a reader of its events. Traces made of a bare array of events have no metadata.
+    pub fn new(reader: R) -> Result<(Option<Metadata>, Self), InterpolError> {
+        let mut trace = Self {
+            reader,
+            value: Vec::new(),
+            first: true,
+            done: false,
+        };
+        let metadata = trace.read_metadata()?;
+        Ok((metadata, trace))
+    }
+
+    fn read_metadata(&mut self) -> Result<Option<Metadata>, InterpolError> {
+        let mut metadata = None;
+        if self.next_byte()? == b'[' {
+            self.reader.consume(1);
+            return Ok(metadata);
+        }
+
+        self.expect(b'{')?;
+        loop {
+            self.read_value()?;
+            let key: String = self.parse_value()?;
+            self.expect(b':')?;
+            match key.as_str() {
+                "events" => {
+                    self.expect(b'[')?;
+                    return Ok(metadata);
+                }
+                "metadata" => {
+                    self.read_value()?;
+                    metadata = self.parse_value()?;
+                }
+                _ => self.read_value()?,
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    /// Skips whitespace, and returns the next byte without consuming it.
+    fn next_byte(&mut self) -> Result<u8, InterpolError> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            let whitespace = buf.iter().take_while(|b| b.is_ascii_whitespace()).count();
+            match buf.get(whitespace) {
+                Some(&byte) => {
+                    self.reader.consume(whitespace);
+                    return Ok(byte);
+                }
+                None if buf.is_empty() => return Err(truncated()),
+                None => self.reader.consume(whitespace),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), InterpolError> {
+        match self.next_byte()? {
+            byte if byte == expected => {
+                self.reader.consume(1);
+                Ok(())
+            }
+            byte => Err(InterpolError::new(
+                InterpolErrorKind::Deserialization,
+                format!(
+                    "expected `{}`, found `{}`",
+                    expected as char,
+                    byte.escape_ascii()
+                ),
+            )),
+        }
+    }
+
+    /// Reads the next JSON value into `self.value`, without parsing it.
+    fn read_value(&mut self) -> Result<(), InterpolError> {
+        self.value.clear();
+        self.next_byte()?;
+
+        let mut depth = 0_usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                return Err(truncated());
+            }
+
+            let mut len = 0;
+            let mut end = false;
+            for &byte in buf {
+                if in_string {
+                    len += 1;
+                    if escaped {
+                        escaped = false;
+                    } else if byte == b'\\' {
+                        escaped = true;
+                    } else if byte == b'"' {
+                        in_string = false;
+                        end = depth == 0;
+                    }
+                } else if depth == 0
+                    && (matches!(byte, b',' | b':' | b']' | b'}') || byte.is_ascii_whitespace())
+                {
+                    // The end of a number or a literal
+                    end = true;
+                } else {
+                    len += 1;
+                    match byte {
+                        b'"' => in_string = true,
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            end = depth == 0;
+                        }
+                        _ => {}
+                    }
+                }
+                if end {
+                    break;
+                }
+            }
+
+            self.value.extend_from_slice(&buf[..len]);
+            self.reader.consume(len);
+            if end {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_value<T: serde::de::DeserializeOwned>(&self) -> Result<T, InterpolError> {
+        serde_json::from_slice(&self.value)
+            .map_err(|e| InterpolError::new(InterpolErrorKind::Deserialization, e.to_string()))
+    }
+
+    fn read_event(&mut self) -> Result<Option<MpiEvent>, InterpolError> {
+        if self.next_byte()? == b']' {
+            self.reader.consume(1);
+            return Ok(None);
+        }
+        if !self.first {
+            self.expect(b',')?;
+        }
+        self.first = false;
+
+        self.read_value()?;
+        self.parse_value().map(Some)
+    }
+}
+
+impl<R: BufRead> Iterator for JsonTraceReader<R> {
+    type Item = Result<MpiEvent, InterpolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let event = self.read_event().transpose();
+        self.done = !matches!(event, Some(Ok(_)));
+        event
+    }
+}
+
+fn truncated() -> InterpolError {
+    InterpolError::new(InterpolErrorKind::Deserialization, "truncated trace")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::RankInfo;
+    use crate::mpi_events::{management::mpi_init::MpiInit, synchronization::mpi_wait::MpiWait};
+
+    fn events() -> Vec<MpiEvent> {
+        vec![
+            MpiInit::new(0, 512, 0.1).into(),
+            MpiWait::new(0, 7, 4096, 128).into(),
+        ]
+    }
+
+    #[test]
+    fn reads_bare_arrays() {
+        let json = serde_json::to_string(&events()).expect("failed to serialize events");
+        let trace = TraceFile::from_json(&json).expect("failed to deserialize trace");
+
+        assert_eq!(trace.metadata, None);
+        assert_eq!(trace.events, events());
+    }
+
+    #[test]
+    fn round_trips_with_metadata() {
+        let metadata = Metadata {
+            interpol_version: String::from("0.3.0"),
+            command_line: vec![String::from("./a.out"), String::from("--verbose")],
+            start_date: String::from("2022-11-04T10:20:30Z"),
+            mpi_library_version: Some(String::from("MPICH Version: 4.0.2")),
+            world_size: Some(1),
+            provided_thread_lvl: Some(3),
+            ranks: vec![RankInfo {
+                rank: 0,
+                hostname: String::from("node0"),
+                pid: 1000,
+                tsc_clock: None,
+                init_sync: None,
+                finalize_sync: None,
+                drift: None,
+                incomplete: None,
+            }],
+            global_timebase: false,
+            causality: None,
+            filters: None,
+        };
+        let json =
+            to_json(Some(&metadata), &events(), true, false).expect("failed to serialize trace");
+        assert!(json.trim_start().starts_with('{'));
+
+        let trace = TraceFile::from_json(&json).expect("failed to deserialize trace");
+        assert_eq!(trace.metadata, Some(metadata));
+        assert_eq!(trace.events, events());
+    }
+
+    /// Returns the metadata of a single rank whose TSC is calibrated.
+    fn calibrated_metadata() -> Metadata {
+        let mut metadata: Metadata = serde_json::from_str(
+            "{\"interpol_version\":\"0.3.0\",\"command_line\":[],\"start_date\":\"\",\"mpi_library_version\":null,\"world_size\":null,\"provided_thread_lvl\":null,\"ranks\":[{\"rank\":0,\"hostname\":\"node0\",\"pid\":1000}]}",
+        )
+        .expect("failed to deserialize metadata");
+        metadata.ranks[0].tsc_clock = Some(TscClock {
+            init_tsc: 0,
+            init_time: 1.0,
+            frequency: 1e9,
+        });
+        metadata
+    }
+
+    #[test]
+    fn adds_ns_timestamps() {
+        let metadata = calibrated_metadata();
+
+        let json =
+            to_json(Some(&metadata), &events(), false, true).expect("failed to serialize trace");
+        assert!(json.contains("\"type\":\"MpiWait\",\"current_rank\":0,\"req\":7,\"tsc\":4096,\"duration\":128,\"start_ns\":1000004096,\"duration_ns\":128}"));
+
+        // Timestamps are ignored when reading the trace back
+        let trace = TraceFile::from_json(&json).expect("failed to deserialize trace");
+        assert_eq!(trace.events, events());
+    }
+
+    /// Writes a trace with a `JsonTraceWriter`, rewriting its metadata with `final_metadata`.
+    fn stream(
+        metadata: Option<&Metadata>,
+        final_metadata: Option<&Metadata>,
+        events: &[MpiEvent],
+        pretty: bool,
+    ) -> String {
+        let mut writer =
+            JsonTraceWriter::new(std::io::Cursor::new(Vec::new()), metadata, pretty, true)
+                .expect("failed to write trace");
+        for event in events {
+            writer.write_event(event).expect("failed to write event");
+        }
+        let bytes = writer
+            .finish(final_metadata)
+            .expect("failed to finish trace")
+            .into_inner();
+        String::from_utf8(bytes).expect("trace is not UTF-8")
+    }
+
+    #[test]
+    fn streams_like_to_json() {
+        let metadata = calibrated_metadata();
+        for pretty in [false, true] {
+            for events in [events(), Vec::new()] {
+                for metadata in [Some(&metadata), None] {
+                    let json = to_json(metadata, &events, pretty, true)
+                        .expect("failed to serialize trace");
+                    assert_eq!(stream(metadata, metadata, &events, pretty), json);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rewrites_metadata() {
+        let mut placeholder = calibrated_metadata();
+        placeholder.start_date = String::from("a date longer than the final one");
+        let metadata = calibrated_metadata();
+
+        for pretty in [false, true] {
+            let json = stream(Some(&placeholder), Some(&metadata), &events(), pretty);
+            let trace = TraceFile::from_json(&json).expect("failed to deserialize trace");
+            assert_eq!(trace.metadata.as_ref(), Some(&metadata));
+            assert_eq!(trace.events, events());
+        }
+    }
+
+    #[test]
+    fn reads_events_one_by_one() {
+        let metadata = calibrated_metadata();
+        let traces = [
+            to_json(Some(&metadata), &events(), false, true),
+            to_json(Some(&metadata), &events(), true, false),
+            serde_json::to_string_pretty(&events()),
+        ];
+        for (i, trace) in traces.into_iter().enumerate() {
+            let trace = trace.expect("failed to serialize trace");
+            let (read_metadata, reader) =
+                JsonTraceReader::new(trace.as_bytes()).expect("failed to read metadata");
+            assert_eq!(read_metadata.is_some(), i < 2);
+            let read: Vec<MpiEvent> = reader.collect::<Result<_, _>>().expect("failed to read");
+            assert_eq!(read, events());
+        }
+
+        let trace = to_json(None, &events(), false, false).expect("failed to serialize trace");
+        let truncated = &trace.as_bytes()[..trace.len() - 10];
+        let (_, mut reader) = JsonTraceReader::new(truncated).expect("failed to read metadata");
+        assert!(reader.next().is_some_and(|event| event.is_ok()));
+        let error = reader
+            .next()
+            .and_then(Result::err)
+            .expect("read a truncated event");
+        assert_eq!(error.kind(), InterpolErrorKind::Deserialization);
+        assert!(reader.next().is_none());
+    }
+}
@@ -9,6 +9,7 @@ pub type MpiTag = i32;
 // Others
 pub type Tsc = u64;
 pub type Usecs = f64;
+pub type RegionId = u32;
 
 #[derive(Debug, PartialEq, Eq)]
 #[repr(i8)]
@@ -28,6 +29,104 @@ pub enum MpiCallType {
     Igather,
     Ireduce,
     Iscatter,
+    Waitall,
+    Waitany,
+    Waitsome,
+    Testall,
+    Testany,
+    Testsome,
+    Bcast,
+    Reduce,
+    Allreduce,
+    Gather,
+    Scatter,
+    Allgather,
+    Alltoall,
+    Iallreduce,
+    Iallgather,
+    Ialltoall,
+    IreduceScatter,
+    Iscan,
+    Iexscan,
+    Gatherv,
+    Scatterv,
+    Allgatherv,
+    Alltoallv,
+    Igatherv,
+    Iscatterv,
+    Iallgatherv,
+    Ialltoallv,
+}
+
+impl MpiCallType {
+    /// Returns the name of the event recorded for this kind of call, as written in the `type`
+    /// field of serialized events.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            MpiCallType::Init => "MpiInit",
+            MpiCallType::Initthread => "MpiInitThread",
+            MpiCallType::Finalize => "MpiFinalize",
+            MpiCallType::Send => "MpiSend",
+            MpiCallType::Recv => "MpiRecv",
+            MpiCallType::Isend => "MpiIsend",
+            MpiCallType::Irecv => "MpiIrecv",
+            MpiCallType::Test => "MpiTest",
+            MpiCallType::Wait => "MpiWait",
+            MpiCallType::Barrier => "MpiBarrier",
+            MpiCallType::Ibarrier => "MpiIbarrier",
+            MpiCallType::Ibcast => "MpiIbcast",
+            MpiCallType::Igather => "MpiIgather",
+            MpiCallType::Ireduce => "MpiIreduce",
+            MpiCallType::Iscatter => "MpiIscatter",
+            MpiCallType::Waitall => "MpiWaitall",
+            MpiCallType::Waitany => "MpiWaitany",
+            MpiCallType::Waitsome => "MpiWaitsome",
+            MpiCallType::Testall => "MpiTestall",
+            MpiCallType::Testany => "MpiTestany",
+            MpiCallType::Testsome => "MpiTestsome",
+            MpiCallType::Bcast => "MpiBcast",
+            MpiCallType::Reduce => "MpiReduce",
+            MpiCallType::Allreduce => "MpiAllreduce",
+            MpiCallType::Gather => "MpiGather",
+            MpiCallType::Scatter => "MpiScatter",
+            MpiCallType::Allgather => "MpiAllgather",
+            MpiCallType::Alltoall => "MpiAlltoall",
+            MpiCallType::Iallreduce => "MpiIallreduce",
+            MpiCallType::Iallgather => "MpiIallgather",
+            MpiCallType::Ialltoall => "MpiIalltoall",
+            MpiCallType::IreduceScatter => "MpiIreduceScatter",
+            MpiCallType::Iscan => "MpiIscan",
+            MpiCallType::Iexscan => "MpiIexscan",
+            MpiCallType::Gatherv => "MpiGatherv",
+            MpiCallType::Scatterv => "MpiScatterv",
+            MpiCallType::Allgatherv => "MpiAllgatherv",
+            MpiCallType::Alltoallv => "MpiAlltoallv",
+            MpiCallType::Igatherv => "MpiIgatherv",
+            MpiCallType::Iscatterv => "MpiIscatterv",
+            MpiCallType::Iallgatherv => "MpiIallgatherv",
+            MpiCallType::Ialltoallv => "MpiIalltoallv",
+        }
+    }
+}
+
+/// The point of the run at which the clocks of the ranks are synchronized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i8)]
+pub enum ClockSyncPoint {
+    AtInit,
+    AtFinalize,
+}
+
+/// How the traces of every rank are brought together to be merged at `MPI_Finalize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i8)]
+pub enum Aggregation {
+    /// Every rank writes its trace file, which the merging rank reads back.
+    ViaFilesystem,
+    /// The traces of every rank are gathered on the merging rank.
+    ViaGather,
+    /// The traces are passed up a binary tree of ranks rooted at the merging rank.
+    ViaTree,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -1,14 +1,347 @@
 #include "interpol.h"
 #include "tsc.h"
 
+#include <limits.h>
 #include <mpi.h>
 #include <stdint.h>
 #include <stdio.h>
+#include <stdlib.h>
 #include <sys/time.h>
 
 /// Global variable that stores the rank of the current process.
 static MpiRank current_rank = -1;
 
+/// Passes information about the MPI library to the Rust backend, to be stored in
+/// the metadata of the trace.
+static void register_library_info()
+{
+    int world_size;
+    PMPI_Comm_size(MPI_COMM_WORLD, &world_size);
+
+    char library_version[MPI_MAX_LIBRARY_VERSION_STRING];
+    int len;
+    PMPI_Get_library_version(library_version, &len);
+
+    register_mpi_info(world_size, library_version);
+}
+
+/// Passes the date at which rank 0 initialized MPI to the Rust backend, which
+/// names the directory of the run after it unless the configuration or the
+/// batch scheduler gives it a name.
+///
+/// This must be done before `MPI_Init` is registered, as the trace files of
+/// the rank are created in that directory.
+static void register_run(Usecs time)
+{
+    Usecs start = time;
+    PMPI_Bcast(&start, 1, MPI_DOUBLE, 0, MPI_COMM_WORLD);
+    register_run_start(start);
+}
+
+/// Number of ping-pongs exchanged with rank 0 to synchronize the clocks.
+#define CLOCK_SYNC_ROUNDS 16
+
+/// Measures the offset between the TSC of the current rank and the TSC of rank
+/// 0 with ping-pongs, and passes it to the Rust backend.
+///
+/// This is done at `MPI_Init` and `MPI_Finalize`, so that the drift between the
+/// clocks can be corrected when the traces are merged.
+///
+/// Rank 0 answers the pings of every other rank in turn with its own TSC. Each
+/// rank keeps the ping-pong with the shortest round-trip, and assumes that the
+/// TSC of rank 0 was read halfway through it. The ping-pongs are exchanged on a
+/// duplicate of `MPI_COMM_WORLD` so that they cannot match messages of the
+/// application.
+static void synchronize_clocks(ClockSyncPoint point)
+{
+    MPI_Comm sync_comm;
+    PMPI_Comm_dup(MPI_COMM_WORLD, &sync_comm);
+
+    int world_size;
+    PMPI_Comm_size(sync_comm, &world_size);
+
+    if (current_rank == 0) {
+        for (int rank = 1; rank < world_size; rank++) {
+            for (int i = 0; i < CLOCK_SYNC_ROUNDS; i++) {
+                Tsc ping;
+                PMPI_Recv(&ping, 1, MPI_UINT64_T, rank, 0, sync_comm, MPI_STATUS_IGNORE);
+                Tsc const tsc = fenced_rdtscp();
+                PMPI_Send(&tsc, 1, MPI_UINT64_T, rank, 0, sync_comm);
+            }
+        }
+        register_clock_sync(point, fenced_rdtscp(), 0, 0);
+    } else {
+        Tsc best_tsc = 0;
+        Tsc best_round_trip = UINT64_MAX;
+        int64_t best_offset = 0;
+        for (int i = 0; i < CLOCK_SYNC_ROUNDS; i++) {
+            Tsc const start = fenced_rdtscp();
+            PMPI_Send(&start, 1, MPI_UINT64_T, 0, 0, sync_comm);
+            Tsc remote;
+            PMPI_Recv(&remote, 1, MPI_UINT64_T, 0, 0, sync_comm, MPI_STATUS_IGNORE);
+            Tsc const end = fenced_rdtscp();
+
+            if (end - start < best_round_trip) {
+                best_round_trip = end - start;
+                best_tsc = start + best_round_trip / 2;
+                best_offset = (int64_t)(remote - best_tsc);
+            }
+        }
+        register_clock_sync(point, best_tsc, best_offset, best_round_trip);
+    }
+
+    PMPI_Comm_free(&sync_comm);
+}
+
+/// Size of the chunks in which serialized traces are sent, as MPI counts are
+/// `int`s.
+#define TRACE_CHUNK_SIZE ((size_t)1 << 30)
+
+/// Sends the `len` bytes of serialized traces to `dest`, preceded by their
+/// length.
+static void send_traces(uint8_t const* buffer, size_t len, int dest, MPI_Comm comm)
+{
+    uint64_t const total = len;
+    PMPI_Send(&total, 1, MPI_UINT64_T, dest, 0, comm);
+    for (size_t sent = 0; sent < len; sent += TRACE_CHUNK_SIZE) {
+        size_t const chunk = len - sent < TRACE_CHUNK_SIZE ? len - sent : TRACE_CHUNK_SIZE;
+        PMPI_Send(buffer + sent, (int)chunk, MPI_BYTE, dest, 0, comm);
+    }
+}
+
+/// Receives serialized traces sent with `send_traces` from `source`, and
+/// stores their length in `len`. The returned buffer must be freed with `free`.
+static uint8_t* recv_traces(int source, MPI_Comm comm, size_t* len)
+{
+    uint64_t total;
+    PMPI_Recv(&total, 1, MPI_UINT64_T, source, 0, comm, MPI_STATUS_IGNORE);
+    uint8_t* buffer = malloc(total > 0 ? total : 1);
+    for (size_t received = 0; received < total; received += TRACE_CHUNK_SIZE) {
+        size_t const chunk = total - received < TRACE_CHUNK_SIZE ? total - received : TRACE_CHUNK_SIZE;
+        PMPI_Recv(buffer + received, (int)chunk, MPI_BYTE, source, 0, comm, MPI_STATUS_IGNORE);
+    }
+
+    *len = total;
+    return buffer;
+}
+
+/// Gathers the serialized traces of every rank on `root`, which merges them.
+///
+/// The lengths of the traces are gathered first. The traces themselves are
+/// gathered with a single `MPI_Gatherv` if they fit in its `int`
+/// displacements, and sent one by one to the root otherwise.
+static void gather_traces(int root, MPI_Comm comm)
+{
+    int world_size;
+    PMPI_Comm_size(comm, &world_size);
+
+    size_t len;
+    uint8_t* buffer = interpol_serialize_rank(&len);
+    uint64_t const local_len = len;
+
+    uint64_t* lens = NULL;
+    if (current_rank == root) {
+        lens = malloc(world_size * sizeof *lens);
+    }
+    PMPI_Gather(&local_len, 1, MPI_UINT64_T, lens, 1, MPI_UINT64_T, root, comm);
+
+    int fits = 1;
+    if (current_rank == root) {
+        uint64_t total = 0;
+        for (int rank = 0; rank < world_size; rank++) {
+            total += lens[rank];
+        }
+        fits = total <= INT_MAX;
+    }
+    PMPI_Bcast(&fits, 1, MPI_INT, root, comm);
+
+    if (current_rank != root) {
+        if (fits) {
+            PMPI_Gatherv(buffer, (int)len, MPI_BYTE, NULL, NULL, NULL, MPI_BYTE, root, comm);
+        } else {
+            send_traces(buffer, len, root, comm);
+        }
+        interpol_free_buffer(buffer, len);
+        return;
+    }
+
+    uint8_t const** buffers = malloc(world_size * sizeof *buffers);
+    size_t* sizes = malloc(world_size * sizeof *sizes);
+    uint8_t* gathered = NULL;
+    if (fits) {
+        int* counts = malloc(world_size * sizeof *counts);
+        int* displs = malloc(world_size * sizeof *displs);
+        int offset = 0;
+        for (int rank = 0; rank < world_size; rank++) {
+            counts[rank] = (int)lens[rank];
+            displs[rank] = offset;
+            offset += counts[rank];
+        }
+        gathered = malloc(offset > 0 ? offset : 1);
+        PMPI_Gatherv(buffer, (int)len, MPI_BYTE, gathered, counts, displs, MPI_BYTE, root, comm);
+        for (int rank = 0; rank < world_size; rank++) {
+            buffers[rank] = gathered + displs[rank];
+            sizes[rank] = counts[rank];
+        }
+        free(counts);
+        free(displs);
+    } else {
+        for (int rank = 0; rank < world_size; rank++) {
+            if (rank == root) {
+                buffers[rank] = buffer;
+                sizes[rank] = len;
+            } else {
+                buffers[rank] = recv_traces(rank, comm, &sizes[rank]);
+            }
+        }
+    }
+
+    interpol_write_merged(buffers, sizes, world_size);
+
+    if (!fits) {
+        for (int rank = 0; rank < world_size; rank++) {
+            if (rank != root) {
+                free((uint8_t*)buffers[rank]);
+            }
+        }
+    }
+    free(gathered);
+    free(buffers);
+    free(sizes);
+    free(lens);
+    interpol_free_buffer(buffer, len);
+}
+
+/// Passes the serialized traces up a binary tree of ranks rooted at `root`:
+/// every rank merges the traces of its children with its own and sends them to
+/// its parent, so that the root ends up with the traces of every rank, which it
+/// merges.
+static void reduce_traces(int root, MPI_Comm comm)
+{
+    int world_size;
+    PMPI_Comm_size(comm, &world_size);
+    // The position of the rank in the tree, whose node 0 is the root
+    int const node = (current_rank - root + world_size) % world_size;
+
+    uint8_t const* buffers[3];
+    size_t lens[3];
+    size_t count = 0;
+
+    size_t len;
+    uint8_t* own = interpol_serialize_rank(&len);
+    buffers[count] = own;
+    lens[count++] = len;
+
+    uint8_t* children[2] = { NULL, NULL };
+    for (int i = 0; i < 2; i++) {
+        int const child = 2 * node + 1 + i;
+        if (child < world_size) {
+            children[i] = recv_traces((child + root) % world_size, comm, &lens[count]);
+            buffers[count++] = children[i];
+        }
+    }
+
+    size_t merged_len;
+    uint8_t* merged = interpol_merge_buffers(buffers, lens, count, &merged_len);
+    interpol_free_buffer(own, len);
+    free(children[0]);
+    free(children[1]);
+
+    if (node == 0) {
+        uint8_t const* const all = merged;
+        interpol_write_merged(&all, &merged_len, 1);
+    } else {
+        send_traces(merged, merged_len, ((node - 1) / 2 + root) % world_size, comm);
+    }
+    interpol_free_buffer(merged, merged_len);
+}
+
+/// Gathers the names of the trace files written by every rank on `root`,
+/// which lists them in the manifest of the run so that only these files are
+/// merged.
+static void write_manifest(int root, MPI_Comm comm)
+{
+    int world_size;
+    PMPI_Comm_size(comm, &world_size);
+
+    size_t len;
+    uint8_t* files = interpol_rank_files(&len);
+    int const local_len = (int)len;
+
+    int* counts = NULL;
+    if (current_rank == root) {
+        counts = malloc(world_size * sizeof *counts);
+    }
+    PMPI_Gather(&local_len, 1, MPI_INT, counts, 1, MPI_INT, root, comm);
+
+    if (current_rank != root) {
+        PMPI_Gatherv(files, local_len, MPI_BYTE, NULL, NULL, NULL, MPI_BYTE, root, comm);
+        interpol_free_buffer(files, len);
+        return;
+    }
+
+    int* displs = malloc(world_size * sizeof *displs);
+    int offset = 0;
+    for (int rank = 0; rank < world_size; rank++) {
+        displs[rank] = offset;
+        offset += counts[rank];
+    }
+    uint8_t* gathered = malloc(offset > 0 ? offset : 1);
+    PMPI_Gatherv(files, local_len, MPI_BYTE, gathered, counts, displs, MPI_BYTE, root, comm);
+
+    uint8_t const** buffers = malloc(world_size * sizeof *buffers);
+    size_t* sizes = malloc(world_size * sizeof *sizes);
+    for (int rank = 0; rank < world_size; rank++) {
+        buffers[rank] = gathered + displs[rank];
+        sizes[rank] = counts[rank];
+    }
+    interpol_write_manifest(buffers, sizes, world_size);
+
+    free(buffers);
+    free(sizes);
+    free(gathered);
+    free(displs);
+    free(counts);
+    interpol_free_buffer(files, len);
+}
+
+/// Brings the traces of every rank together on the merging rank, which merges
+/// them into a single trace.
+///
+/// The traces are either written to trace files, listed in the manifest of the
+/// run and read back by the merging rank, or aggregated in-band. They are
+/// exchanged on a duplicate of `MPI_COMM_WORLD` so that they cannot match
+/// messages of the application.
+static void aggregate_traces()
+{
+    int world_size;
+    PMPI_Comm_size(MPI_COMM_WORLD, &world_size);
+
+    int root = interpol_merge_root();
+    if (root >= world_size) {
+        if (current_rank == 0) {
+            fprintf(stderr, "[interpol]: the merge root %d is not a rank of the job, merging on rank 0\n", root);
+        }
+        root = 0;
+    }
+
+    MPI_Comm comm;
+    PMPI_Comm_dup(MPI_COMM_WORLD, &comm);
+    Aggregation const aggregation = interpol_aggregation();
+    if (aggregation == ViaFilesystem) {
+        // The root only gets the manifest once every rank has written its files
+        write_manifest(root, comm);
+        if (current_rank == root) {
+            sort_all_traces();
+        }
+    } else if (aggregation == ViaGather) {
+        gather_traces(root, comm);
+    } else {
+        reduce_traces(root, comm);
+    }
+    PMPI_Comm_free(&comm);
+}
+
 /** ------------------------------------------------------------------------ **
  * Management functions.                                                      *
  ** ------------------------------------------------------------------------ **/
@@ -25,6 +358,7 @@ int MPI_Init(int* argc, char*** argv)
 
     // Set the rank of the current MPI process/thread
     PMPI_Comm_rank(MPI_COMM_WORLD, &current_rank);
+    register_run(timeofday.tv_sec + timeofday.tv_usec / 1e6);
 
     MpiCall const init = {
         .kind = Init,
@@ -45,6 +379,8 @@ int MPI_Init(int* argc, char*** argv)
     };
 
     register_mpi_call(init);
+    synchronize_clocks(AtInit);
+    register_library_info();
     return ret;
 }
 
@@ -60,6 +396,7 @@ int MPI_Init_thread(int* argc, char*** argv, int required, int* provided)
 
     // Set the rank of the current MPI process/thread
     PMPI_Comm_rank(MPI_COMM_WORLD, &current_rank);
+    register_run(timeofday.tv_sec + timeofday.tv_usec / 1e6);
 
     MpiCall const initthread = {
         .kind = Initthread,
@@ -80,11 +417,14 @@ int MPI_Init_thread(int* argc, char*** argv, int required, int* provided)
     };
 
     register_mpi_call(initthread);
+    synchronize_clocks(AtInit);
+    register_library_info();
     return ret;
 }
 
 int MPI_Finalize()
 {
+    synchronize_clocks(AtFinalize);
     PMPI_Barrier(MPI_COMM_WORLD);
     // Measure the current time and TSC.
     Tsc const tsc = fenced_rdtscp();
@@ -111,10 +451,7 @@ int MPI_Finalize()
 
     register_mpi_call(finalize);
 
-    PMPI_Barrier(MPI_COMM_WORLD);
-    if (current_rank == 0) {
-        sort_all_traces();
-    }
+    aggregate_traces();
 
     int ret = PMPI_Finalize();
     return ret;
@@ -370,177 +707,1374 @@ int MPI_Wait(MPI_Request* request, MPI_Status* status)
     return ret;
 }
 
-/** ------------------------------------------------------------------------ **
- * Collective functions.                                                      *
- ** ------------------------------------------------------------------------ **/
+/// Returns the identifiers of `count` requests, to be passed to the Rust
+/// backend. They must be read before the requests are completed, as completed
+/// requests are set to `MPI_REQUEST_NULL`. The array must be freed by the
+/// caller.
+static MpiReq* request_ids(int count, MPI_Request const* requests)
+{
+    MpiReq* reqs = malloc((count > 0 ? count : 1) * sizeof *reqs);
+    for (int i = 0; i < count; ++i) {
+        reqs[i] = PMPI_Request_c2f(requests[i]);
+    }
+    return reqs;
+}
 
-int MPI_Ibcast(void* buf, int count, MPI_Datatype datatype, int root,
-               MPI_Comm comm, MPI_Request* request)
+int MPI_Waitall(int count, MPI_Request requests[], MPI_Status statuses[])
 {
+    MpiReq* reqs = request_ids(count, requests);
     Tsc const tsc = rdtsc();
-    int ret = PMPI_Ibcast(buf, count, datatype, root, comm, request);
+    int ret = PMPI_Waitall(count, requests, statuses);
     Tsc const duration = rdtsc() - tsc;
 
-    int nb_bytes;
-    PMPI_Type_size(datatype, &nb_bytes);
-
-    MpiCall const ibcast = {
-        .kind = Ibcast,
+    MpiCall const waitall = {
+        .kind = Waitall,
         .time = -1.0,
         .tsc = tsc,
         .duration = duration,
         .current_rank = current_rank,
-        .partner_rank = root,
-        .nb_bytes_s = nb_bytes * count,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
         .nb_bytes_r = 0,
-        .comm = PMPI_Comm_c2f(comm),
-        .req = PMPI_Request_c2f(*request),
+        .comm = -1,
+        .req = -1,
         .tag = -1,
         .required_thread_lvl = -1,
         .provided_thread_lvl = -1,
         .op_type = -1,
-        .finished = false,
+        .finished = true,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = count,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
     };
 
-    register_mpi_call(ibcast);
+    register_mpi_call_arrays(waitall, arrays);
+    free(reqs);
     return ret;
 }
 
-int MPI_Igather(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
-                void* recvbuf, int recvcount, MPI_Datatype recvtype, int root,
-                MPI_Comm comm, MPI_Request* request)
+int MPI_Waitany(int count, MPI_Request requests[], int* index,
+                MPI_Status* status)
 {
+    MpiReq* reqs = request_ids(count, requests);
     Tsc const tsc = rdtsc();
-    int ret = PMPI_Igather(sendbuf, sendcount, sendtype, recvbuf, recvcount,
-                           recvtype, root, comm, request);
+    int ret = PMPI_Waitany(count, requests, index, status);
     Tsc const duration = rdtsc() - tsc;
 
-    int nb_bytes_send, nb_bytes_recv;
-    PMPI_Type_size(sendtype, &nb_bytes_send);
-    PMPI_Type_size(recvtype, &nb_bytes_recv);
+    // `index` is `MPI_UNDEFINED` if every request was inactive
+    int const completed = *index;
+    MpiCall const waitany = {
+        .kind = Waitany,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = completed != MPI_UNDEFINED,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = count,
+        .indices = &completed,
+        .nb_indices = completed != MPI_UNDEFINED ? 1 : 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
 
-    MpiCall const igather = {
-        .kind = Igather,
+    register_mpi_call_arrays(waitany, arrays);
+    free(reqs);
+    return ret;
+}
+
+int MPI_Waitsome(int incount, MPI_Request requests[], int* outcount,
+                 int indices[], MPI_Status statuses[])
+{
+    MpiReq* reqs = request_ids(incount, requests);
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Waitsome(incount, requests, outcount, indices, statuses);
+    Tsc const duration = rdtsc() - tsc;
+
+    // `outcount` is `MPI_UNDEFINED` if every request was inactive
+    int const nb_completed = *outcount != MPI_UNDEFINED ? *outcount : 0;
+    MpiCall const waitsome = {
+        .kind = Waitsome,
         .time = -1.0,
         .tsc = tsc,
         .duration = duration,
         .current_rank = current_rank,
-        .partner_rank = root,
-        .nb_bytes_s = nb_bytes_send * sendcount,
-        .nb_bytes_r = nb_bytes_recv * recvcount,
-        .comm = PMPI_Comm_c2f(comm),
-        .req = PMPI_Request_c2f(*request),
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
         .tag = -1,
         .required_thread_lvl = -1,
         .provided_thread_lvl = -1,
         .op_type = -1,
-        .finished = false,
+        .finished = nb_completed > 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = incount,
+        .indices = indices,
+        .nb_indices = nb_completed,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
     };
 
-    register_mpi_call(igather);
+    register_mpi_call_arrays(waitsome, arrays);
+    free(reqs);
     return ret;
 }
 
-int MPI_Iscatter(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
-                 void* recvbuf, int recvcount, MPI_Datatype recvtype, int root,
-                 MPI_Comm comm, MPI_Request* request)
+int MPI_Testall(int count, MPI_Request requests[], int* flag,
+                MPI_Status statuses[])
 {
+    MpiReq* reqs = request_ids(count, requests);
     Tsc const tsc = rdtsc();
-    int ret = PMPI_Iscatter(sendbuf, sendcount, sendtype, recvbuf, recvcount,
-                            recvtype, root, comm, request);
+    int ret = PMPI_Testall(count, requests, flag, statuses);
     Tsc const duration = rdtsc() - tsc;
 
-    int nb_bytes_send, nb_bytes_recv;
-    PMPI_Type_size(sendtype, &nb_bytes_send);
-    PMPI_Type_size(recvtype, &nb_bytes_recv);
+    MpiCall const testall = {
+        .kind = Testall,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = *flag != 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = count,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
 
-    MpiCall const iscatter = {
-        .kind = Iscatter,
+    register_mpi_call_arrays(testall, arrays);
+    free(reqs);
+    return ret;
+}
+
+int MPI_Testany(int count, MPI_Request requests[], int* index, int* flag,
+                MPI_Status* status)
+{
+    MpiReq* reqs = request_ids(count, requests);
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Testany(count, requests, index, flag, status);
+    Tsc const duration = rdtsc() - tsc;
+
+    // `index` is `MPI_UNDEFINED` if no request completed or if every request
+    // was inactive
+    int const completed = *index;
+    MpiCall const testany = {
+        .kind = Testany,
         .time = -1.0,
         .tsc = tsc,
         .duration = duration,
         .current_rank = current_rank,
-        .partner_rank = root,
-        .nb_bytes_s = nb_bytes_send * sendcount,
-        .nb_bytes_r = nb_bytes_recv * recvcount,
-        .comm = PMPI_Comm_c2f(comm),
-        .req = PMPI_Request_c2f(*request),
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
         .tag = -1,
         .required_thread_lvl = -1,
         .provided_thread_lvl = -1,
         .op_type = -1,
-        .finished = false,
+        .finished = *flag != 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = count,
+        .indices = &completed,
+        .nb_indices = completed != MPI_UNDEFINED ? 1 : 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
     };
 
-    register_mpi_call(iscatter);
+    register_mpi_call_arrays(testany, arrays);
+    free(reqs);
     return ret;
 }
 
-int MPI_Ireduce(const void* sendbuf, void* recvbuf, int count,
-                MPI_Datatype datatype, MPI_Op op, int root, MPI_Comm comm,
-                MPI_Request* request)
+int MPI_Testsome(int incount, MPI_Request requests[], int* outcount,
+                 int indices[], MPI_Status statuses[])
 {
+    MpiReq* reqs = request_ids(incount, requests);
     Tsc const tsc = rdtsc();
-    int ret = PMPI_Ireduce(sendbuf, recvbuf, count, datatype, op, root, comm,
-                           request);
+    int ret = PMPI_Testsome(incount, requests, outcount, indices, statuses);
     Tsc const duration = rdtsc() - tsc;
 
-    int nb_bytes;
-    PMPI_Type_size(datatype, &nb_bytes);
+    // `outcount` is `MPI_UNDEFINED` if every request was inactive
+    int const nb_completed = *outcount != MPI_UNDEFINED ? *outcount : 0;
+    MpiCall const testsome = {
+        .kind = Testsome,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = nb_completed > 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = incount,
+        .indices = indices,
+        .nb_indices = nb_completed,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
+
+    register_mpi_call_arrays(testsome, arrays);
+    free(reqs);
+    return ret;
+}
+
+/** ------------------------------------------------------------------------ **
+ * Collective functions.                                                      *
+ ** ------------------------------------------------------------------------ **/
 
-    MpiOp op_type;
+/// Returns the type of a reduction operation, as passed to the Rust backend, or
+/// -1 if it is a user-defined operation.
+static MpiOp op_type(MPI_Op op)
+{
     if (op == MPI_MAX) {
-        op_type = Max;
+        return Max;
     } else if (op == MPI_MIN) {
-        op_type = Min;
+        return Min;
     } else if (op == MPI_SUM) {
-        op_type = Sum;
+        return Sum;
     } else if (op == MPI_PROD) {
-        op_type = Prod;
+        return Prod;
     } else if (op == MPI_LAND) {
-        op_type = Land;
+        return Land;
     } else if (op == MPI_BAND) {
-        op_type = Band;
+        return Band;
     } else if (op == MPI_LOR) {
-        op_type = Lor;
+        return Lor;
     } else if (op == MPI_BOR) {
-        op_type = Bor;
+        return Bor;
     } else if (op == MPI_LXOR) {
-        op_type = Lxor;
+        return Lxor;
     } else if (op == MPI_BXOR) {
-        op_type = Bxor;
+        return Bxor;
     } else if (op == MPI_MAXLOC) {
-        op_type = Maxloc;
+        return Maxloc;
     } else if (op == MPI_MINLOC) {
-        op_type = Minloc;
+        return Minloc;
     } else if (op == MPI_REPLACE) {
-        op_type = Replace;
+        return Replace;
     } else if (op == MPI_OP_NULL) {
-        op_type = Opnull;
+        return Opnull;
     } else if (op == MPI_NO_OP) {
-        op_type = Opnull;
+        return Opnull;
     } else {
-        op_type = -1;
+        return -1;
     }
+}
 
-    MpiCall const ireduce = {
-        .kind = Ireduce,
+int MPI_Bcast(void* buf, int count, MPI_Datatype datatype, int root,
+              MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Bcast(buf, count, datatype, root, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    MpiCall const bcast = {
+        .kind = Bcast,
         .time = -1.0,
         .tsc = tsc,
         .duration = duration,
         .current_rank = current_rank,
         .partner_rank = root,
-        .nb_bytes_s = nb_bytes,
-        .nb_bytes_r = nb_bytes * count,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = 0,
         .comm = PMPI_Comm_c2f(comm),
-        .req = PMPI_Request_c2f(*request),
+        .req = -1,
         .tag = -1,
         .required_thread_lvl = -1,
         .provided_thread_lvl = -1,
-        .op_type = op_type,
+        .op_type = -1,
         .finished = false,
     };
 
-    register_mpi_call(ireduce);
+    register_mpi_call(bcast);
     return ret;
 }
+
+int MPI_Reduce(const void* sendbuf, void* recvbuf, int count,
+               MPI_Datatype datatype, MPI_Op op, int root, MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Reduce(sendbuf, recvbuf, count, datatype, op, root, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    MpiCall const reduce = {
+        .kind = Reduce,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = 0,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(op),
+        .finished = false,
+    };
+
+    register_mpi_call(reduce);
+    return ret;
+}
+
+int MPI_Allreduce(const void* sendbuf, void* recvbuf, int count,
+                  MPI_Datatype datatype, MPI_Op op, MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Allreduce(sendbuf, recvbuf, count, datatype, op, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    MpiCall const allreduce = {
+        .kind = Allreduce,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = 0,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(op),
+        .finished = false,
+    };
+
+    register_mpi_call(allreduce);
+    return ret;
+}
+
+int MPI_Gather(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+               void* recvbuf, int recvcount, MPI_Datatype recvtype, int root,
+               MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Gather(sendbuf, sendcount, sendtype, recvbuf, recvcount,
+                          recvtype, root, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(sendtype, &nb_bytes_send);
+    PMPI_Type_size(recvtype, &nb_bytes_recv);
+
+    MpiCall const gather = {
+        .kind = Gather,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes_send * sendcount,
+        .nb_bytes_r = nb_bytes_recv * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(gather);
+    return ret;
+}
+
+int MPI_Scatter(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                void* recvbuf, int recvcount, MPI_Datatype recvtype, int root,
+                MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Scatter(sendbuf, sendcount, sendtype, recvbuf, recvcount,
+                           recvtype, root, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(sendtype, &nb_bytes_send);
+    PMPI_Type_size(recvtype, &nb_bytes_recv);
+
+    MpiCall const scatter = {
+        .kind = Scatter,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes_send * sendcount,
+        .nb_bytes_r = nb_bytes_recv * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(scatter);
+    return ret;
+}
+
+int MPI_Allgather(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                  void* recvbuf, int recvcount, MPI_Datatype recvtype,
+                  MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Allgather(sendbuf, sendcount, sendtype, recvbuf, recvcount,
+                             recvtype, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(sendtype, &nb_bytes_send);
+    PMPI_Type_size(recvtype, &nb_bytes_recv);
+
+    MpiCall const allgather = {
+        .kind = Allgather,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send * sendcount,
+        .nb_bytes_r = nb_bytes_recv * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(allgather);
+    return ret;
+}
+
+int MPI_Alltoall(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                 void* recvbuf, int recvcount, MPI_Datatype recvtype,
+                 MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Alltoall(sendbuf, sendcount, sendtype, recvbuf, recvcount,
+                            recvtype, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(sendtype, &nb_bytes_send);
+    PMPI_Type_size(recvtype, &nb_bytes_recv);
+
+    MpiCall const alltoall = {
+        .kind = Alltoall,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send * sendcount,
+        .nb_bytes_r = nb_bytes_recv * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(alltoall);
+    return ret;
+}
+
+int MPI_Ibcast(void* buf, int count, MPI_Datatype datatype, int root,
+               MPI_Comm comm, MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Ibcast(buf, count, datatype, root, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    MpiCall const ibcast = {
+        .kind = Ibcast,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = 0,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(ibcast);
+    return ret;
+}
+
+int MPI_Igather(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                void* recvbuf, int recvcount, MPI_Datatype recvtype, int root,
+                MPI_Comm comm, MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Igather(sendbuf, sendcount, sendtype, recvbuf, recvcount,
+                           recvtype, root, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(sendtype, &nb_bytes_send);
+    PMPI_Type_size(recvtype, &nb_bytes_recv);
+
+    MpiCall const igather = {
+        .kind = Igather,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes_send * sendcount,
+        .nb_bytes_r = nb_bytes_recv * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(igather);
+    return ret;
+}
+
+int MPI_Iscatter(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                 void* recvbuf, int recvcount, MPI_Datatype recvtype, int root,
+                 MPI_Comm comm, MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Iscatter(sendbuf, sendcount, sendtype, recvbuf, recvcount,
+                            recvtype, root, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(sendtype, &nb_bytes_send);
+    PMPI_Type_size(recvtype, &nb_bytes_recv);
+
+    MpiCall const iscatter = {
+        .kind = Iscatter,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes_send * sendcount,
+        .nb_bytes_r = nb_bytes_recv * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(iscatter);
+    return ret;
+}
+
+int MPI_Ireduce(const void* sendbuf, void* recvbuf, int count,
+                MPI_Datatype datatype, MPI_Op op, int root, MPI_Comm comm,
+                MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Ireduce(sendbuf, recvbuf, count, datatype, op, root, comm,
+                           request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    MpiCall const ireduce = {
+        .kind = Ireduce,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes,
+        .nb_bytes_r = nb_bytes * count,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(op),
+        .finished = false,
+    };
+
+    register_mpi_call(ireduce);
+    return ret;
+}
+
+int MPI_Iallreduce(const void* sendbuf, void* recvbuf, int count,
+                   MPI_Datatype datatype, MPI_Op op, MPI_Comm comm,
+                   MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Iallreduce(sendbuf, recvbuf, count, datatype, op, comm,
+                              request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    MpiCall const iallreduce = {
+        .kind = Iallreduce,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = 0,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(op),
+        .finished = false,
+    };
+
+    register_mpi_call(iallreduce);
+    return ret;
+}
+
+int MPI_Iscan(const void* sendbuf, void* recvbuf, int count,
+              MPI_Datatype datatype, MPI_Op op, MPI_Comm comm,
+              MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Iscan(sendbuf, recvbuf, count, datatype, op, comm,
+                         request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    MpiCall const iscan = {
+        .kind = Iscan,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = 0,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(op),
+        .finished = false,
+    };
+
+    register_mpi_call(iscan);
+    return ret;
+}
+
+int MPI_Iexscan(const void* sendbuf, void* recvbuf, int count,
+                MPI_Datatype datatype, MPI_Op op, MPI_Comm comm,
+                MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Iexscan(sendbuf, recvbuf, count, datatype, op, comm,
+                           request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    MpiCall const iexscan = {
+        .kind = Iexscan,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = 0,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(op),
+        .finished = false,
+    };
+
+    register_mpi_call(iexscan);
+    return ret;
+}
+
+int MPI_Iallgather(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                   void* recvbuf, int recvcount, MPI_Datatype recvtype,
+                   MPI_Comm comm, MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Iallgather(sendbuf, sendcount, sendtype, recvbuf, recvcount,
+                              recvtype, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(sendtype, &nb_bytes_send);
+    PMPI_Type_size(recvtype, &nb_bytes_recv);
+
+    MpiCall const iallgather = {
+        .kind = Iallgather,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send * sendcount,
+        .nb_bytes_r = nb_bytes_recv * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(iallgather);
+    return ret;
+}
+
+int MPI_Ialltoall(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                  void* recvbuf, int recvcount, MPI_Datatype recvtype,
+                  MPI_Comm comm, MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Ialltoall(sendbuf, sendcount, sendtype, recvbuf, recvcount,
+                             recvtype, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(sendtype, &nb_bytes_send);
+    PMPI_Type_size(recvtype, &nb_bytes_recv);
+
+    MpiCall const ialltoall = {
+        .kind = Ialltoall,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send * sendcount,
+        .nb_bytes_r = nb_bytes_recv * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(ialltoall);
+    return ret;
+}
+
+int MPI_Ireduce_scatter(const void* sendbuf, void* recvbuf,
+                        const int recvcounts[], MPI_Datatype datatype,
+                        MPI_Op op, MPI_Comm comm, MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Ireduce_scatter(sendbuf, recvbuf, recvcounts, datatype, op,
+                                   comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(datatype, &nb_bytes);
+
+    // Every rank contributes the elements scattered to all the ranks
+    int comm_size, comm_rank;
+    PMPI_Comm_size(comm, &comm_size);
+    PMPI_Comm_rank(comm, &comm_rank);
+    int count = 0;
+    for (int i = 0; i < comm_size; ++i) {
+        count += recvcounts[i];
+    }
+
+    MpiCall const ireduce_scatter = {
+        .kind = IreduceScatter,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = nb_bytes * recvcounts[comm_rank],
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(op),
+        .finished = false,
+    };
+
+    register_mpi_call(ireduce_scatter);
+    return ret;
+}
+
+/// Returns the number of bytes exchanged with each of the `size` ranks of a
+/// communicator, given the number of elements of type `datatype` exchanged with
+/// each of them, and adds them up in `total`. The array must be freed by the
+/// caller.
+static uint32_t* peer_bytes(int size, int const counts[], MPI_Datatype datatype,
+                            uint32_t* total)
+{
+    int type_size;
+    PMPI_Type_size(datatype, &type_size);
+
+    uint32_t* bytes = malloc((size > 0 ? size : 1) * sizeof *bytes);
+    *total = 0;
+    for (int i = 0; i < size; ++i) {
+        bytes[i] = counts[i] * type_size;
+        *total += bytes[i];
+    }
+    return bytes;
+}
+
+int MPI_Gatherv(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                void* recvbuf, const int recvcounts[], const int displs[],
+                MPI_Datatype recvtype, int root, MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Gatherv(sendbuf, sendcount, sendtype, recvbuf, recvcounts,
+                           displs, recvtype, root, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size, comm_rank;
+    PMPI_Comm_size(comm, &comm_size);
+    PMPI_Comm_rank(comm, &comm_rank);
+
+    int send_size;
+    PMPI_Type_size(sendtype, &send_size);
+
+    // The counts of received elements are only significant on the root
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv = comm_rank == root
+        ? peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv)
+        : NULL;
+
+    MpiCall const gatherv = {
+        .kind = Gatherv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = send_size * sendcount,
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = bytes_recv != NULL ? comm_size : 0,
+    };
+
+    register_mpi_call_arrays(gatherv, arrays);
+    free(bytes_recv);
+    return ret;
+}
+
+int MPI_Scatterv(void const* sendbuf, const int sendcounts[],
+                 const int displs[], MPI_Datatype sendtype, void* recvbuf,
+                 int recvcount, MPI_Datatype recvtype, int root, MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Scatterv(sendbuf, sendcounts, displs, sendtype, recvbuf,
+                            recvcount, recvtype, root, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size, comm_rank;
+    PMPI_Comm_size(comm, &comm_size);
+    PMPI_Comm_rank(comm, &comm_rank);
+
+    // The counts of sent elements are only significant on the root
+    uint32_t nb_bytes_send = 0;
+    uint32_t* bytes_send = comm_rank == root
+        ? peer_bytes(comm_size, sendcounts, sendtype, &nb_bytes_send)
+        : NULL;
+
+    int recv_size;
+    PMPI_Type_size(recvtype, &recv_size);
+
+    MpiCall const scatterv = {
+        .kind = Scatterv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes_send,
+        .nb_bytes_r = recv_size * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = bytes_send,
+        .nb_peers_s = bytes_send != NULL ? comm_size : 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
+
+    register_mpi_call_arrays(scatterv, arrays);
+    free(bytes_send);
+    return ret;
+}
+
+int MPI_Allgatherv(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                   void* recvbuf, const int recvcounts[], const int displs[],
+                   MPI_Datatype recvtype, MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Allgatherv(sendbuf, sendcount, sendtype, recvbuf, recvcounts,
+                              displs, recvtype, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size;
+    PMPI_Comm_size(comm, &comm_size);
+
+    int send_size;
+    PMPI_Type_size(sendtype, &send_size);
+
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv =
+        peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv);
+
+    MpiCall const allgatherv = {
+        .kind = Allgatherv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = send_size * sendcount,
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = comm_size,
+    };
+
+    register_mpi_call_arrays(allgatherv, arrays);
+    free(bytes_recv);
+    return ret;
+}
+
+int MPI_Alltoallv(void const* sendbuf, const int sendcounts[],
+                  const int sdispls[], MPI_Datatype sendtype, void* recvbuf,
+                  const int recvcounts[], const int rdispls[],
+                  MPI_Datatype recvtype, MPI_Comm comm)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Alltoallv(sendbuf, sendcounts, sdispls, sendtype, recvbuf,
+                             recvcounts, rdispls, recvtype, comm);
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size;
+    PMPI_Comm_size(comm, &comm_size);
+
+    uint32_t nb_bytes_send = 0;
+    uint32_t* bytes_send =
+        peer_bytes(comm_size, sendcounts, sendtype, &nb_bytes_send);
+
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv =
+        peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv);
+
+    MpiCall const alltoallv = {
+        .kind = Alltoallv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send,
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = bytes_send,
+        .nb_peers_s = comm_size,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = comm_size,
+    };
+
+    register_mpi_call_arrays(alltoallv, arrays);
+    free(bytes_send);
+    free(bytes_recv);
+    return ret;
+}
+
+int MPI_Igatherv(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                 void* recvbuf, const int recvcounts[], const int displs[],
+                 MPI_Datatype recvtype, int root, MPI_Comm comm,
+                 MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Igatherv(sendbuf, sendcount, sendtype, recvbuf, recvcounts,
+                            displs, recvtype, root, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size, comm_rank;
+    PMPI_Comm_size(comm, &comm_size);
+    PMPI_Comm_rank(comm, &comm_rank);
+
+    int send_size;
+    PMPI_Type_size(sendtype, &send_size);
+
+    // The counts of received elements are only significant on the root
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv = comm_rank == root
+        ? peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv)
+        : NULL;
+
+    MpiCall const igatherv = {
+        .kind = Igatherv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = send_size * sendcount,
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = bytes_recv != NULL ? comm_size : 0,
+    };
+
+    register_mpi_call_arrays(igatherv, arrays);
+    free(bytes_recv);
+    return ret;
+}
+
+int MPI_Iscatterv(void const* sendbuf, const int sendcounts[],
+                  const int displs[], MPI_Datatype sendtype, void* recvbuf,
+                  int recvcount, MPI_Datatype recvtype, int root, MPI_Comm comm,
+                  MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Iscatterv(sendbuf, sendcounts, displs, sendtype, recvbuf,
+                             recvcount, recvtype, root, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size, comm_rank;
+    PMPI_Comm_size(comm, &comm_size);
+    PMPI_Comm_rank(comm, &comm_rank);
+
+    // The counts of sent elements are only significant on the root
+    uint32_t nb_bytes_send = 0;
+    uint32_t* bytes_send = comm_rank == root
+        ? peer_bytes(comm_size, sendcounts, sendtype, &nb_bytes_send)
+        : NULL;
+
+    int recv_size;
+    PMPI_Type_size(recvtype, &recv_size);
+
+    MpiCall const iscatterv = {
+        .kind = Iscatterv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = root,
+        .nb_bytes_s = nb_bytes_send,
+        .nb_bytes_r = recv_size * recvcount,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = bytes_send,
+        .nb_peers_s = bytes_send != NULL ? comm_size : 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
+
+    register_mpi_call_arrays(iscatterv, arrays);
+    free(bytes_send);
+    return ret;
+}
+
+int MPI_Iallgatherv(void const* sendbuf, int sendcount, MPI_Datatype sendtype,
+                    void* recvbuf, const int recvcounts[], const int displs[],
+                    MPI_Datatype recvtype, MPI_Comm comm, MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Iallgatherv(sendbuf, sendcount, sendtype, recvbuf,
+                               recvcounts, displs, recvtype, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size;
+    PMPI_Comm_size(comm, &comm_size);
+
+    int send_size;
+    PMPI_Type_size(sendtype, &send_size);
+
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv =
+        peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv);
+
+    MpiCall const iallgatherv = {
+        .kind = Iallgatherv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = send_size * sendcount,
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = comm_size,
+    };
+
+    register_mpi_call_arrays(iallgatherv, arrays);
+    free(bytes_recv);
+    return ret;
+}
+
+int MPI_Ialltoallv(void const* sendbuf, const int sendcounts[],
+                   const int sdispls[], MPI_Datatype sendtype, void* recvbuf,
+                   const int recvcounts[], const int rdispls[],
+                   MPI_Datatype recvtype, MPI_Comm comm, MPI_Request* request)
+{
+    Tsc const tsc = rdtsc();
+    int ret = PMPI_Ialltoallv(sendbuf, sendcounts, sdispls, sendtype, recvbuf,
+                              recvcounts, rdispls, recvtype, comm, request);
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size;
+    PMPI_Comm_size(comm, &comm_size);
+
+    uint32_t nb_bytes_send = 0;
+    uint32_t* bytes_send =
+        peer_bytes(comm_size, sendcounts, sendtype, &nb_bytes_send);
+
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv =
+        peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv);
+
+    MpiCall const ialltoallv = {
+        .kind = Ialltoallv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send,
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = PMPI_Comm_c2f(comm),
+        .req = PMPI_Request_c2f(*request),
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = bytes_send,
+        .nb_peers_s = comm_size,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = comm_size,
+    };
+
+    register_mpi_call_arrays(ialltoallv, arrays);
+    free(bytes_send);
+    free(bytes_recv);
+    return ret;
+}
+
+int MPI_Abort(MPI_Comm comm, int errorcode)
+{
+    // Write the events of this rank before the MPI library terminates the job
+    register_abort(errorcode);
+
+    return PMPI_Abort(comm, errorcode);
+}
+
+int MPI_Pcontrol(const int level, ...)
+{
+    // Level 0 disables tracing, any other level enables it
+    if (level == 0) {
+        interpol_pause();
+    } else {
+        interpol_resume();
+    }
+
+    return PMPI_Pcontrol(level);
+}
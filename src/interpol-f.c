@@ -1,9 +1,12 @@
 #include "../include/interpol.h"
 #include "../include/tsc.h"
 
+#include <limits.h>
 #include <mpi.h>
 #include <stdint.h>
 #include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
 #include <sys/time.h>
 
 #ifndef _EXTERN_C_
@@ -35,6 +38,337 @@ _EXTERN_C_ void pmpi_init__(MPI_Fint *ierr);
 static int fortran_init = 0;
 static MpiRank current_rank = -1;
 
+/// Passes information about the MPI library to the Rust backend, to be stored in
+/// the metadata of the trace.
+static void register_library_info()
+{
+    int world_size;
+    PMPI_Comm_size(MPI_COMM_WORLD, &world_size);
+
+    char library_version[MPI_MAX_LIBRARY_VERSION_STRING];
+    int len;
+    PMPI_Get_library_version(library_version, &len);
+
+    register_mpi_info(world_size, library_version);
+}
+
+/// Passes the date at which rank 0 initialized MPI to the Rust backend, which
+/// names the directory of the run after it unless the configuration or the
+/// batch scheduler gives it a name.
+///
+/// This must be done before `MPI_Init` is registered, as the trace files of
+/// the rank are created in that directory.
+static void register_run(Usecs time)
+{
+    Usecs start = time;
+    PMPI_Bcast(&start, 1, MPI_DOUBLE, 0, MPI_COMM_WORLD);
+    register_run_start(start);
+}
+
+/// Number of ping-pongs exchanged with rank 0 to synchronize the clocks.
+#define CLOCK_SYNC_ROUNDS 16
+
+/// Measures the offset between the TSC of the current rank and the TSC of rank
+/// 0 with ping-pongs, and passes it to the Rust backend.
+///
+/// This is done at `MPI_Init` and `MPI_Finalize`, so that the drift between the
+/// clocks can be corrected when the traces are merged.
+///
+/// Rank 0 answers the pings of every other rank in turn with its own TSC. Each
+/// rank keeps the ping-pong with the shortest round-trip, and assumes that the
+/// TSC of rank 0 was read halfway through it. The ping-pongs are exchanged on a
+/// duplicate of `MPI_COMM_WORLD` so that they cannot match messages of the
+/// application.
+static void synchronize_clocks(ClockSyncPoint point)
+{
+    MPI_Comm sync_comm;
+    PMPI_Comm_dup(MPI_COMM_WORLD, &sync_comm);
+
+    int world_size;
+    PMPI_Comm_size(sync_comm, &world_size);
+
+    if (current_rank == 0) {
+        for (int rank = 1; rank < world_size; rank++) {
+            for (int i = 0; i < CLOCK_SYNC_ROUNDS; i++) {
+                Tsc ping;
+                PMPI_Recv(&ping, 1, MPI_UINT64_T, rank, 0, sync_comm, MPI_STATUS_IGNORE);
+                Tsc const tsc = fenced_rdtscp();
+                PMPI_Send(&tsc, 1, MPI_UINT64_T, rank, 0, sync_comm);
+            }
+        }
+        register_clock_sync(point, fenced_rdtscp(), 0, 0);
+    } else {
+        Tsc best_tsc = 0;
+        Tsc best_round_trip = UINT64_MAX;
+        int64_t best_offset = 0;
+        for (int i = 0; i < CLOCK_SYNC_ROUNDS; i++) {
+            Tsc const start = fenced_rdtscp();
+            PMPI_Send(&start, 1, MPI_UINT64_T, 0, 0, sync_comm);
+            Tsc remote;
+            PMPI_Recv(&remote, 1, MPI_UINT64_T, 0, 0, sync_comm, MPI_STATUS_IGNORE);
+            Tsc const end = fenced_rdtscp();
+
+            if (end - start < best_round_trip) {
+                best_round_trip = end - start;
+                best_tsc = start + best_round_trip / 2;
+                best_offset = (int64_t)(remote - best_tsc);
+            }
+        }
+        register_clock_sync(point, best_tsc, best_offset, best_round_trip);
+    }
+
+    PMPI_Comm_free(&sync_comm);
+}
+
+/// Size of the chunks in which serialized traces are sent, as MPI counts are
+/// `int`s.
+#define TRACE_CHUNK_SIZE ((size_t)1 << 30)
+
+/// Sends the `len` bytes of serialized traces to `dest`, preceded by their
+/// length.
+static void send_traces(uint8_t const* buffer, size_t len, int dest, MPI_Comm comm)
+{
+    uint64_t const total = len;
+    PMPI_Send(&total, 1, MPI_UINT64_T, dest, 0, comm);
+    for (size_t sent = 0; sent < len; sent += TRACE_CHUNK_SIZE) {
+        size_t const chunk = len - sent < TRACE_CHUNK_SIZE ? len - sent : TRACE_CHUNK_SIZE;
+        PMPI_Send(buffer + sent, (int)chunk, MPI_BYTE, dest, 0, comm);
+    }
+}
+
+/// Receives serialized traces sent with `send_traces` from `source`, and
+/// stores their length in `len`. The returned buffer must be freed with `free`.
+static uint8_t* recv_traces(int source, MPI_Comm comm, size_t* len)
+{
+    uint64_t total;
+    PMPI_Recv(&total, 1, MPI_UINT64_T, source, 0, comm, MPI_STATUS_IGNORE);
+    uint8_t* buffer = malloc(total > 0 ? total : 1);
+    for (size_t received = 0; received < total; received += TRACE_CHUNK_SIZE) {
+        size_t const chunk = total - received < TRACE_CHUNK_SIZE ? total - received : TRACE_CHUNK_SIZE;
+        PMPI_Recv(buffer + received, (int)chunk, MPI_BYTE, source, 0, comm, MPI_STATUS_IGNORE);
+    }
+
+    *len = total;
+    return buffer;
+}
+
+/// Gathers the serialized traces of every rank on `root`, which merges them.
+///
+/// The lengths of the traces are gathered first. The traces themselves are
+/// gathered with a single `MPI_Gatherv` if they fit in its `int`
+/// displacements, and sent one by one to the root otherwise.
+static void gather_traces(int root, MPI_Comm comm)
+{
+    int world_size;
+    PMPI_Comm_size(comm, &world_size);
+
+    size_t len;
+    uint8_t* buffer = interpol_serialize_rank(&len);
+    uint64_t const local_len = len;
+
+    uint64_t* lens = NULL;
+    if (current_rank == root) {
+        lens = malloc(world_size * sizeof *lens);
+    }
+    PMPI_Gather(&local_len, 1, MPI_UINT64_T, lens, 1, MPI_UINT64_T, root, comm);
+
+    int fits = 1;
+    if (current_rank == root) {
+        uint64_t total = 0;
+        for (int rank = 0; rank < world_size; rank++) {
+            total += lens[rank];
+        }
+        fits = total <= INT_MAX;
+    }
+    PMPI_Bcast(&fits, 1, MPI_INT, root, comm);
+
+    if (current_rank != root) {
+        if (fits) {
+            PMPI_Gatherv(buffer, (int)len, MPI_BYTE, NULL, NULL, NULL, MPI_BYTE, root, comm);
+        } else {
+            send_traces(buffer, len, root, comm);
+        }
+        interpol_free_buffer(buffer, len);
+        return;
+    }
+
+    uint8_t const** buffers = malloc(world_size * sizeof *buffers);
+    size_t* sizes = malloc(world_size * sizeof *sizes);
+    uint8_t* gathered = NULL;
+    if (fits) {
+        int* counts = malloc(world_size * sizeof *counts);
+        int* displs = malloc(world_size * sizeof *displs);
+        int offset = 0;
+        for (int rank = 0; rank < world_size; rank++) {
+            counts[rank] = (int)lens[rank];
+            displs[rank] = offset;
+            offset += counts[rank];
+        }
+        gathered = malloc(offset > 0 ? offset : 1);
+        PMPI_Gatherv(buffer, (int)len, MPI_BYTE, gathered, counts, displs, MPI_BYTE, root, comm);
+        for (int rank = 0; rank < world_size; rank++) {
+            buffers[rank] = gathered + displs[rank];
+            sizes[rank] = counts[rank];
+        }
+        free(counts);
+        free(displs);
+    } else {
+        for (int rank = 0; rank < world_size; rank++) {
+            if (rank == root) {
+                buffers[rank] = buffer;
+                sizes[rank] = len;
+            } else {
+                buffers[rank] = recv_traces(rank, comm, &sizes[rank]);
+            }
+        }
+    }
+
+    interpol_write_merged(buffers, sizes, world_size);
+
+    if (!fits) {
+        for (int rank = 0; rank < world_size; rank++) {
+            if (rank != root) {
+                free((uint8_t*)buffers[rank]);
+            }
+        }
+    }
+    free(gathered);
+    free(buffers);
+    free(sizes);
+    free(lens);
+    interpol_free_buffer(buffer, len);
+}
+
+/// Passes the serialized traces up a binary tree of ranks rooted at `root`:
+/// every rank merges the traces of its children with its own and sends them to
+/// its parent, so that the root ends up with the traces of every rank, which it
+/// merges.
+static void reduce_traces(int root, MPI_Comm comm)
+{
+    int world_size;
+    PMPI_Comm_size(comm, &world_size);
+    // The position of the rank in the tree, whose node 0 is the root
+    int const node = (current_rank - root + world_size) % world_size;
+
+    uint8_t const* buffers[3];
+    size_t lens[3];
+    size_t count = 0;
+
+    size_t len;
+    uint8_t* own = interpol_serialize_rank(&len);
+    buffers[count] = own;
+    lens[count++] = len;
+
+    uint8_t* children[2] = { NULL, NULL };
+    for (int i = 0; i < 2; i++) {
+        int const child = 2 * node + 1 + i;
+        if (child < world_size) {
+            children[i] = recv_traces((child + root) % world_size, comm, &lens[count]);
+            buffers[count++] = children[i];
+        }
+    }
+
+    size_t merged_len;
+    uint8_t* merged = interpol_merge_buffers(buffers, lens, count, &merged_len);
+    interpol_free_buffer(own, len);
+    free(children[0]);
+    free(children[1]);
+
+    if (node == 0) {
+        uint8_t const* const all = merged;
+        interpol_write_merged(&all, &merged_len, 1);
+    } else {
+        send_traces(merged, merged_len, ((node - 1) / 2 + root) % world_size, comm);
+    }
+    interpol_free_buffer(merged, merged_len);
+}
+
+/// Gathers the names of the trace files written by every rank on `root`,
+/// which lists them in the manifest of the run so that only these files are
+/// merged.
+static void write_manifest(int root, MPI_Comm comm)
+{
+    int world_size;
+    PMPI_Comm_size(comm, &world_size);
+
+    size_t len;
+    uint8_t* files = interpol_rank_files(&len);
+    int const local_len = (int)len;
+
+    int* counts = NULL;
+    if (current_rank == root) {
+        counts = malloc(world_size * sizeof *counts);
+    }
+    PMPI_Gather(&local_len, 1, MPI_INT, counts, 1, MPI_INT, root, comm);
+
+    if (current_rank != root) {
+        PMPI_Gatherv(files, local_len, MPI_BYTE, NULL, NULL, NULL, MPI_BYTE, root, comm);
+        interpol_free_buffer(files, len);
+        return;
+    }
+
+    int* displs = malloc(world_size * sizeof *displs);
+    int offset = 0;
+    for (int rank = 0; rank < world_size; rank++) {
+        displs[rank] = offset;
+        offset += counts[rank];
+    }
+    uint8_t* gathered = malloc(offset > 0 ? offset : 1);
+    PMPI_Gatherv(files, local_len, MPI_BYTE, gathered, counts, displs, MPI_BYTE, root, comm);
+
+    uint8_t const** buffers = malloc(world_size * sizeof *buffers);
+    size_t* sizes = malloc(world_size * sizeof *sizes);
+    for (int rank = 0; rank < world_size; rank++) {
+        buffers[rank] = gathered + displs[rank];
+        sizes[rank] = counts[rank];
+    }
+    interpol_write_manifest(buffers, sizes, world_size);
+
+    free(buffers);
+    free(sizes);
+    free(gathered);
+    free(displs);
+    free(counts);
+    interpol_free_buffer(files, len);
+}
+
+/// Brings the traces of every rank together on the merging rank, which merges
+/// them into a single trace.
+///
+/// The traces are either written to trace files, listed in the manifest of the
+/// run and read back by the merging rank, or aggregated in-band. They are
+/// exchanged on a duplicate of `MPI_COMM_WORLD` so that they cannot match
+/// messages of the application.
+static void aggregate_traces()
+{
+    int world_size;
+    PMPI_Comm_size(MPI_COMM_WORLD, &world_size);
+
+    int root = interpol_merge_root();
+    if (root >= world_size) {
+        if (current_rank == 0) {
+            fprintf(stderr, "[interpol]: the merge root %d is not a rank of the job, merging on rank 0\n", root);
+        }
+        root = 0;
+    }
+
+    MPI_Comm comm;
+    PMPI_Comm_dup(MPI_COMM_WORLD, &comm);
+    Aggregation const aggregation = interpol_aggregation();
+    if (aggregation == ViaFilesystem) {
+        // The root only gets the manifest once every rank has written its files
+        write_manifest(root, comm);
+        if (current_rank == root) {
+            sort_all_traces();
+        }
+    } else if (aggregation == ViaGather) {
+        gather_traces(root, comm);
+    } else {
+        reduce_traces(root, comm);
+    }
+    PMPI_Comm_free(&comm);
+}
+
 int32_t jenkins_one_at_a_time_hash(char const* key, size_t len)
 {
     int32_t hash = 0;
@@ -68,6 +402,7 @@ static void MPI_Init_fortran_wrapper(MPI_Fint *ierr) {
 
     // Set the rank of the current MPI process/thread
     PMPI_Comm_rank(MPI_COMM_WORLD, &current_rank);
+    register_run(timeofday.tv_sec + timeofday.tv_usec / 1e6);
 
     MpiCall const init = {
         .kind = Init,
@@ -88,6 +423,8 @@ static void MPI_Init_fortran_wrapper(MPI_Fint *ierr) {
     };
 
     register_mpi_call(init);
+    synchronize_clocks(AtInit);
+    register_library_info();
 
     *ierr = _wrap_py_return_val;
 }
@@ -126,6 +463,7 @@ static void MPI_Init_thread_fortran_wrapper(MPI_Fint *argc, MPI_Fint ***argv, MP
 
     // Set the rank of the current MPI process/thread
     PMPI_Comm_rank(MPI_COMM_WORLD, &current_rank);
+    register_run(timeofday.tv_sec + timeofday.tv_usec / 1e6);
 
     MpiCall const initthread = {
         .kind = Initthread,
@@ -146,6 +484,8 @@ static void MPI_Init_thread_fortran_wrapper(MPI_Fint *argc, MPI_Fint ***argv, MP
     };
 
     register_mpi_call(initthread);
+    synchronize_clocks(AtInit);
+    register_library_info();
     *ierr = _wrap_py_return_val;
 }
 
@@ -169,6 +509,7 @@ _EXTERN_C_ void mpi_init_thread__(MPI_Fint *argc, MPI_Fint ***argv, MPI_Fint *re
 static void MPI_Finalize_fortran_wrapper(MPI_Fint *ierr) { 
     int _wrap_py_return_val = 0;
 
+    synchronize_clocks(AtFinalize);
     PMPI_Barrier(MPI_COMM_WORLD);
     // Measure the current time and TSC.
     Tsc const tsc = fenced_rdtscp();
@@ -195,10 +536,7 @@ static void MPI_Finalize_fortran_wrapper(MPI_Fint *ierr) {
 
     register_mpi_call(finalize);
 
-    PMPI_Barrier(MPI_COMM_WORLD);
-    if (current_rank == 0) {
-        sort_all_traces();
-    }
+    aggregate_traces();
 
     _wrap_py_return_val = PMPI_Finalize();
 
@@ -683,274 +1021,2325 @@ _EXTERN_C_ void mpi_wait__(MPI_Fint *request, MPI_Fint *status, MPI_Fint *ierr)
 }
 
 
-static void MPI_Ibcast_fortran_wrapper(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+/// Returns a copy of the identifiers of `count` Fortran requests, to be passed
+/// to the Rust backend. They must be read before the requests are completed,
+/// as completed requests are set to `MPI_REQUEST_NULL`. The array must be freed
+/// by the caller.
+static MpiReq* request_ids(int count, MPI_Fint const* requests)
+{
+    MpiReq* reqs = malloc((count > 0 ? count : 1) * sizeof *reqs);
+    for (int i = 0; i < count; ++i) {
+        reqs[i] = requests[i];
+    }
+    return reqs;
+}
+
+/// Converts the `count` 1-based indices of completed requests returned to a
+/// Fortran program into 0-based indices, to be passed to the Rust backend. The
+/// array must be freed by the caller.
+static int32_t* request_indices(int count, MPI_Fint const* indices)
+{
+    int32_t* completed = malloc((count > 0 ? count : 1) * sizeof *completed);
+    for (int i = 0; i < count; ++i) {
+        completed[i] = indices[i] - 1;
+    }
+    return completed;
+}
+
+static void MPI_Waitall_fortran_wrapper(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
     int _wrap_py_return_val = 0;
 
+    MpiReq* reqs = request_ids(*count, array_of_requests);
     Tsc const tsc = rdtsc();
 
     #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
-        _wrap_py_return_val = PMPI_Ibcast((void*)buffer, *count, (MPI_Datatype)(*datatype), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+        _wrap_py_return_val = PMPI_Waitall(*count, (MPI_Request*)array_of_requests, (MPI_Status*)array_of_statuses);
     #else /* MPI-2 safe call */
-        MPI_Request temp_request;
-        temp_request = MPI_Request_f2c(*request);
-        _wrap_py_return_val = PMPI_Ibcast((void*)buffer, *count, MPI_Type_f2c(*datatype), *root, MPI_Comm_f2c(*comm), &temp_request);
-        *request = MPI_Request_c2f(temp_request);
+        MPI_Request* temp_requests = malloc((*count > 0 ? *count : 1) * sizeof *temp_requests);
+        MPI_Status* temp_statuses = malloc((*count > 0 ? *count : 1) * sizeof *temp_statuses);
+        for (int i = 0; i < *count; ++i) {
+            temp_requests[i] = MPI_Request_f2c(array_of_requests[i]);
+        }
+        _wrap_py_return_val = PMPI_Waitall(*count, temp_requests, temp_statuses);
+        for (int i = 0; i < *count; ++i) {
+            array_of_requests[i] = MPI_Request_c2f(temp_requests[i]);
+            MPI_Status_c2f(&temp_statuses[i], &array_of_statuses[i * MPI_F_STATUS_SIZE]);
+        }
+        free(temp_requests);
+        free(temp_statuses);
     #endif /* MPICH test */
 
     Tsc const duration = rdtsc() - tsc;
 
-    int nb_bytes;
-    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
-
-    MpiCall const ibcast = {
-        .kind = Ibcast,
+    MpiCall const waitall = {
+        .kind = Waitall,
         .time = -1.0,
         .tsc = tsc,
         .duration = duration,
         .current_rank = current_rank,
-        .partner_rank = *root,
-        .nb_bytes_s = nb_bytes * (*count),
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
         .nb_bytes_r = 0,
-        .comm = PMPI_Comm_c2f((MPI_Comm)comm),
-        .req = *request,
+        .comm = -1,
+        .req = -1,
         .tag = -1,
         .required_thread_lvl = -1,
         .provided_thread_lvl = -1,
         .op_type = -1,
-        .finished = false,
+        .finished = true,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = *count,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
     };
 
-    register_mpi_call(ibcast);
+    register_mpi_call_arrays(waitall, arrays);
+    free(reqs);
 
     *ierr = _wrap_py_return_val;
 }
 
-_EXTERN_C_ void MPI_IBCAST(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Ibcast_fortran_wrapper(buffer, count, datatype, root, comm, request, ierr);
+_EXTERN_C_ void MPI_WAITALL(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Waitall_fortran_wrapper(count, array_of_requests, array_of_statuses, ierr);
 }
 
-_EXTERN_C_ void mpi_ibcast(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Ibcast_fortran_wrapper(buffer, count, datatype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitall(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Waitall_fortran_wrapper(count, array_of_requests, array_of_statuses, ierr);
 }
 
-_EXTERN_C_ void mpi_ibcast_(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Ibcast_fortran_wrapper(buffer, count, datatype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitall_(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Waitall_fortran_wrapper(count, array_of_requests, array_of_statuses, ierr);
 }
 
-_EXTERN_C_ void mpi_ibcast__(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Ibcast_fortran_wrapper(buffer, count, datatype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitall__(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Waitall_fortran_wrapper(count, array_of_requests, array_of_statuses, ierr);
 }
 
 
-static void MPI_Igather_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+static void MPI_Waitany_fortran_wrapper(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *status, MPI_Fint *ierr) { 
     int _wrap_py_return_val = 0;
 
+    MpiReq* reqs = request_ids(*count, array_of_requests);
     Tsc const tsc = rdtsc();
 
     #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
-        _wrap_py_return_val = PMPI_Igather((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+        _wrap_py_return_val = PMPI_Waitany(*count, (MPI_Request*)array_of_requests, (int*)index, (MPI_Status*)status);
     #else /* MPI-2 safe call */
-        MPI_Request temp_request;
-        temp_request = MPI_Request_f2c(*request);
-        _wrap_py_return_val = PMPI_Igather((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm), &temp_request);
-        *request = MPI_Request_c2f(temp_request);
+        MPI_Request* temp_requests = malloc((*count > 0 ? *count : 1) * sizeof *temp_requests);
+        MPI_Status temp_status;
+        int temp_index;
+        for (int i = 0; i < *count; ++i) {
+            temp_requests[i] = MPI_Request_f2c(array_of_requests[i]);
+        }
+        _wrap_py_return_val = PMPI_Waitany(*count, temp_requests, &temp_index, &temp_status);
+        for (int i = 0; i < *count; ++i) {
+            array_of_requests[i] = MPI_Request_c2f(temp_requests[i]);
+        }
+        free(temp_requests);
+        *index = temp_index != MPI_UNDEFINED ? temp_index + 1 : MPI_UNDEFINED;
+        MPI_Status_c2f(&temp_status, status);
     #endif /* MPICH test */
 
     Tsc const duration = rdtsc() - tsc;
 
-    int nb_bytes_send, nb_bytes_recv;
-    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
-    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
-
-    MpiCall const igather = {
-        .kind = Igather,
+    // `index` is 1-based, or `MPI_UNDEFINED` if every request was inactive
+    int32_t const completed = *index != MPI_UNDEFINED ? *index - 1 : -1;
+    MpiCall const waitany = {
+        .kind = Waitany,
         .time = -1.0,
         .tsc = tsc,
         .duration = duration,
         .current_rank = current_rank,
-        .partner_rank = *root,
-        .nb_bytes_s = nb_bytes_send * (*sendcount),
-        .nb_bytes_r = nb_bytes_recv * (*recvcount),
-        .comm = PMPI_Comm_c2f((MPI_Comm)comm),
-        .req = *request,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
         .tag = -1,
         .required_thread_lvl = -1,
         .provided_thread_lvl = -1,
         .op_type = -1,
-        .finished = false,
+        .finished = completed >= 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = *count,
+        .indices = &completed,
+        .nb_indices = completed >= 0 ? 1 : 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
     };
 
-    register_mpi_call(igather);
+    register_mpi_call_arrays(waitany, arrays);
+    free(reqs);
 
     *ierr = _wrap_py_return_val;
 }
 
-_EXTERN_C_ void MPI_IGATHER(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Igather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+_EXTERN_C_ void MPI_WAITANY(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *status, MPI_Fint *ierr) { 
+    MPI_Waitany_fortran_wrapper(count, array_of_requests, index, status, ierr);
 }
 
-_EXTERN_C_ void mpi_igather(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Igather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitany(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *status, MPI_Fint *ierr) { 
+    MPI_Waitany_fortran_wrapper(count, array_of_requests, index, status, ierr);
 }
 
-_EXTERN_C_ void mpi_igather_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Igather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitany_(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *status, MPI_Fint *ierr) { 
+    MPI_Waitany_fortran_wrapper(count, array_of_requests, index, status, ierr);
 }
 
-_EXTERN_C_ void mpi_igather__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Igather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitany__(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *status, MPI_Fint *ierr) { 
+    MPI_Waitany_fortran_wrapper(count, array_of_requests, index, status, ierr);
 }
 
 
-static void MPI_Iscatter_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+static void MPI_Waitsome_fortran_wrapper(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
     int _wrap_py_return_val = 0;
 
+    MpiReq* reqs = request_ids(*incount, array_of_requests);
     Tsc const tsc = rdtsc();
 
     #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
-        _wrap_py_return_val = MPI_Iscatter((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+        _wrap_py_return_val = PMPI_Waitsome(*incount, (MPI_Request*)array_of_requests, (int*)outcount, (int*)array_of_indices, (MPI_Status*)array_of_statuses);
     #else /* MPI-2 safe call */
-        MPI_Request temp_request;
-        temp_request = MPI_Request_f2c(*request);
-        _wrap_py_return_val = PMPI_Iscatter((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm), &temp_request);
-        *request = MPI_Request_c2f(temp_request);
+        MPI_Request* temp_requests = malloc((*incount > 0 ? *incount : 1) * sizeof *temp_requests);
+        MPI_Status* temp_statuses = malloc((*incount > 0 ? *incount : 1) * sizeof *temp_statuses);
+        int* temp_indices = malloc((*incount > 0 ? *incount : 1) * sizeof *temp_indices);
+        int temp_outcount;
+        for (int i = 0; i < *incount; ++i) {
+            temp_requests[i] = MPI_Request_f2c(array_of_requests[i]);
+        }
+        _wrap_py_return_val = PMPI_Waitsome(*incount, temp_requests, &temp_outcount, temp_indices, temp_statuses);
+        for (int i = 0; i < *incount; ++i) {
+            array_of_requests[i] = MPI_Request_c2f(temp_requests[i]);
+        }
+        *outcount = temp_outcount;
+        for (int i = 0; temp_outcount != MPI_UNDEFINED && i < temp_outcount; ++i) {
+            array_of_indices[i] = temp_indices[i] + 1;
+            MPI_Status_c2f(&temp_statuses[i], &array_of_statuses[i * MPI_F_STATUS_SIZE]);
+        }
+        free(temp_requests);
+        free(temp_statuses);
+        free(temp_indices);
     #endif /* MPICH test */
 
     Tsc const duration = rdtsc() - tsc;
 
-    int nb_bytes_send, nb_bytes_recv;
-    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
-    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
-
-    MpiCall const iscatter = {
-        .kind = Iscatter,
+    // `outcount` is `MPI_UNDEFINED` if every request was inactive
+    int const nb_completed = *outcount != MPI_UNDEFINED ? *outcount : 0;
+    int32_t* indices = request_indices(nb_completed, array_of_indices);
+    MpiCall const waitsome = {
+        .kind = Waitsome,
         .time = -1.0,
         .tsc = tsc,
         .duration = duration,
         .current_rank = current_rank,
-        .partner_rank = *root,
-        .nb_bytes_s = nb_bytes_send * (*sendcount),
-        .nb_bytes_r = nb_bytes_recv * (*recvcount),
-        .comm = PMPI_Comm_c2f((MPI_Comm)comm),
-        .req = *request,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
         .tag = -1,
         .required_thread_lvl = -1,
         .provided_thread_lvl = -1,
         .op_type = -1,
-        .finished = false,
+        .finished = nb_completed > 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = *incount,
+        .indices = indices,
+        .nb_indices = nb_completed,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
     };
 
-    register_mpi_call(iscatter);
+    register_mpi_call_arrays(waitsome, arrays);
+    free(reqs);
+    free(indices);
 
     *ierr = _wrap_py_return_val;
 }
 
-_EXTERN_C_ void MPI_ISCATTER(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Iscatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+_EXTERN_C_ void MPI_WAITSOME(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Waitsome_fortran_wrapper(incount, array_of_requests, outcount, array_of_indices, array_of_statuses, ierr);
 }
 
-_EXTERN_C_ void mpi_iscatter(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Iscatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitsome(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Waitsome_fortran_wrapper(incount, array_of_requests, outcount, array_of_indices, array_of_statuses, ierr);
 }
 
-_EXTERN_C_ void mpi_iscatter_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Iscatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitsome_(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Waitsome_fortran_wrapper(incount, array_of_requests, outcount, array_of_indices, array_of_statuses, ierr);
 }
 
-_EXTERN_C_ void mpi_iscatter__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Iscatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+_EXTERN_C_ void mpi_waitsome__(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Waitsome_fortran_wrapper(incount, array_of_requests, outcount, array_of_indices, array_of_statuses, ierr);
 }
 
 
-static void MPI_Ireduce_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+static void MPI_Testall_fortran_wrapper(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *flag, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
     int _wrap_py_return_val = 0;
 
+    MpiReq* reqs = request_ids(*count, array_of_requests);
     Tsc const tsc = rdtsc();
 
     #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
-        _wrap_py_return_val = PMPI_Ireduce((const void*)sendbuf, (void*)recvbuf, *count, (MPI_Datatype)(*datatype), (MPI_Op)(*op), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+        _wrap_py_return_val = PMPI_Testall(*count, (MPI_Request*)array_of_requests, (int*)flag, (MPI_Status*)array_of_statuses);
     #else /* MPI-2 safe call */
-        MPI_Request temp_request;
-        temp_request = MPI_Request_f2c(*request);
-        _wrap_py_return_val = PMPI_Ireduce((const void*)sendbuf, (void*)recvbuf, *count, MPI_Type_f2c(*datatype), MPI_Op_f2c(*op), *root, MPI_Comm_f2c(*comm), &temp_request);
-        *request = MPI_Request_c2f(temp_request);
+        MPI_Request* temp_requests = malloc((*count > 0 ? *count : 1) * sizeof *temp_requests);
+        MPI_Status* temp_statuses = malloc((*count > 0 ? *count : 1) * sizeof *temp_statuses);
+        for (int i = 0; i < *count; ++i) {
+            temp_requests[i] = MPI_Request_f2c(array_of_requests[i]);
+        }
+        _wrap_py_return_val = PMPI_Testall(*count, temp_requests, (int*)flag, temp_statuses);
+        for (int i = 0; i < *count; ++i) {
+            array_of_requests[i] = MPI_Request_c2f(temp_requests[i]);
+            if (*flag) {
+                MPI_Status_c2f(&temp_statuses[i], &array_of_statuses[i * MPI_F_STATUS_SIZE]);
+            }
+        }
+        free(temp_requests);
+        free(temp_statuses);
     #endif /* MPICH test */
 
     Tsc const duration = rdtsc() - tsc;
 
-    int nb_bytes;
-    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+    MpiCall const testall = {
+        .kind = Testall,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = *flag != 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = *count,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
 
-    MpiOp op_type;
-    if ((MPI_Op)op == MPI_MAX) {
-        op_type = Max;
-    } else if ((MPI_Op)op == MPI_MIN) {
-        op_type = Min;
-    } else if ((MPI_Op)op == MPI_SUM) {
-        op_type = Sum;
-    } else if ((MPI_Op)op == MPI_PROD) {
-        op_type = Prod;
-    } else if ((MPI_Op)op == MPI_LAND) {
-        op_type = Land;
-    } else if ((MPI_Op)op == MPI_BAND) {
-        op_type = Band;
-    } else if ((MPI_Op)op == MPI_LOR) {
-        op_type = Lor;
-    } else if ((MPI_Op)op == MPI_BOR) {
-        op_type = Bor;
-    } else if ((MPI_Op)op == MPI_LXOR) {
-        op_type = Lxor;
-    } else if ((MPI_Op)op == MPI_BXOR) {
-        op_type = Bxor;
-    } else if ((MPI_Op)op == MPI_MAXLOC) {
-        op_type = Maxloc;
-    } else if ((MPI_Op)op == MPI_MINLOC) {
-        op_type = Minloc;
-    } else if ((MPI_Op)op == MPI_REPLACE) {
-        op_type = Replace;
-    } else if ((MPI_Op)op == MPI_OP_NULL) {
-        op_type = Opnull;
-    } else if ((MPI_Op)op == MPI_NO_OP) {
-        op_type = Opnull;
-    } else {
-        op_type = -1;
-    }
+    register_mpi_call_arrays(testall, arrays);
+    free(reqs);
 
-    MpiCall const ireduce = {
-        .kind = Ireduce,
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_TESTALL(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *flag, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Testall_fortran_wrapper(count, array_of_requests, flag, array_of_statuses, ierr);
+}
+
+_EXTERN_C_ void mpi_testall(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *flag, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Testall_fortran_wrapper(count, array_of_requests, flag, array_of_statuses, ierr);
+}
+
+_EXTERN_C_ void mpi_testall_(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *flag, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Testall_fortran_wrapper(count, array_of_requests, flag, array_of_statuses, ierr);
+}
+
+_EXTERN_C_ void mpi_testall__(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *flag, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Testall_fortran_wrapper(count, array_of_requests, flag, array_of_statuses, ierr);
+}
+
+
+static void MPI_Testany_fortran_wrapper(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *flag, MPI_Fint *status, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    MpiReq* reqs = request_ids(*count, array_of_requests);
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Testany(*count, (MPI_Request*)array_of_requests, (int*)index, (int*)flag, (MPI_Status*)status);
+    #else /* MPI-2 safe call */
+        MPI_Request* temp_requests = malloc((*count > 0 ? *count : 1) * sizeof *temp_requests);
+        MPI_Status temp_status;
+        int temp_index;
+        for (int i = 0; i < *count; ++i) {
+            temp_requests[i] = MPI_Request_f2c(array_of_requests[i]);
+        }
+        _wrap_py_return_val = PMPI_Testany(*count, temp_requests, &temp_index, (int*)flag, &temp_status);
+        for (int i = 0; i < *count; ++i) {
+            array_of_requests[i] = MPI_Request_c2f(temp_requests[i]);
+        }
+        free(temp_requests);
+        *index = temp_index != MPI_UNDEFINED ? temp_index + 1 : MPI_UNDEFINED;
+        if (*flag) {
+            MPI_Status_c2f(&temp_status, status);
+        }
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    // `index` is 1-based, or `MPI_UNDEFINED` if no request completed or if
+    // every request was inactive
+    int32_t const completed = *index != MPI_UNDEFINED ? *index - 1 : -1;
+    MpiCall const testany = {
+        .kind = Testany,
         .time = -1.0,
         .tsc = tsc,
         .duration = duration,
         .current_rank = current_rank,
-        .partner_rank = *root,
-        .nb_bytes_s = nb_bytes,
-        .nb_bytes_r = nb_bytes * (*count),
-        .comm = PMPI_Comm_c2f((MPI_Comm)comm),
-        .req = *request,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
         .tag = -1,
         .required_thread_lvl = -1,
         .provided_thread_lvl = -1,
-        .op_type = op_type,
-        .finished = false,
+        .op_type = -1,
+        .finished = *flag != 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = *count,
+        .indices = &completed,
+        .nb_indices = completed >= 0 ? 1 : 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
     };
 
-    register_mpi_call(ireduce);
+    register_mpi_call_arrays(testany, arrays);
+    free(reqs);
 
     *ierr = _wrap_py_return_val;
 }
 
-_EXTERN_C_ void MPI_IREDUCE(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Ireduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, request, ierr);
+_EXTERN_C_ void MPI_TESTANY(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *flag, MPI_Fint *status, MPI_Fint *ierr) { 
+    MPI_Testany_fortran_wrapper(count, array_of_requests, index, flag, status, ierr);
 }
 
-_EXTERN_C_ void mpi_ireduce(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Ireduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, request, ierr);
+_EXTERN_C_ void mpi_testany(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *flag, MPI_Fint *status, MPI_Fint *ierr) { 
+    MPI_Testany_fortran_wrapper(count, array_of_requests, index, flag, status, ierr);
 }
 
-_EXTERN_C_ void mpi_ireduce_(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Ireduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, request, ierr);
+_EXTERN_C_ void mpi_testany_(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *flag, MPI_Fint *status, MPI_Fint *ierr) { 
+    MPI_Testany_fortran_wrapper(count, array_of_requests, index, flag, status, ierr);
 }
 
-_EXTERN_C_ void mpi_ireduce__(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
-    MPI_Ireduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, request, ierr);
-}
\ No newline at end of file
+_EXTERN_C_ void mpi_testany__(MPI_Fint *count, MPI_Fint *array_of_requests, MPI_Fint *index, MPI_Fint *flag, MPI_Fint *status, MPI_Fint *ierr) { 
+    MPI_Testany_fortran_wrapper(count, array_of_requests, index, flag, status, ierr);
+}
+
+
+static void MPI_Testsome_fortran_wrapper(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    MpiReq* reqs = request_ids(*incount, array_of_requests);
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Testsome(*incount, (MPI_Request*)array_of_requests, (int*)outcount, (int*)array_of_indices, (MPI_Status*)array_of_statuses);
+    #else /* MPI-2 safe call */
+        MPI_Request* temp_requests = malloc((*incount > 0 ? *incount : 1) * sizeof *temp_requests);
+        MPI_Status* temp_statuses = malloc((*incount > 0 ? *incount : 1) * sizeof *temp_statuses);
+        int* temp_indices = malloc((*incount > 0 ? *incount : 1) * sizeof *temp_indices);
+        int temp_outcount;
+        for (int i = 0; i < *incount; ++i) {
+            temp_requests[i] = MPI_Request_f2c(array_of_requests[i]);
+        }
+        _wrap_py_return_val = PMPI_Testsome(*incount, temp_requests, &temp_outcount, temp_indices, temp_statuses);
+        for (int i = 0; i < *incount; ++i) {
+            array_of_requests[i] = MPI_Request_c2f(temp_requests[i]);
+        }
+        *outcount = temp_outcount;
+        for (int i = 0; temp_outcount != MPI_UNDEFINED && i < temp_outcount; ++i) {
+            array_of_indices[i] = temp_indices[i] + 1;
+            MPI_Status_c2f(&temp_statuses[i], &array_of_statuses[i * MPI_F_STATUS_SIZE]);
+        }
+        free(temp_requests);
+        free(temp_statuses);
+        free(temp_indices);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    // `outcount` is `MPI_UNDEFINED` if every request was inactive
+    int const nb_completed = *outcount != MPI_UNDEFINED ? *outcount : 0;
+    int32_t* indices = request_indices(nb_completed, array_of_indices);
+    MpiCall const testsome = {
+        .kind = Testsome,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = 0,
+        .nb_bytes_r = 0,
+        .comm = -1,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = nb_completed > 0,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = reqs,
+        .nb_reqs = *incount,
+        .indices = indices,
+        .nb_indices = nb_completed,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
+
+    register_mpi_call_arrays(testsome, arrays);
+    free(reqs);
+    free(indices);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_TESTSOME(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Testsome_fortran_wrapper(incount, array_of_requests, outcount, array_of_indices, array_of_statuses, ierr);
+}
+
+_EXTERN_C_ void mpi_testsome(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Testsome_fortran_wrapper(incount, array_of_requests, outcount, array_of_indices, array_of_statuses, ierr);
+}
+
+_EXTERN_C_ void mpi_testsome_(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Testsome_fortran_wrapper(incount, array_of_requests, outcount, array_of_indices, array_of_statuses, ierr);
+}
+
+_EXTERN_C_ void mpi_testsome__(MPI_Fint *incount, MPI_Fint *array_of_requests, MPI_Fint *outcount, MPI_Fint *array_of_indices, MPI_Fint *array_of_statuses, MPI_Fint *ierr) { 
+    MPI_Testsome_fortran_wrapper(incount, array_of_requests, outcount, array_of_indices, array_of_statuses, ierr);
+}
+
+
+/// Returns the type of a reduction operation, as passed to the Rust backend, or
+/// -1 if it is a user-defined operation.
+static MpiOp op_type(MPI_Op op)
+{
+    if (op == MPI_MAX) {
+        return Max;
+    } else if (op == MPI_MIN) {
+        return Min;
+    } else if (op == MPI_SUM) {
+        return Sum;
+    } else if (op == MPI_PROD) {
+        return Prod;
+    } else if (op == MPI_LAND) {
+        return Land;
+    } else if (op == MPI_BAND) {
+        return Band;
+    } else if (op == MPI_LOR) {
+        return Lor;
+    } else if (op == MPI_BOR) {
+        return Bor;
+    } else if (op == MPI_LXOR) {
+        return Lxor;
+    } else if (op == MPI_BXOR) {
+        return Bxor;
+    } else if (op == MPI_MAXLOC) {
+        return Maxloc;
+    } else if (op == MPI_MINLOC) {
+        return Minloc;
+    } else if (op == MPI_REPLACE) {
+        return Replace;
+    } else if (op == MPI_OP_NULL) {
+        return Opnull;
+    } else if (op == MPI_NO_OP) {
+        return Opnull;
+    } else {
+        return -1;
+    }
+}
+
+static void MPI_Bcast_fortran_wrapper(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Bcast((void*)buffer, *count, (MPI_Datatype)(*datatype), *root, (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Bcast((void*)buffer, *count, MPI_Type_f2c(*datatype), *root, MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    MpiCall const bcast = {
+        .kind = Bcast,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes * (*count),
+        .nb_bytes_r = 0,
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(bcast);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_BCAST(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Bcast_fortran_wrapper(buffer, count, datatype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_bcast(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Bcast_fortran_wrapper(buffer, count, datatype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_bcast_(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Bcast_fortran_wrapper(buffer, count, datatype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_bcast__(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Bcast_fortran_wrapper(buffer, count, datatype, root, comm, ierr);
+}
+
+
+static void MPI_Reduce_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Reduce((const void*)sendbuf, (void*)recvbuf, *count, (MPI_Datatype)(*datatype), (MPI_Op)(*op), *root, (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Reduce((const void*)sendbuf, (void*)recvbuf, *count, MPI_Type_f2c(*datatype), MPI_Op_f2c(*op), *root, MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    MpiCall const reduce = {
+        .kind = Reduce,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes * (*count),
+        .nb_bytes_r = 0,
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(MPI_Op_f2c(*op)),
+        .finished = false,
+    };
+
+    register_mpi_call(reduce);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_REDUCE(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Reduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_reduce(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Reduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_reduce_(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Reduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_reduce__(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Reduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, ierr);
+}
+
+
+static void MPI_Allreduce_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Allreduce((const void*)sendbuf, (void*)recvbuf, *count, (MPI_Datatype)(*datatype), (MPI_Op)(*op), (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Allreduce((const void*)sendbuf, (void*)recvbuf, *count, MPI_Type_f2c(*datatype), MPI_Op_f2c(*op), MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    MpiCall const allreduce = {
+        .kind = Allreduce,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * (*count),
+        .nb_bytes_r = 0,
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(MPI_Op_f2c(*op)),
+        .finished = false,
+    };
+
+    register_mpi_call(allreduce);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ALLREDUCE(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allreduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allreduce(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allreduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allreduce_(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allreduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allreduce__(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allreduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, ierr);
+}
+
+
+static void MPI_Gather_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Gather((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Gather((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
+
+    MpiCall const gather = {
+        .kind = Gather,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes_send * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv * (*recvcount),
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(gather);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_GATHER(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Gather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_gather(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Gather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_gather_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Gather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_gather__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Gather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+
+static void MPI_Scatter_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Scatter((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Scatter((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
+
+    MpiCall const scatter = {
+        .kind = Scatter,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes_send * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv * (*recvcount),
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(scatter);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_SCATTER(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Scatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_scatter(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Scatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_scatter_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Scatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_scatter__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Scatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+
+static void MPI_Allgather_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Allgather((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Allgather((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
+
+    MpiCall const allgather = {
+        .kind = Allgather,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv * (*recvcount),
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(allgather);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ALLGATHER(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allgather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allgather(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allgather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allgather_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allgather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allgather__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allgather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, ierr);
+}
+
+
+static void MPI_Alltoall_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Alltoall((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Alltoall((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
+
+    MpiCall const alltoall = {
+        .kind = Alltoall,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv * (*recvcount),
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(alltoall);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ALLTOALL(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Alltoall_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_alltoall(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Alltoall_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_alltoall_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Alltoall_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_alltoall__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Alltoall_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, ierr);
+}
+
+
+static void MPI_Ibcast_fortran_wrapper(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Ibcast((void*)buffer, *count, (MPI_Datatype)(*datatype), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Ibcast((void*)buffer, *count, MPI_Type_f2c(*datatype), *root, MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    MpiCall const ibcast = {
+        .kind = Ibcast,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes * (*count),
+        .nb_bytes_r = 0,
+        .comm = PMPI_Comm_c2f((MPI_Comm)comm),
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(ibcast);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IBCAST(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ibcast_fortran_wrapper(buffer, count, datatype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ibcast(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ibcast_fortran_wrapper(buffer, count, datatype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ibcast_(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ibcast_fortran_wrapper(buffer, count, datatype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ibcast__(MPI_Fint *buffer, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ibcast_fortran_wrapper(buffer, count, datatype, root, comm, request, ierr);
+}
+
+
+static void MPI_Igather_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Igather((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Igather((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
+
+    MpiCall const igather = {
+        .kind = Igather,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes_send * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv * (*recvcount),
+        .comm = PMPI_Comm_c2f((MPI_Comm)comm),
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(igather);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IGATHER(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Igather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_igather(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Igather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_igather_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Igather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_igather__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Igather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+
+static void MPI_Iscatter_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = MPI_Iscatter((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Iscatter((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
+
+    MpiCall const iscatter = {
+        .kind = Iscatter,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes_send * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv * (*recvcount),
+        .comm = PMPI_Comm_c2f((MPI_Comm)comm),
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(iscatter);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ISCATTER(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscatter(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscatter_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscatter__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscatter_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+
+static void MPI_Ireduce_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Ireduce((const void*)sendbuf, (void*)recvbuf, *count, (MPI_Datatype)(*datatype), (MPI_Op)(*op), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Ireduce((const void*)sendbuf, (void*)recvbuf, *count, MPI_Type_f2c(*datatype), MPI_Op_f2c(*op), *root, MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    MpiCall const ireduce = {
+        .kind = Ireduce,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes,
+        .nb_bytes_r = nb_bytes * (*count),
+        .comm = PMPI_Comm_c2f((MPI_Comm)comm),
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(MPI_Op_f2c(*op)),
+        .finished = false,
+    };
+
+    register_mpi_call(ireduce);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IREDUCE(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ireduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ireduce(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ireduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ireduce_(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ireduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ireduce__(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ireduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, root, comm, request, ierr);
+}
+
+static void MPI_Iallreduce_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Iallreduce((const void*)sendbuf, (void*)recvbuf, *count, (MPI_Datatype)(*datatype), (MPI_Op)(*op), (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Iallreduce((const void*)sendbuf, (void*)recvbuf, *count, MPI_Type_f2c(*datatype), MPI_Op_f2c(*op), MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    MpiCall const iallreduce = {
+        .kind = Iallreduce,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * (*count),
+        .nb_bytes_r = 0,
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(MPI_Op_f2c(*op)),
+        .finished = false,
+    };
+
+    register_mpi_call(iallreduce);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IALLREDUCE(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallreduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallreduce(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallreduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallreduce_(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallreduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallreduce__(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallreduce_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+
+static void MPI_Iscan_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Iscan((const void*)sendbuf, (void*)recvbuf, *count, (MPI_Datatype)(*datatype), (MPI_Op)(*op), (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Iscan((const void*)sendbuf, (void*)recvbuf, *count, MPI_Type_f2c(*datatype), MPI_Op_f2c(*op), MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    MpiCall const iscan = {
+        .kind = Iscan,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * (*count),
+        .nb_bytes_r = 0,
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(MPI_Op_f2c(*op)),
+        .finished = false,
+    };
+
+    register_mpi_call(iscan);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ISCAN(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscan_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscan(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscan_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscan_(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscan_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscan__(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscan_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+
+static void MPI_Iexscan_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Iexscan((const void*)sendbuf, (void*)recvbuf, *count, (MPI_Datatype)(*datatype), (MPI_Op)(*op), (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Iexscan((const void*)sendbuf, (void*)recvbuf, *count, MPI_Type_f2c(*datatype), MPI_Op_f2c(*op), MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    MpiCall const iexscan = {
+        .kind = Iexscan,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * (*count),
+        .nb_bytes_r = 0,
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(MPI_Op_f2c(*op)),
+        .finished = false,
+    };
+
+    register_mpi_call(iexscan);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IEXSCAN(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iexscan_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iexscan(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iexscan_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iexscan_(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iexscan_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iexscan__(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *count, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iexscan_fortran_wrapper(sendbuf, recvbuf, count, datatype, op, comm, request, ierr);
+}
+
+
+static void MPI_Iallgather_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Iallgather((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Iallgather((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
+
+    MpiCall const iallgather = {
+        .kind = Iallgather,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv * (*recvcount),
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(iallgather);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IALLGATHER(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallgather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallgather(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallgather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallgather_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallgather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallgather__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallgather_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, request, ierr);
+}
+
+
+static void MPI_Ialltoall_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Ialltoall((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Ialltoall((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes_send, nb_bytes_recv;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &nb_bytes_send);
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &nb_bytes_recv);
+
+    MpiCall const ialltoall = {
+        .kind = Ialltoall,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv * (*recvcount),
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+
+    register_mpi_call(ialltoall);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IALLTOALL(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ialltoall_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ialltoall(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ialltoall_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ialltoall_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ialltoall_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ialltoall__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ialltoall_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcount, recvtype, comm, request, ierr);
+}
+
+
+static void MPI_Ireduce_scatter_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Ireduce_scatter((const void*)sendbuf, (void*)recvbuf, (const int*)recvcounts, (MPI_Datatype)(*datatype), (MPI_Op)(*op), (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Ireduce_scatter((const void*)sendbuf, (void*)recvbuf, (const int*)recvcounts, MPI_Type_f2c(*datatype), MPI_Op_f2c(*op), MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int nb_bytes;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &nb_bytes);
+
+    // Every rank contributes the elements scattered to all the ranks
+    int comm_size, comm_rank;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+    PMPI_Comm_rank(MPI_Comm_f2c(*comm), &comm_rank);
+    int count = 0;
+    for (int i = 0; i < comm_size; ++i) {
+        count += recvcounts[i];
+    }
+
+    MpiCall const ireduce_scatter = {
+        .kind = IreduceScatter,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes * count,
+        .nb_bytes_r = nb_bytes * recvcounts[comm_rank],
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = op_type(MPI_Op_f2c(*op)),
+        .finished = false,
+    };
+
+    register_mpi_call(ireduce_scatter);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IREDUCE_SCATTER(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ireduce_scatter_fortran_wrapper(sendbuf, recvbuf, recvcounts, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ireduce_scatter(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ireduce_scatter_fortran_wrapper(sendbuf, recvbuf, recvcounts, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ireduce_scatter_(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ireduce_scatter_fortran_wrapper(sendbuf, recvbuf, recvcounts, datatype, op, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ireduce_scatter__(MPI_Fint *sendbuf, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *datatype, MPI_Fint *op, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ireduce_scatter_fortran_wrapper(sendbuf, recvbuf, recvcounts, datatype, op, comm, request, ierr);
+}
+
+
+/// Returns the number of bytes exchanged with each of the `size` ranks of a
+/// communicator, given the number of elements of the Fortran type `datatype`
+/// exchanged with each of them, and adds them up in `total`. The array must be
+/// freed by the caller.
+static uint32_t* peer_bytes(int size, MPI_Fint const* counts, MPI_Fint* datatype,
+                            uint32_t* total)
+{
+    int type_size;
+    PMPI_Type_size(MPI_Type_f2c(*datatype), &type_size);
+
+    uint32_t* bytes = malloc((size > 0 ? size : 1) * sizeof *bytes);
+    *total = 0;
+    for (int i = 0; i < size; ++i) {
+        bytes[i] = counts[i] * type_size;
+        *total += bytes[i];
+    }
+    return bytes;
+}
+
+static void MPI_Gatherv_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Gatherv((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)displs, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Gatherv((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)displs, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size, comm_rank;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+    PMPI_Comm_rank(MPI_Comm_f2c(*comm), &comm_rank);
+
+    int send_size;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &send_size);
+
+    // The counts of received elements are only significant on the root
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv = comm_rank == *root
+        ? peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv)
+        : NULL;
+
+    MpiCall const gatherv = {
+        .kind = Gatherv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = send_size * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = bytes_recv != NULL ? comm_size : 0,
+    };
+
+    register_mpi_call_arrays(gatherv, arrays);
+    free(bytes_recv);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_GATHERV(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Gatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_gatherv(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Gatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_gatherv_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Gatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_gatherv__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Gatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, root, comm, ierr);
+}
+
+
+static void MPI_Scatterv_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Scatterv((const void*)sendbuf, (const int*)sendcounts, (const int*)displs, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Scatterv((const void*)sendbuf, (const int*)sendcounts, (const int*)displs, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size, comm_rank;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+    PMPI_Comm_rank(MPI_Comm_f2c(*comm), &comm_rank);
+
+    // The counts of sent elements are only significant on the root
+    uint32_t nb_bytes_send = 0;
+    uint32_t* bytes_send = comm_rank == *root
+        ? peer_bytes(comm_size, sendcounts, sendtype, &nb_bytes_send)
+        : NULL;
+
+    int recv_size;
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &recv_size);
+
+    MpiCall const scatterv = {
+        .kind = Scatterv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes_send,
+        .nb_bytes_r = recv_size * (*recvcount),
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = bytes_send,
+        .nb_peers_s = bytes_send != NULL ? comm_size : 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
+
+    register_mpi_call_arrays(scatterv, arrays);
+    free(bytes_send);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_SCATTERV(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Scatterv_fortran_wrapper(sendbuf, sendcounts, displs, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_scatterv(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Scatterv_fortran_wrapper(sendbuf, sendcounts, displs, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_scatterv_(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Scatterv_fortran_wrapper(sendbuf, sendcounts, displs, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_scatterv__(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Scatterv_fortran_wrapper(sendbuf, sendcounts, displs, sendtype, recvbuf, recvcount, recvtype, root, comm, ierr);
+}
+
+
+static void MPI_Allgatherv_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Allgatherv((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)displs, (MPI_Datatype)(*recvtype), (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Allgatherv((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)displs, MPI_Type_f2c(*recvtype), MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+
+    int send_size;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &send_size);
+
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv =
+        peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv);
+
+    MpiCall const allgatherv = {
+        .kind = Allgatherv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = send_size * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = comm_size,
+    };
+
+    register_mpi_call_arrays(allgatherv, arrays);
+    free(bytes_recv);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ALLGATHERV(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allgatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allgatherv(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allgatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allgatherv_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allgatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_allgatherv__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Allgatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, comm, ierr);
+}
+
+
+static void MPI_Alltoallv_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Alltoallv((const void*)sendbuf, (const int*)sendcounts, (const int*)sdispls, (MPI_Datatype)(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)rdispls, (MPI_Datatype)(*recvtype), (MPI_Comm)(*comm));
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Alltoallv((const void*)sendbuf, (const int*)sendcounts, (const int*)sdispls, MPI_Type_f2c(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)rdispls, MPI_Type_f2c(*recvtype), MPI_Comm_f2c(*comm));
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+
+    uint32_t nb_bytes_send = 0;
+    uint32_t* bytes_send =
+        peer_bytes(comm_size, sendcounts, sendtype, &nb_bytes_send);
+
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv =
+        peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv);
+
+    MpiCall const alltoallv = {
+        .kind = Alltoallv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send,
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = *comm,
+        .req = -1,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = bytes_send,
+        .nb_peers_s = comm_size,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = comm_size,
+    };
+
+    register_mpi_call_arrays(alltoallv, arrays);
+    free(bytes_send);
+    free(bytes_recv);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ALLTOALLV(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Alltoallv_fortran_wrapper(sendbuf, sendcounts, sdispls, sendtype, recvbuf, recvcounts, rdispls, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_alltoallv(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Alltoallv_fortran_wrapper(sendbuf, sendcounts, sdispls, sendtype, recvbuf, recvcounts, rdispls, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_alltoallv_(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Alltoallv_fortran_wrapper(sendbuf, sendcounts, sdispls, sendtype, recvbuf, recvcounts, rdispls, recvtype, comm, ierr);
+}
+
+_EXTERN_C_ void mpi_alltoallv__(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *ierr) { 
+    MPI_Alltoallv_fortran_wrapper(sendbuf, sendcounts, sdispls, sendtype, recvbuf, recvcounts, rdispls, recvtype, comm, ierr);
+}
+
+
+static void MPI_Igatherv_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Igatherv((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)displs, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Igatherv((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)displs, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size, comm_rank;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+    PMPI_Comm_rank(MPI_Comm_f2c(*comm), &comm_rank);
+
+    int send_size;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &send_size);
+
+    // The counts of received elements are only significant on the root
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv = comm_rank == *root
+        ? peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv)
+        : NULL;
+
+    MpiCall const igatherv = {
+        .kind = Igatherv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = send_size * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = bytes_recv != NULL ? comm_size : 0,
+    };
+
+    register_mpi_call_arrays(igatherv, arrays);
+    free(bytes_recv);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IGATHERV(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Igatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_igatherv(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Igatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_igatherv_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Igatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_igatherv__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Igatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, root, comm, request, ierr);
+}
+
+
+static void MPI_Iscatterv_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Iscatterv((const void*)sendbuf, (const int*)sendcounts, (const int*)displs, (MPI_Datatype)(*sendtype), (void*)recvbuf, *recvcount, (MPI_Datatype)(*recvtype), *root, (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Iscatterv((const void*)sendbuf, (const int*)sendcounts, (const int*)displs, MPI_Type_f2c(*sendtype), (void*)recvbuf, *recvcount, MPI_Type_f2c(*recvtype), *root, MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size, comm_rank;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+    PMPI_Comm_rank(MPI_Comm_f2c(*comm), &comm_rank);
+
+    // The counts of sent elements are only significant on the root
+    uint32_t nb_bytes_send = 0;
+    uint32_t* bytes_send = comm_rank == *root
+        ? peer_bytes(comm_size, sendcounts, sendtype, &nb_bytes_send)
+        : NULL;
+
+    int recv_size;
+    PMPI_Type_size(MPI_Type_f2c(*recvtype), &recv_size);
+
+    MpiCall const iscatterv = {
+        .kind = Iscatterv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = *root,
+        .nb_bytes_s = nb_bytes_send,
+        .nb_bytes_r = recv_size * (*recvcount),
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = bytes_send,
+        .nb_peers_s = bytes_send != NULL ? comm_size : 0,
+        .peer_bytes_r = NULL,
+        .nb_peers_r = 0,
+    };
+
+    register_mpi_call_arrays(iscatterv, arrays);
+    free(bytes_send);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ISCATTERV(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscatterv_fortran_wrapper(sendbuf, sendcounts, displs, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscatterv(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscatterv_fortran_wrapper(sendbuf, sendcounts, displs, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscatterv_(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscatterv_fortran_wrapper(sendbuf, sendcounts, displs, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iscatterv__(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *displs, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcount, MPI_Fint *recvtype, MPI_Fint *root, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iscatterv_fortran_wrapper(sendbuf, sendcounts, displs, sendtype, recvbuf, recvcount, recvtype, root, comm, request, ierr);
+}
+
+
+static void MPI_Iallgatherv_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Iallgatherv((const void*)sendbuf, *sendcount, (MPI_Datatype)(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)displs, (MPI_Datatype)(*recvtype), (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Iallgatherv((const void*)sendbuf, *sendcount, MPI_Type_f2c(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)displs, MPI_Type_f2c(*recvtype), MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+
+    int send_size;
+    PMPI_Type_size(MPI_Type_f2c(*sendtype), &send_size);
+
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv =
+        peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv);
+
+    MpiCall const iallgatherv = {
+        .kind = Iallgatherv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = send_size * (*sendcount),
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = NULL,
+        .nb_peers_s = 0,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = comm_size,
+    };
+
+    register_mpi_call_arrays(iallgatherv, arrays);
+    free(bytes_recv);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IALLGATHERV(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallgatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallgatherv(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallgatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallgatherv_(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallgatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_iallgatherv__(MPI_Fint *sendbuf, MPI_Fint *sendcount, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *displs, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Iallgatherv_fortran_wrapper(sendbuf, sendcount, sendtype, recvbuf, recvcounts, displs, recvtype, comm, request, ierr);
+}
+
+
+static void MPI_Ialltoallv_fortran_wrapper(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    Tsc const tsc = rdtsc();
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Ialltoallv((const void*)sendbuf, (const int*)sendcounts, (const int*)sdispls, (MPI_Datatype)(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)rdispls, (MPI_Datatype)(*recvtype), (MPI_Comm)(*comm), (MPI_Request*)request);
+    #else /* MPI-2 safe call */
+        MPI_Request temp_request;
+        temp_request = MPI_Request_f2c(*request);
+        _wrap_py_return_val = PMPI_Ialltoallv((const void*)sendbuf, (const int*)sendcounts, (const int*)sdispls, MPI_Type_f2c(*sendtype), (void*)recvbuf, (const int*)recvcounts, (const int*)rdispls, MPI_Type_f2c(*recvtype), MPI_Comm_f2c(*comm), &temp_request);
+        *request = MPI_Request_c2f(temp_request);
+    #endif /* MPICH test */
+
+    Tsc const duration = rdtsc() - tsc;
+
+    int comm_size;
+    PMPI_Comm_size(MPI_Comm_f2c(*comm), &comm_size);
+
+    uint32_t nb_bytes_send = 0;
+    uint32_t* bytes_send =
+        peer_bytes(comm_size, sendcounts, sendtype, &nb_bytes_send);
+
+    uint32_t nb_bytes_recv = 0;
+    uint32_t* bytes_recv =
+        peer_bytes(comm_size, recvcounts, recvtype, &nb_bytes_recv);
+
+    MpiCall const ialltoallv = {
+        .kind = Ialltoallv,
+        .time = -1.0,
+        .tsc = tsc,
+        .duration = duration,
+        .current_rank = current_rank,
+        .partner_rank = -1,
+        .nb_bytes_s = nb_bytes_send,
+        .nb_bytes_r = nb_bytes_recv,
+        .comm = *comm,
+        .req = *request,
+        .tag = -1,
+        .required_thread_lvl = -1,
+        .provided_thread_lvl = -1,
+        .op_type = -1,
+        .finished = false,
+    };
+    MpiCallArrays const arrays = {
+        .reqs = NULL,
+        .nb_reqs = 0,
+        .indices = NULL,
+        .nb_indices = 0,
+        .peer_bytes_s = bytes_send,
+        .nb_peers_s = comm_size,
+        .peer_bytes_r = bytes_recv,
+        .nb_peers_r = comm_size,
+    };
+
+    register_mpi_call_arrays(ialltoallv, arrays);
+    free(bytes_send);
+    free(bytes_recv);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_IALLTOALLV(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ialltoallv_fortran_wrapper(sendbuf, sendcounts, sdispls, sendtype, recvbuf, recvcounts, rdispls, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ialltoallv(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ialltoallv_fortran_wrapper(sendbuf, sendcounts, sdispls, sendtype, recvbuf, recvcounts, rdispls, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ialltoallv_(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ialltoallv_fortran_wrapper(sendbuf, sendcounts, sdispls, sendtype, recvbuf, recvcounts, rdispls, recvtype, comm, request, ierr);
+}
+
+_EXTERN_C_ void mpi_ialltoallv__(MPI_Fint *sendbuf, MPI_Fint *sendcounts, MPI_Fint *sdispls, MPI_Fint *sendtype, MPI_Fint *recvbuf, MPI_Fint *recvcounts, MPI_Fint *rdispls, MPI_Fint *recvtype, MPI_Fint *comm, MPI_Fint *request, MPI_Fint *ierr) { 
+    MPI_Ialltoallv_fortran_wrapper(sendbuf, sendcounts, sdispls, sendtype, recvbuf, recvcounts, rdispls, recvtype, comm, request, ierr);
+}
+
+
+static void MPI_Abort_fortran_wrapper(MPI_Fint *comm, MPI_Fint *errorcode, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    // Write the events of this rank before the MPI library terminates the job
+    register_abort(*errorcode);
+
+    #if (!defined(MPICH_HAS_C2F) && defined(MPICH_NAME) && (MPICH_NAME == 1)) /* MPICH test */
+        _wrap_py_return_val = PMPI_Abort((MPI_Comm)(*comm), *errorcode);
+    #else /* MPI-2 safe call */
+        _wrap_py_return_val = PMPI_Abort(MPI_Comm_f2c(*comm), *errorcode);
+    #endif /* MPICH test */
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_ABORT(MPI_Fint *comm, MPI_Fint *errorcode, MPI_Fint *ierr) { 
+    MPI_Abort_fortran_wrapper(comm, errorcode, ierr);
+}
+
+_EXTERN_C_ void mpi_abort(MPI_Fint *comm, MPI_Fint *errorcode, MPI_Fint *ierr) { 
+    MPI_Abort_fortran_wrapper(comm, errorcode, ierr);
+}
+
+_EXTERN_C_ void mpi_abort_(MPI_Fint *comm, MPI_Fint *errorcode, MPI_Fint *ierr) { 
+    MPI_Abort_fortran_wrapper(comm, errorcode, ierr);
+}
+
+_EXTERN_C_ void mpi_abort__(MPI_Fint *comm, MPI_Fint *errorcode, MPI_Fint *ierr) { 
+    MPI_Abort_fortran_wrapper(comm, errorcode, ierr);
+}
+
+static void MPI_Pcontrol_fortran_wrapper(MPI_Fint *level, MPI_Fint *ierr) { 
+    int _wrap_py_return_val = 0;
+
+    // Level 0 disables tracing, any other level enables it
+    if (*level == 0) {
+        interpol_pause();
+    } else {
+        interpol_resume();
+    }
+
+    _wrap_py_return_val = PMPI_Pcontrol(*level);
+
+    *ierr = _wrap_py_return_val;
+}
+
+_EXTERN_C_ void MPI_PCONTROL(MPI_Fint *level, MPI_Fint *ierr) { 
+    MPI_Pcontrol_fortran_wrapper(level, ierr);
+}
+
+_EXTERN_C_ void mpi_pcontrol(MPI_Fint *level, MPI_Fint *ierr) { 
+    MPI_Pcontrol_fortran_wrapper(level, ierr);
+}
+
+_EXTERN_C_ void mpi_pcontrol_(MPI_Fint *level, MPI_Fint *ierr) { 
+    MPI_Pcontrol_fortran_wrapper(level, ierr);
+}
+
+_EXTERN_C_ void mpi_pcontrol__(MPI_Fint *level, MPI_Fint *ierr) { 
+    MPI_Pcontrol_fortran_wrapper(level, ierr);
+}
+
+/* Fortran entry points of `interpol_pause`/`interpol_resume`. The lowercase,
+   non-mangled names are the Rust functions themselves.  */
+_EXTERN_C_ void INTERPOL_PAUSE() { 
+    interpol_pause();
+}
+
+_EXTERN_C_ void interpol_pause_() { 
+    interpol_pause();
+}
+
+_EXTERN_C_ void interpol_pause__() { 
+    interpol_pause();
+}
+
+_EXTERN_C_ void INTERPOL_RESUME() { 
+    interpol_resume();
+}
+
+_EXTERN_C_ void interpol_resume_() { 
+    interpol_resume();
+}
+
+_EXTERN_C_ void interpol_resume__() { 
+    interpol_resume();
+}
+
+/* Calls `region` with a null-terminated copy of a Fortran string, whose length
+   is passed as a hidden argument and which is padded with trailing blanks.  */
+static void call_with_fortran_string(void (*region)(const char *), const char *name, size_t len) {
+    while (len > 0 && name[len - 1] == ' ') {
+        len--;
+    }
+
+    char *c_name = malloc(len + 1);
+    if (c_name == NULL) {
+        return;
+    }
+    memcpy(c_name, name, len);
+    c_name[len] = '\0';
+
+    region(c_name);
+    free(c_name);
+}
+
+/* Fortran entry points of `interpol_region_begin`/`interpol_region_end`, e.g.
+   `call interpol_region_begin("solver")`. The lowercase, non-mangled names are
+   the Rust functions themselves, which expect null-terminated strings.  */
+_EXTERN_C_ void INTERPOL_REGION_BEGIN(const char *name, size_t len) { 
+    call_with_fortran_string(interpol_region_begin, name, len);
+}
+
+_EXTERN_C_ void interpol_region_begin_(const char *name, size_t len) { 
+    call_with_fortran_string(interpol_region_begin, name, len);
+}
+
+_EXTERN_C_ void interpol_region_begin__(const char *name, size_t len) { 
+    call_with_fortran_string(interpol_region_begin, name, len);
+}
+
+_EXTERN_C_ void INTERPOL_REGION_END(const char *name, size_t len) { 
+    call_with_fortran_string(interpol_region_end, name, len);
+}
+
+_EXTERN_C_ void interpol_region_end_(const char *name, size_t len) { 
+    call_with_fortran_string(interpol_region_end, name, len);
+}
+
+_EXTERN_C_ void interpol_region_end__(const char *name, size_t len) { 
+    call_with_fortran_string(interpol_region_end, name, len);
+}